@@ -0,0 +1,142 @@
+use bincode::config;
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::VecDeque;
+
+const LEN: usize = 1024 * 1024;
+
+/// A byte that is encoded the same way as `u8`, but one at a time. This is used as a baseline for the byte slice fast path.
+#[derive(Clone, Copy)]
+struct Byte(u8);
+
+impl bincode::Encode for Byte {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl bincode::Decode for Byte {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        u8::decode(decoder).map(Byte)
+    }
+}
+
+fn input() -> Vec<u8> {
+    (0..LEN).map(|i| i as u8).collect()
+}
+
+fn encode_bytes(c: &mut Criterion) {
+    let config = config::standard();
+    let bytes = input();
+    let bytes_per_element: Vec<Byte> = bytes.iter().copied().map(Byte).collect();
+    let deque: VecDeque<u8> = bytes.iter().copied().collect();
+    let mut buffer = vec![0u8; LEN + 16];
+
+    c.bench_function("encode_vec_u8", |b| {
+        b.iter(|| bincode::encode_into_slice(black_box(&bytes), &mut buffer, config).unwrap())
+    });
+    c.bench_function("encode_vec_u8_per_element", |b| {
+        b.iter(|| {
+            bincode::encode_into_slice(black_box(&bytes_per_element), &mut buffer, config).unwrap()
+        })
+    });
+    c.bench_function("encode_vec_deque_u8", |b| {
+        b.iter(|| bincode::encode_into_slice(black_box(&deque), &mut buffer, config).unwrap())
+    });
+    c.bench_function("encode_array_u8", |b| {
+        let array = [5u8; 4096];
+        b.iter(|| bincode::encode_into_slice(black_box(&array), &mut buffer, config).unwrap())
+    });
+}
+
+fn decode_bytes(c: &mut Criterion) {
+    let config = config::standard();
+    let encoded = bincode::encode_to_vec(input(), config).unwrap();
+
+    c.bench_function("decode_vec_u8", |b| {
+        b.iter(|| {
+            let _: (Vec<u8>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded), config).unwrap());
+        })
+    });
+    c.bench_function("decode_vec_u8_per_element", |b| {
+        b.iter(|| {
+            let _: (Vec<Byte>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded), config).unwrap());
+        })
+    });
+    c.bench_function("decode_boxed_slice_u8", |b| {
+        b.iter(|| {
+            let _: (Box<[u8]>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded), config).unwrap());
+        })
+    });
+    c.bench_function("decode_vec_deque_u8", |b| {
+        b.iter(|| {
+            let _: (VecDeque<u8>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded), config).unwrap());
+        })
+    });
+    c.bench_function("bufreader_decode_vec_u8", |b| {
+        b.iter(|| {
+            let _: Vec<u8> = black_box(
+                bincode::decode_from_reader(
+                    &mut std::io::BufReader::new(black_box(&encoded[..])),
+                    config,
+                )
+                .unwrap(),
+            );
+        })
+    });
+}
+
+fn numeric_slices(c: &mut Criterion) {
+    let config = config::legacy();
+    let ints: Vec<u32> = (0..LEN as u32).collect();
+    let encoded = bincode::encode_to_vec(&ints, config).unwrap();
+    let mut buffer = vec![0u8; encoded.len()];
+
+    c.bench_function("encode_vec_u32_fixint", |b| {
+        b.iter(|| bincode::encode_into_slice(black_box(&ints), &mut buffer, config).unwrap())
+    });
+    c.bench_function("decode_vec_u32_fixint", |b| {
+        b.iter(|| {
+            let _: (Vec<u32>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded), config).unwrap());
+        })
+    });
+}
+
+fn varint_slices(c: &mut Criterion) {
+    let config = config::standard();
+    let small: Vec<u64> = (0..LEN as u64).map(|i| i % 200).collect();
+    let encoded_small = bincode::encode_to_vec(&small, config).unwrap();
+    let mixed: Vec<u64> = (0..LEN as u64)
+        .map(|i| if i % 64 == 0 { i * i } else { i % 200 })
+        .collect();
+    let encoded_mixed = bincode::encode_to_vec(&mixed, config).unwrap();
+
+    c.bench_function("decode_vec_u64_varint_small", |b| {
+        b.iter(|| {
+            let _: (Vec<u64>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded_small), config).unwrap());
+        })
+    });
+    c.bench_function("decode_vec_u64_varint_mixed", |b| {
+        b.iter(|| {
+            let _: (Vec<u64>, usize) =
+                black_box(bincode::decode_from_slice(black_box(&encoded_mixed), config).unwrap());
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    encode_bytes,
+    decode_bytes,
+    numeric_slices,
+    varint_slices
+);
+criterion_main!(benches);
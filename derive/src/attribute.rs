@@ -7,6 +7,7 @@ pub struct ContainerAttributes {
     pub decode_bounds: Option<(String, Literal)>,
     pub borrow_decode_bounds: Option<(String, Literal)>,
     pub encode_bounds: Option<(String, Literal)>,
+    pub tag_type: Option<(String, Span)>,
 }
 
 impl Default for ContainerAttributes {
@@ -17,10 +18,29 @@ impl Default for ContainerAttributes {
             decode_bounds: None,
             encode_bounds: None,
             borrow_decode_bounds: None,
+            tag_type: None,
         }
     }
 }
 
+/// The integer types that can be used as the tag of an enum
+const TAG_TYPES: &[&str] = &["u8", "u16", "u32"];
+
+impl ContainerAttributes {
+    /// Parse the `#[bincode(...)]` attribute of a container. If no `tag_type` is given, the tag type is taken from a `#[repr(u8)]`, `#[repr(u16)]` or `#[repr(u32)]` attribute instead.
+    pub fn from_attributes(attributes: &impl AttributeAccess) -> Result<Self> {
+        let mut result = attributes
+            .get_attribute::<ContainerAttributes>()?
+            .unwrap_or_default();
+        if result.tag_type.is_none() {
+            result.tag_type = attributes
+                .get_attribute::<ReprAttribute>()?
+                .map(|repr| repr.tag_type);
+        }
+        Ok(result)
+    }
+}
+
 impl FromAttribute for ContainerAttributes {
     fn parse(group: &Group) -> Result<Option<Self>> {
         let attributes = match parse_tagged_attribute(group, "bincode")? {
@@ -76,6 +96,21 @@ impl FromAttribute for ContainerAttributes {
                         return Err(Error::custom_at("Should be a literal str", val.span()));
                     }
                 }
+                ParsedAttribute::Property(key, val) if key.to_string() == "tag_type" => {
+                    let val_string = val.to_string();
+                    if val_string.starts_with('"') && val_string.ends_with('"') {
+                        let tag_type = &val_string[1..val_string.len() - 1];
+                        if !TAG_TYPES.contains(&tag_type) {
+                            return Err(Error::custom_at(
+                                "Should be one of \"u8\", \"u16\" or \"u32\"",
+                                val.span(),
+                            ));
+                        }
+                        result.tag_type = Some((tag_type.to_string(), val.span()));
+                    } else {
+                        return Err(Error::custom_at("Should be a literal str", val.span()));
+                    }
+                }
                 ParsedAttribute::Tag(i) => {
                     return Err(Error::custom_at("Unknown field attribute", i.span()))
                 }
@@ -89,9 +124,39 @@ impl FromAttribute for ContainerAttributes {
     }
 }
 
+/// The integer type of a `#[repr(...)]` attribute, if it is one of the supported tag types.
+struct ReprAttribute {
+    tag_type: (String, Span),
+}
+
+impl FromAttribute for ReprAttribute {
+    fn parse(group: &Group) -> Result<Option<Self>> {
+        let mut stream = group.stream().into_iter();
+        match stream.next() {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "repr" => {}
+            _ => return Ok(None),
+        }
+        // `#[repr(...)]` can contain multiple items, e.g. `#[repr(C, u8)]`
+        if let Some(TokenTree::Group(group)) = stream.next() {
+            for token in group.stream() {
+                if let TokenTree::Ident(ident) = token {
+                    let ident_string = ident.to_string();
+                    if TAG_TYPES.contains(&ident_string.as_str()) {
+                        return Ok(Some(Self {
+                            tag_type: (ident_string, ident.span()),
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
 #[derive(Default)]
 pub struct FieldAttributes {
     pub with_serde: bool,
+    pub max_len: Option<usize>,
 }
 
 impl FromAttribute for FieldAttributes {
@@ -106,6 +171,17 @@ impl FromAttribute for FieldAttributes {
                 ParsedAttribute::Tag(i) if i.to_string() == "with_serde" => {
                     result.with_serde = true;
                 }
+                ParsedAttribute::Property(key, val) if key.to_string() == "max_len" => {
+                    match val.to_string().replace('_', "").parse() {
+                        Ok(max_len) => result.max_len = Some(max_len),
+                        Err(_) => {
+                            return Err(Error::custom_at(
+                                "Should be an integer literal",
+                                val.span(),
+                            ))
+                        }
+                    }
+                }
                 ParsedAttribute::Tag(i) => {
                     return Err(Error::custom_at("Unknown field attribute", i.span()))
                 }
@@ -115,6 +191,12 @@ impl FromAttribute for FieldAttributes {
                 _ => {}
             }
         }
+        if result.with_serde && result.max_len.is_some() {
+            return Err(Error::custom_at(
+                "`max_len` can not be combined with `with_serde`",
+                group.span(),
+            ));
+        }
         Ok(Some(result))
     }
 }
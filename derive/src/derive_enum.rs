@@ -1,4 +1,5 @@
 use crate::attribute::{ContainerAttributes, FieldAttributes};
+use crate::derive_max_size::{fields_max_size, MaxSizeGenerics, CONFIG_GENERIC};
 use virtue::prelude::*;
 
 const TUPLE_FIELD_PREFIX: &str = "field_";
@@ -9,11 +10,58 @@ pub(crate) struct DeriveEnum {
 }
 
 impl DeriveEnum {
-    fn iter_fields(&self) -> EnumVariantIterator {
-        EnumVariantIterator {
+    fn iter_fields(&self) -> Result<EnumVariantIterator> {
+        Ok(EnumVariantIterator {
             idx: 0,
             variants: &self.variants,
+            tags: self.tags()?,
+            tag_type: self.tag_type(),
+        })
+    }
+
+    /// The integer type of the tag. This is `u32` unless a tag type is given.
+    fn tag_type(&self) -> &str {
+        match &self.attributes.tag_type {
+            Some((tag_type, _)) => tag_type,
+            None => "u32",
+        }
+    }
+
+    /// The tag of every variant.
+    ///
+    /// Without a tag type, this is the index of the variant.
+    /// With a tag type, this is the discriminant of the variant, which has to fit in the tag type.
+    fn tags(&self) -> Result<Vec<u32>> {
+        let tag_type = match &self.attributes.tag_type {
+            Some((tag_type, _)) => tag_type,
+            None => return Ok((0..self.variants.len() as u32).collect()),
+        };
+        let max = match tag_type.as_str() {
+            "u8" => u8::MAX as i128,
+            "u16" => u16::MAX as i128,
+            _ => u32::MAX as i128,
+        };
+
+        let mut tags = Vec::with_capacity(self.variants.len());
+        let mut next_value = 0;
+        for variant in &self.variants {
+            let value = match &variant.fields {
+                Fields::Integer(lit) => parse_integer_literal(lit)?,
+                _ => next_value,
+            };
+            if !(0..=max).contains(&value) {
+                return Err(Error::custom_at(
+                    format!(
+                        "Discriminant {} of variant {} does not fit in tag type {}",
+                        value, variant.name, tag_type
+                    ),
+                    variant.name.span(),
+                ));
+            }
+            tags.push(value as u32);
+            next_value = value + 1;
         }
+        Ok(tags)
     }
 
     pub fn generate_encode(self, generator: &mut Generator) -> Result<()> {
@@ -52,7 +100,7 @@ impl DeriveEnum {
                     if self.variants.is_empty() {
                         self.encode_empty_enum_case(match_body)?;
                     }
-                    for (variant_index, variant) in self.iter_fields() {
+                    for (variant_index, variant) in self.iter_fields()? {
                         // Self::Variant
                         match_body.ident_str("Self");
                         match_body.puncts("::");
@@ -83,24 +131,48 @@ impl DeriveEnum {
                         // Body of this variant
                         // Note that the fields are available as locals because of the match destructuring above
                         // {
-                        //      encoder.encode_u32(n)?;
+                        //      bincode::enc::encode_variant_index(encoder, n)?;
+                        //      // or, with a tag type:
+                        //      <u8 as bincode::Encode>::encode(&n, encoder)?;
                         //      bincode::Encode::encode(a, encoder)?;
                         //      bincode::Encode::encode(b, encoder)?;
                         //      bincode::Encode::encode(c, encoder)?;
                         // }
                         match_body.group(Delimiter::Brace, |body| {
                             // variant index
-                            body.push_parsed(format!("<u32 as {}::Encode>::encode", crate_name))?;
-                            body.group(Delimiter::Parenthesis, |args| {
-                                args.punct('&');
-                                args.group(Delimiter::Parenthesis, |num| {
-                                    num.extend(variant_index);
+                            if self.attributes.tag_type.is_some() {
+                                body.push_parsed(format!(
+                                    "{}::enc::encode_fixed_tag",
+                                    crate_name
+                                ))?;
+                                body.group(Delimiter::Parenthesis, |args| {
+                                    args.push_parsed("encoder, u32::from")?;
+                                    args.group(Delimiter::Parenthesis, |num| {
+                                        num.extend(variant_index);
+                                        Ok(())
+                                    })?;
+                                    args.punct(',');
+                                    args.push_parsed(format!(
+                                        "core::mem::size_of::<{}>()",
+                                        self.tag_type()
+                                    ))?;
                                     Ok(())
                                 })?;
-                                args.punct(',');
-                                args.push_parsed("encoder")?;
-                                Ok(())
-                            })?;
+                            } else {
+                                body.push_parsed(format!(
+                                    "{}::enc::encode_variant_index",
+                                    crate_name
+                                ))?;
+                                body.group(Delimiter::Parenthesis, |args| {
+                                    args.push_parsed("encoder")?;
+                                    args.punct(',');
+                                    args.group(Delimiter::Parenthesis, |num| {
+                                        num.extend(variant_index);
+                                        Ok(())
+                                    })?;
+                                    Ok(())
+                                })?;
+                            }
                             body.punct('?');
                             body.punct(';');
                             // If we have any fields, encode them all one by one
@@ -115,6 +187,13 @@ impl DeriveEnum {
                                         crate_name,
                                         field_name.to_string_with_prefix(TUPLE_FIELD_PREFIX),
                                     ))?;
+                                } else if let Some(max_len) = attributes.max_len {
+                                    body.push_parsed(format!(
+                                        "{0}::enc::EncodeMaxLen::encode_max_len({1}, encoder, {2})?;",
+                                        crate_name,
+                                        field_name.to_string_with_prefix(TUPLE_FIELD_PREFIX),
+                                        max_len,
+                                    ))?;
                                 } else {
                                     body.push_parsed(format!(
                                         "{0}::Encode::encode({1}, encoder)?;",
@@ -148,7 +227,7 @@ impl DeriveEnum {
         // we'll be generating:
         // variant => Err(
         //    bincode::error::DecodeError::UnexpectedVariant {
-        //        found: variant,
+        //        found: u32::from(variant),
         //        type_name: <enum_name>
         //        allowed: ...,
         //    }
@@ -170,7 +249,7 @@ impl DeriveEnum {
             err_inner.group(Delimiter::Brace, |variant_inner| {
                 variant_inner.ident_str("found");
                 variant_inner.punct(':');
-                variant_inner.ident_str("variant");
+                variant_inner.push_parsed("u32::from(variant)")?;
                 variant_inner.punct(',');
 
                 variant_inner.ident_str("type_name");
@@ -181,6 +260,7 @@ impl DeriveEnum {
                 variant_inner.ident_str("allowed");
                 variant_inner.punct(':');
 
+                let tags = self.tags()?;
                 if self.variants.iter().any(|i| i.has_fixed_value()) {
                     // we have fixed values, implement AllowedEnumVariants::Allowed
                     variant_inner.push_parsed(format!(
@@ -190,11 +270,11 @@ impl DeriveEnum {
                     variant_inner.group(Delimiter::Parenthesis, |allowed_inner| {
                         allowed_inner.punct('&');
                         allowed_inner.group(Delimiter::Bracket, |allowed_slice| {
-                            for (idx, (ident, _)) in self.iter_fields().enumerate() {
+                            for (idx, tag) in tags.iter().enumerate() {
                                 if idx != 0 {
                                     allowed_slice.punct(',');
                                 }
-                                allowed_slice.extend(ident);
+                                allowed_slice.push(TokenTree::Literal(Literal::u32_suffixed(*tag)));
                             }
                             Ok(())
                         })?;
@@ -205,7 +285,7 @@ impl DeriveEnum {
                     variant_inner.push_parsed(format!(
                         "&{0}::error::AllowedEnumVariants::Range {{ min: 0, max: {1} }}",
                         crate_name,
-                        self.variants.len() - 1
+                        tags.len() - 1
                     ))?;
                 }
                 Ok(())
@@ -215,6 +295,25 @@ impl DeriveEnum {
         Ok(())
     }
 
+    /// Decode the tag of the variant into `variant_index`
+    fn decode_variant_index(&self, fn_builder: &mut StreamBuilder) -> Result {
+        let crate_name = self.attributes.crate_name.as_str();
+        if self.attributes.tag_type.is_some() {
+            // The tag is at most `size_of::<tag_type>()` bytes, so it always fits in the tag type
+            fn_builder.push_parsed(format!(
+                "let variant_index = {1}::de::decode_fixed_tag(decoder, core::mem::size_of::<{0}>())? as {0};",
+                self.tag_type(),
+                crate_name
+            ))?;
+        } else {
+            fn_builder.push_parsed(format!(
+                "let variant_index = {}::de::decode_variant_index(decoder)?;",
+                crate_name
+            ))?;
+        }
+        Ok(())
+    }
+
     pub fn generate_decode(self, generator: &mut Generator) -> Result<()> {
         let crate_name = self.attributes.crate_name.as_str();
 
@@ -246,14 +345,14 @@ impl DeriveEnum {
                         crate_name
                     ))?;
                 } else {
-                    fn_builder
-                        .push_parsed(format!(
-                            "let variant_index = <u32 as {}::Decode>::decode(decoder)?;",
-                            crate_name
-                        ))?;
-                    fn_builder.push_parsed("match variant_index")?;
+                    // bincode::de::Decoder::claim_depth(decoder)?;
+                    // let variant_index = ...;
+                    // let result = match variant_index { ... };
+                    fn_builder.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                    self.decode_variant_index(fn_builder)?;
+                    fn_builder.push_parsed("let result = match variant_index")?;
                     fn_builder.group(Delimiter::Brace, |variant_case| {
-                        for (mut variant_index, variant) in self.iter_fields() {
+                        for (mut variant_index, variant) in self.iter_fields()? {
                             // idx => Ok(..)
                             if variant_index.len() > 1 {
                                 variant_case.push_parsed("x if x == ")?;
@@ -287,6 +386,13 @@ impl DeriveEnum {
                                                     "<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?.0,",
                                                     crate_name
                                                 ))?;
+                                        } else if let Some(max_len) = attributes.max_len {
+                                            variant_body
+                                                .push_parsed(format!(
+                                                    "{}::de::DecodeMaxLen::decode_max_len(decoder, {})?,",
+                                                    crate_name,
+                                                    max_len
+                                                ))?;
                                         } else {
                                             variant_body
                                                 .push_parsed(format!(
@@ -305,6 +411,12 @@ impl DeriveEnum {
                         // invalid idx
                         self.invalid_variant_case(&enum_name, variant_case)
                     })?;
+                    // ;
+                    // bincode::de::Decoder::unclaim_depth(decoder);
+                    // result
+                    fn_builder.punct(';');
+                    fn_builder.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                    fn_builder.ident_str("result");
                 }
                 Ok(())
             })?;
@@ -341,11 +453,14 @@ impl DeriveEnum {
                         crate_name
                     ))?;
                 } else {
-                    fn_builder
-                        .push_parsed(format!("let variant_index = <u32 as {}::Decode>::decode(decoder)?;", crate_name))?;
-                    fn_builder.push_parsed("match variant_index")?;
+                    // bincode::de::Decoder::claim_depth(decoder)?;
+                    // let variant_index = ...;
+                    // let result = match variant_index { ... };
+                    fn_builder.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                    self.decode_variant_index(fn_builder)?;
+                    fn_builder.push_parsed("let result = match variant_index")?;
                     fn_builder.group(Delimiter::Brace, |variant_case| {
-                        for (mut variant_index, variant) in self.iter_fields() {
+                        for (mut variant_index, variant) in self.iter_fields()? {
                             // idx => Ok(..)
                             if variant_index.len() > 1 {
                                 variant_case.push_parsed("x if x == ")?;
@@ -376,6 +491,8 @@ impl DeriveEnum {
                                         if attributes.with_serde {
                                             variant_body
                                                 .push_parsed(format!("<{0}::serde::BorrowCompat<_> as {0}::BorrowDecode>::borrow_decode(decoder)?.0,", crate_name))?;
+                                        } else if let Some(max_len) = attributes.max_len {
+                                            variant_body.push_parsed(format!("{}::de::BorrowDecodeMaxLen::borrow_decode_max_len(decoder, {})?,", crate_name, max_len))?;
                                         } else {
                                             variant_body.push_parsed(format!("{}::BorrowDecode::borrow_decode(decoder)?,", crate_name))?;
                                         }
@@ -390,16 +507,219 @@ impl DeriveEnum {
                         // invalid idx
                         self.invalid_variant_case(&enum_name, variant_case)
                     })?;
+                    // ;
+                    // bincode::de::Decoder::unclaim_depth(decoder);
+                    // result
+                    fn_builder.punct(';');
+                    fn_builder.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                    fn_builder.ident_str("result");
+                }
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    pub fn generate_decode_in_place(self, generator: &mut Generator) -> Result<()> {
+        let crate_name = self.attributes.crate_name.as_str();
+
+        // Remember to keep this mostly in sync with generate_decode
+
+        let enum_name = generator.target_name().to_string();
+
+        generator
+            .impl_for(format!("{}::de::DecodeInPlace", crate_name))
+            .modify_generic_constraints(|generics, where_constraints| {
+                if let Some((bounds, lit)) = (self.attributes.decode_bounds.as_ref()).or(self.attributes.bounds.as_ref()) {
+                    where_constraints.clear();
+                    where_constraints.push_parsed_constraint(bounds).map_err(|e| e.with_span(lit.span()))?;
+                } else {
+                    for g in generics.iter_generics() {
+                        where_constraints.push_constraint(g, format!("{}::de::DecodeInPlace", crate_name)).unwrap();
+                    }
+                }
+                Ok(())
+            })?
+            .generate_fn("decode_in_place")
+            .with_generic_deps("__D", [format!("{}::de::Decoder", crate_name)])
+            .with_self_arg(FnSelfArg::MutSelf)
+            .with_arg("decoder", "&mut __D")
+            .with_return_type(format!("core::result::Result<(), {}::error::DecodeError>", crate_name))
+            .body(|fn_builder| {
+                if self.variants.is_empty() {
+                    fn_builder.push_parsed(format!(
+                        "core::result::Result::Err({}::error::DecodeError::EmptyEnum {{ type_name: core::any::type_name::<Self>() }})",
+                        crate_name
+                    ))?;
+                } else {
+                    // bincode::de::Decoder::claim_depth(decoder)?;
+                    // let variant_index = ...;
+                    // let result = match variant_index { ... };
+                    fn_builder.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                    self.decode_variant_index(fn_builder)?;
+                    fn_builder.push_parsed("let result = match variant_index")?;
+                    fn_builder.group(Delimiter::Brace, |variant_case| {
+                        for (mut variant_index, variant) in self.iter_fields()? {
+                            // idx => { ... }
+                            if variant_index.len() > 1 {
+                                variant_case.push_parsed("x if x == ")?;
+                                variant_case.extend(variant_index);
+                            } else {
+                                variant_case.push(variant_index.remove(0));
+                            }
+                            variant_case.puncts("=>");
+                            variant_case.group(Delimiter::Brace, |body| {
+                                let is_tuple = matches!(variant.fields, Fields::Tuple(_));
+                                let fields = variant.fields.names();
+                                if !fields.is_empty() {
+                                    // If self already is this variant, its fields are decoded in place
+                                    // if let Self::Variant { 0: field_0, 1: field_1 } = self {
+                                    //     bincode::de::DecodeInPlace::decode_in_place(field_0, decoder)?;
+                                    //     ...
+                                    // } else
+                                    body.push_parsed("if let Self::")?;
+                                    body.ident(variant.name.clone());
+                                    body.group(Delimiter::Brace, |pattern| {
+                                        for (idx, field) in fields.iter().enumerate() {
+                                            if is_tuple {
+                                                pattern.lit_usize(idx);
+                                                pattern.punct(':');
+                                            }
+                                            pattern.push(field.to_token_tree_with_prefix(TUPLE_FIELD_PREFIX));
+                                            pattern.punct(',');
+                                        }
+                                        Ok(())
+                                    })?;
+                                    body.push_parsed("= self")?;
+                                    body.group(Delimiter::Brace, |in_place| {
+                                        for field in &fields {
+                                            let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
+                                            let field_name = field.to_string_with_prefix(TUPLE_FIELD_PREFIX);
+                                            if attributes.with_serde {
+                                                in_place.push_parsed(format!(
+                                                    "*{1} = (<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?).0;",
+                                                    crate_name,
+                                                    field_name
+                                                ))?;
+                                            } else if let Some(max_len) = attributes.max_len {
+                                                in_place.push_parsed(format!(
+                                                    "*{1} = {0}::de::DecodeMaxLen::decode_max_len(decoder, {2})?;",
+                                                    crate_name,
+                                                    field_name,
+                                                    max_len
+                                                ))?;
+                                            } else {
+                                                in_place.push_parsed(format!(
+                                                    "{0}::de::DecodeInPlace::decode_in_place({1}, decoder)?;",
+                                                    crate_name,
+                                                    field_name
+                                                ))?;
+                                            }
+                                        }
+                                        Ok(())
+                                    })?;
+                                    body.ident_str("else");
+                                }
+                                // Otherwise the variant is decoded like Decode would
+                                // {
+                                //     *self = Self::Variant { 0: bincode::Decode::decode(decoder)?, ... };
+                                // }
+                                body.group(Delimiter::Brace, |replace| {
+                                    replace.push_parsed("*self = Self::")?;
+                                    replace.ident(variant.name.clone());
+                                    replace.group(Delimiter::Brace, |variant_body| {
+                                        for (idx, field) in fields.iter().enumerate() {
+                                            if is_tuple {
+                                                variant_body.lit_usize(idx);
+                                            } else {
+                                                variant_body.ident(field.unwrap_ident().clone());
+                                            }
+                                            variant_body.punct(':');
+                                            let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
+                                            if attributes.with_serde {
+                                                variant_body
+                                                    .push_parsed(format!(
+                                                        "<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?.0,",
+                                                        crate_name
+                                                    ))?;
+                                            } else if let Some(max_len) = attributes.max_len {
+                                                variant_body
+                                                    .push_parsed(format!(
+                                                        "{}::de::DecodeMaxLen::decode_max_len(decoder, {})?,",
+                                                        crate_name,
+                                                        max_len
+                                                    ))?;
+                                            } else {
+                                                variant_body
+                                                    .push_parsed(format!(
+                                                        "{}::Decode::decode(decoder)?,",
+                                                        crate_name
+                                                    ))?;
+                                            }
+                                        }
+                                        Ok(())
+                                    })?;
+                                    replace.punct(';');
+                                    Ok(())
+                                })?;
+                                body.push_parsed("Ok(())")?;
+                                Ok(())
+                            })?;
+                            variant_case.punct(',');
+                        }
+
+                        // invalid idx
+                        self.invalid_variant_case(&enum_name, variant_case)
+                    })?;
+                    // ;
+                    // bincode::de::Decoder::unclaim_depth(decoder);
+                    // result
+                    fn_builder.punct(';');
+                    fn_builder.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                    fn_builder.ident_str("result");
                 }
                 Ok(())
             })?;
         Ok(())
     }
+
+    pub fn generate_max_size(
+        self,
+        generator: &mut Generator,
+        generics: &MaxSizeGenerics,
+    ) -> Result<()> {
+        let crate_name = self.attributes.crate_name.as_str();
+        // an empty enum can not be encoded, so it takes up 0 bytes
+        let mut max_size = "core::option::Option::Some(0)".to_string();
+        for (idx, (variant, tag)) in self.variants.iter().zip(self.tags()?).enumerate() {
+            let tag_size = match &self.attributes.tag_type {
+                Some((tag_type, _)) => format!(
+                    "core::option::Option::Some(core::mem::size_of::<{}>())",
+                    tag_type
+                ),
+                None => format!(
+                    "core::option::Option::Some({}::enc::variant_index_max_size::<{}>({}))",
+                    crate_name, CONFIG_GENERIC, tag
+                ),
+            };
+            let variant_size = fields_max_size(crate_name, tag_size, &variant.fields)?;
+            max_size = if idx == 0 {
+                variant_size
+            } else {
+                format!(
+                    "{}::enc::max_size_max({}, {})",
+                    crate_name, max_size, variant_size
+                )
+            };
+        }
+        generics.generate_impl(generator, crate_name, &max_size)
+    }
 }
 
 struct EnumVariantIterator<'a> {
     variants: &'a [EnumVariant],
     idx: usize,
+    tags: Vec<u32>,
+    tag_type: &'a str,
 }
 
 impl<'a> Iterator for EnumVariantIterator<'a> {
@@ -410,8 +730,40 @@ impl<'a> Iterator for EnumVariantIterator<'a> {
         let variant = self.variants.get(self.idx)?;
         self.idx += 1;
 
-        let tokens = vec![TokenTree::Literal(Literal::u32_suffixed(idx as u32))];
+        let tag = self.tags[idx];
+        let literal = match self.tag_type {
+            "u8" => Literal::u8_suffixed(tag as u8),
+            "u16" => Literal::u16_suffixed(tag as u16),
+            _ => Literal::u32_suffixed(tag),
+        };
+        let tokens = vec![TokenTree::Literal(literal)];
 
         Some((tokens, variant))
     }
 }
+
+/// Parse the value of an integer literal like `5`, `-1`, `0x1F` or `10u8`
+fn parse_integer_literal(lit: &Literal) -> Result<i128> {
+    let lit_string = lit.to_string().replace('_', "");
+    let (negative, lit_str) = match lit_string.strip_prefix('-') {
+        Some(lit_str) => (true, lit_str),
+        None => (false, lit_string.as_str()),
+    };
+    let (radix, digits) = if let Some(digits) = lit_str.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = lit_str.strip_prefix("0o") {
+        (8, digits)
+    } else if let Some(digits) = lit_str.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, lit_str)
+    };
+    // Strip the type suffix, if any
+    let digits = match digits.find(['u', 'i']) {
+        Some(idx) => &digits[..idx],
+        None => digits,
+    };
+    let value = i128::from_str_radix(digits, radix)
+        .map_err(|_| Error::custom_at("Could not parse discriminant", lit.span()))?;
+    Ok(if negative { -value } else { value })
+}
@@ -0,0 +1,244 @@
+use crate::attribute::FieldAttributes;
+use virtue::generate::Parent;
+use virtue::prelude::*;
+
+/// The name of the config generic that is added to the `MaxEncodedSize` impl
+pub(crate) const CONFIG_GENERIC: &str = "__C";
+
+/// The generics of the type that `MaxEncodedSize` is derived for.
+///
+/// `virtue` can only add lifetimes to an impl, but `MaxEncodedSize` needs an additional `__C` type generic. Because of this, the generics are read from the input tokens directly.
+#[derive(Default)]
+pub(crate) struct MaxSizeGenerics {
+    /// The generics as declared on the type, without default values, e.g. `'a, T: Foo, const N: usize`
+    params: Vec<String>,
+    /// The names of the generics, e.g. `'a, T, N`
+    names: Vec<String>,
+    /// The names of the type generics, e.g. `T`
+    type_names: Vec<String>,
+    /// The contents of the `where` clause of the type
+    where_clause: Option<String>,
+}
+
+impl MaxSizeGenerics {
+    pub fn from_input(input: &TokenStream) -> Result<Self> {
+        let mut result = Self::default();
+        let mut tokens = input.clone().into_iter().peekable();
+
+        // skip everything up to and including the name of the type
+        while let Some(token) = tokens.next() {
+            if let TokenTree::Ident(ident) = &token {
+                let ident = ident.to_string();
+                if ident == "struct" || ident == "enum" {
+                    tokens.next();
+                    break;
+                }
+            }
+        }
+
+        if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '<') {
+            tokens.next();
+            let mut depth = 0;
+            let mut param = Vec::new();
+            let mut last_was_dash = false;
+            for token in tokens.by_ref() {
+                if let TokenTree::Punct(p) = &token {
+                    match p.as_char() {
+                        '<' => depth += 1,
+                        // `->` in e.g. `F: Fn() -> u8` does not close a generic
+                        '>' if last_was_dash => {}
+                        '>' if depth == 0 => {
+                            result.push_param(param);
+                            param = Vec::new();
+                            break;
+                        }
+                        '>' => depth -= 1,
+                        ',' if depth == 0 => {
+                            result.push_param(param);
+                            param = Vec::new();
+                            last_was_dash = false;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    last_was_dash = p.as_char() == '-';
+                } else {
+                    last_was_dash = false;
+                }
+                param.push(token);
+            }
+            if !param.is_empty() {
+                result.push_param(param);
+            }
+        }
+
+        // The where clause is either in front of the body of a struct or enum, or behind the fields of a tuple struct
+        while let Some(token) = tokens.next() {
+            if matches!(&token, TokenTree::Ident(ident) if ident.to_string() == "where") {
+                let mut where_clause = Vec::new();
+                for token in tokens.by_ref() {
+                    match &token {
+                        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => break,
+                        TokenTree::Punct(p) if p.as_char() == ';' => break,
+                        _ => where_clause.push(token),
+                    }
+                }
+                let where_clause = where_clause
+                    .into_iter()
+                    .collect::<TokenStream>()
+                    .to_string();
+                let where_clause = where_clause.trim().trim_end_matches(',').to_string();
+                if !where_clause.is_empty() {
+                    result.where_clause = Some(where_clause);
+                }
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn push_param(&mut self, param: Vec<TokenTree>) {
+        if param.is_empty() {
+            return;
+        }
+        // strip the default value, e.g. `T = u8` or `const N: usize = 5`
+        let mut depth = 0;
+        let mut end = param.len();
+        for (idx, token) in param.iter().enumerate() {
+            if let TokenTree::Punct(p) = token {
+                match p.as_char() {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    '=' if depth == 0 => {
+                        end = idx;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let name = match (&param[0], param.get(1)) {
+            // 'a
+            (TokenTree::Punct(p), Some(ident)) if p.as_char() == '\'' => format!("'{}", ident),
+            // const N: usize
+            (TokenTree::Ident(ident), Some(name)) if ident.to_string() == "const" => {
+                name.to_string()
+            }
+            // T
+            (first, _) => {
+                self.type_names.push(first.to_string());
+                first.to_string()
+            }
+        };
+        self.names.push(name);
+        self.params.push(
+            param[..end]
+                .iter()
+                .cloned()
+                .collect::<TokenStream>()
+                .to_string(),
+        );
+    }
+
+    /// Append `impl<..., __C: StaticConfig> MaxEncodedSize<__C> for Name<...> where ... { const MAX_SIZE: Option<usize> = max_size; }` to the generator
+    pub fn generate_impl(
+        &self,
+        generator: &mut Generator,
+        crate_name: &str,
+        max_size: &str,
+    ) -> Result<()> {
+        let mut params = self.params.clone();
+        params.push(format!(
+            "{}: {}::config::StaticConfig",
+            CONFIG_GENERIC, crate_name
+        ));
+
+        let mut where_clause = Vec::new();
+        where_clause.extend(self.where_clause.clone());
+        for type_name in &self.type_names {
+            where_clause.push(format!(
+                "{}: {}::enc::MaxEncodedSize<{}>",
+                type_name, crate_name, CONFIG_GENERIC
+            ));
+        }
+
+        let type_generics = if self.names.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.names.join(", "))
+        };
+        let where_clause = if where_clause.is_empty() {
+            String::new()
+        } else {
+            format!("where {}", where_clause.join(", "))
+        };
+
+        let mut builder = StreamBuilder::new();
+        builder.push_parsed(format!(
+            "impl<{}> {}::enc::MaxEncodedSize<{}> for {}{} {} {{ const MAX_SIZE: core::option::Option<usize> = {}; }}",
+            params.join(", "),
+            crate_name,
+            CONFIG_GENERIC,
+            generator.target_name(),
+            type_generics,
+            where_clause,
+            max_size
+        ))?;
+        generator.append(builder);
+        Ok(())
+    }
+}
+
+/// The maximum size of a field of type `ty`, as an expression of type `Option<usize>`
+fn field_max_size(crate_name: &str, ty: &[TokenTree], attributes: &FieldAttributes) -> String {
+    if attributes.with_serde {
+        // serde types can be of any size
+        return "core::option::Option::None".to_string();
+    }
+    let ty = ty.iter().cloned().collect::<TokenStream>();
+    if let Some(max_len) = attributes.max_len {
+        // The encoder rejects values with more than `max_len` entries
+        return format!(
+            "{}::enc::max_len_max_size::<{}, {}>({})",
+            crate_name, ty, CONFIG_GENERIC, max_len
+        );
+    }
+    format!(
+        "<{} as {}::enc::MaxEncodedSize<{}>>::MAX_SIZE",
+        ty, crate_name, CONFIG_GENERIC
+    )
+}
+
+/// Sum up the given maximum sizes with `max_size_add`
+fn sum_max_sizes(
+    crate_name: &str,
+    initial: String,
+    sizes: impl IntoIterator<Item = String>,
+) -> String {
+    sizes.into_iter().fold(initial, |acc, size| {
+        format!("{}::enc::max_size_add({}, {})", crate_name, acc, size)
+    })
+}
+
+/// The maximum size of all `fields` combined, as an expression of type `Option<usize>`
+pub(crate) fn fields_max_size(
+    crate_name: &str,
+    initial: String,
+    fields: &Fields,
+) -> Result<String> {
+    let fields: Vec<&UnnamedField> = match fields {
+        Fields::Tuple(fields) => fields.iter().collect(),
+        Fields::Struct(fields) => fields.iter().map(|(_, field)| field).collect(),
+        Fields::Unit | Fields::Integer(_) => Vec::new(),
+    };
+    let mut sizes = Vec::with_capacity(fields.len());
+    for field in fields {
+        let attributes = field
+            .attributes
+            .get_attribute::<FieldAttributes>()?
+            .unwrap_or_default();
+        sizes.push(field_max_size(crate_name, &field.r#type, &attributes));
+    }
+    Ok(sum_max_sizes(crate_name, initial, sizes))
+}
@@ -1,4 +1,5 @@
 use crate::attribute::{ContainerAttributes, FieldAttributes};
+use crate::derive_max_size::{fields_max_size, MaxSizeGenerics};
 use virtue::generate::Generator;
 use virtue::parse::Fields;
 use virtue::prelude::*;
@@ -49,6 +50,11 @@ impl DeriveStruct {
                             "{0}::Encode::encode(&{0}::serde::Compat(&self.{1}), encoder)?;",
                             crate_name, field
                         ))?;
+                    } else if let Some(max_len) = attributes.max_len {
+                        fn_body.push_parsed(format!(
+                            "{}::enc::EncodeMaxLen::encode_max_len(&self.{}, encoder, {})?;",
+                            crate_name, field, max_len
+                        ))?;
                     } else {
                         fn_body.push_parsed(format!(
                             "{}::Encode::encode(&self.{}, encoder)?;",
@@ -84,39 +90,51 @@ impl DeriveStruct {
             .with_arg("decoder", "&mut __D")
             .with_return_type(format!("core::result::Result<Self, {}::error::DecodeError>", crate_name))
             .body(|fn_body| {
-                // Ok(Self {
-                fn_body.ident_str("Ok");
-                fn_body.group(Delimiter::Parenthesis, |ok_group| {
-                    ok_group.ident_str("Self");
-                    ok_group.group(Delimiter::Brace, |struct_body| {
-                        // Fields
-                        // {
-                        //      a: bincode::Decode::decode(decoder)?,
-                        //      b: bincode::Decode::decode(decoder)?,
-                        //      ...
-                        // }
-                        for field in &self.fields.names() {
-                            let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
-                            if attributes.with_serde {
-                                struct_body
-                                    .push_parsed(format!(
-                                        "{1}: (<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?).0,",
-                                        crate_name,
-                                        field
-                                    ))?;
-                            } else {
-                                struct_body
-                                    .push_parsed(format!(
-                                        "{1}: {0}::Decode::decode(decoder)?,",
-                                        crate_name,
-                                        field
-                                    ))?;
-                            }
+                // bincode::de::Decoder::claim_depth(decoder)?;
+                fn_body.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                // let result = Self {
+                fn_body.push_parsed("let result = Self")?;
+                fn_body.group(Delimiter::Brace, |struct_body| {
+                    // Fields
+                    // {
+                    //      a: bincode::Decode::decode(decoder)?,
+                    //      b: bincode::Decode::decode(decoder)?,
+                    //      ...
+                    // }
+                    for field in &self.fields.names() {
+                        let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
+                        if attributes.with_serde {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: (<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?).0,",
+                                    crate_name,
+                                    field
+                                ))?;
+                        } else if let Some(max_len) = attributes.max_len {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: {0}::de::DecodeMaxLen::decode_max_len(decoder, {2})?,",
+                                    crate_name,
+                                    field,
+                                    max_len
+                                ))?;
+                        } else {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: {0}::Decode::decode(decoder)?,",
+                                    crate_name,
+                                    field
+                                ))?;
                         }
-                        Ok(())
-                    })?;
+                    }
                     Ok(())
                 })?;
+                // };
+                // bincode::de::Decoder::unclaim_depth(decoder);
+                // Ok(result)
+                fn_body.punct(';');
+                fn_body.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                fn_body.push_parsed("Ok(result)")?;
                 Ok(())
             })?;
         self.generate_borrow_decode(generator)?;
@@ -145,35 +163,117 @@ impl DeriveStruct {
             .with_arg("decoder", "&mut __D")
             .with_return_type(format!("core::result::Result<Self, {}::error::DecodeError>", crate_name))
             .body(|fn_body| {
-                // Ok(Self {
-                fn_body.ident_str("Ok");
-                fn_body.group(Delimiter::Parenthesis, |ok_group| {
-                    ok_group.ident_str("Self");
-                    ok_group.group(Delimiter::Brace, |struct_body| {
-                        for field in self.fields.names() {
-                            let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
-                            if attributes.with_serde {
-                                struct_body
-                                    .push_parsed(format!(
-                                        "{1}: (<{0}::serde::BorrowCompat<_> as {0}::BorrowDecode>::borrow_decode(decoder)?).0,",
-                                        crate_name,
-                                        field
-                                    ))?;
-                            } else {
-                                struct_body
-                                    .push_parsed(format!(
-                                        "{1}: {0}::BorrowDecode::borrow_decode(decoder)?,",
-                                        crate_name,
-                                        field
-                                    ))?;
-                            }
+                // bincode::de::Decoder::claim_depth(decoder)?;
+                fn_body.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                // let result = Self {
+                fn_body.push_parsed("let result = Self")?;
+                fn_body.group(Delimiter::Brace, |struct_body| {
+                    for field in self.fields.names() {
+                        let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
+                        if attributes.with_serde {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: (<{0}::serde::BorrowCompat<_> as {0}::BorrowDecode>::borrow_decode(decoder)?).0,",
+                                    crate_name,
+                                    field
+                                ))?;
+                        } else if let Some(max_len) = attributes.max_len {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: {0}::de::BorrowDecodeMaxLen::borrow_decode_max_len(decoder, {2})?,",
+                                    crate_name,
+                                    field,
+                                    max_len
+                                ))?;
+                        } else {
+                            struct_body
+                                .push_parsed(format!(
+                                    "{1}: {0}::BorrowDecode::borrow_decode(decoder)?,",
+                                    crate_name,
+                                    field
+                                ))?;
                         }
-                        Ok(())
-                    })?;
+                    }
                     Ok(())
                 })?;
+                // };
+                // bincode::de::Decoder::unclaim_depth(decoder);
+                // Ok(result)
+                fn_body.punct(';');
+                fn_body.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                fn_body.push_parsed("Ok(result)")?;
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    pub fn generate_decode_in_place(self, generator: &mut Generator) -> Result<()> {
+        let crate_name = &self.attributes.crate_name;
+
+        generator
+            .impl_for(format!("{}::de::DecodeInPlace", crate_name))
+            .modify_generic_constraints(|generics, where_constraints| {
+                if let Some((bounds, lit)) = (self.attributes.decode_bounds.as_ref()).or(self.attributes.bounds.as_ref()) {
+                    where_constraints.clear();
+                    where_constraints.push_parsed_constraint(bounds).map_err(|e| e.with_span(lit.span()))?;
+                } else {
+                    for g in generics.iter_generics() {
+                        where_constraints.push_constraint(g, format!("{}::de::DecodeInPlace", crate_name)).unwrap();
+                    }
+                }
+                Ok(())
+            })?
+            .generate_fn("decode_in_place")
+            .with_generic_deps("__D", [format!("{}::de::Decoder", crate_name)])
+            .with_self_arg(virtue::generate::FnSelfArg::MutSelf)
+            .with_arg("decoder", "&mut __D")
+            .with_return_type(format!("core::result::Result<(), {}::error::DecodeError>", crate_name))
+            .body(|fn_body| {
+                // bincode::de::Decoder::claim_depth(decoder)?;
+                fn_body.push_parsed(format!("{}::de::Decoder::claim_depth(decoder)?;", crate_name))?;
+                // Fields that use `with_serde` or `max_len` can not be decoded in place, so they are replaced
+                // bincode::de::DecodeInPlace::decode_in_place(&mut self.a, decoder)?;
+                // self.b = bincode::de::DecodeMaxLen::decode_max_len(decoder, 10)?;
+                // ...
+                for field in self.fields.names() {
+                    let attributes = field.attributes().get_attribute::<FieldAttributes>()?.unwrap_or_default();
+                    if attributes.with_serde {
+                        fn_body.push_parsed(format!(
+                            "self.{1} = (<{0}::serde::Compat<_> as {0}::Decode>::decode(decoder)?).0;",
+                            crate_name, field
+                        ))?;
+                    } else if let Some(max_len) = attributes.max_len {
+                        fn_body.push_parsed(format!(
+                            "self.{1} = {0}::de::DecodeMaxLen::decode_max_len(decoder, {2})?;",
+                            crate_name, field, max_len
+                        ))?;
+                    } else {
+                        fn_body.push_parsed(format!(
+                            "{0}::de::DecodeInPlace::decode_in_place(&mut self.{1}, decoder)?;",
+                            crate_name, field
+                        ))?;
+                    }
+                }
+                // bincode::de::Decoder::unclaim_depth(decoder);
+                // Ok(())
+                fn_body.push_parsed(format!("{}::de::Decoder::unclaim_depth(decoder);", crate_name))?;
+                fn_body.push_parsed("Ok(())")?;
                 Ok(())
             })?;
         Ok(())
     }
+
+    pub fn generate_max_size(
+        self,
+        generator: &mut Generator,
+        generics: &MaxSizeGenerics,
+    ) -> Result<()> {
+        let crate_name = &self.attributes.crate_name;
+        let max_size = fields_max_size(
+            crate_name,
+            "core::option::Option::Some(0)".to_string(),
+            &self.fields,
+        )?;
+        generics.generate_impl(generator, crate_name, &max_size)
+    }
 }
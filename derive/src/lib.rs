@@ -1,8 +1,10 @@
 mod attribute;
 mod derive_enum;
+mod derive_max_size;
 mod derive_struct;
 
 use attribute::ContainerAttributes;
+use derive_max_size::MaxSizeGenerics;
 use virtue::prelude::*;
 
 #[proc_macro_derive(Encode, attributes(bincode))]
@@ -13,12 +15,16 @@ pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 fn derive_encode_inner(input: TokenStream) -> Result<TokenStream> {
     let parse = Parse::new(input)?;
     let (mut generator, attributes, body) = parse.into_generator();
-    let attributes = attributes
-        .get_attribute::<ContainerAttributes>()?
-        .unwrap_or_default();
+    let attributes = ContainerAttributes::from_attributes(&attributes)?;
 
     match body {
         Body::Struct(body) => {
+            if let Some((_, span)) = attributes.tag_type {
+                return Err(Error::custom_at(
+                    "tag_type is only supported on enums",
+                    span,
+                ));
+            }
             derive_struct::DeriveStruct {
                 fields: body.fields,
                 attributes,
@@ -46,12 +52,16 @@ pub fn derive_decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 fn derive_decode_inner(input: TokenStream) -> Result<TokenStream> {
     let parse = Parse::new(input)?;
     let (mut generator, attributes, body) = parse.into_generator();
-    let attributes = attributes
-        .get_attribute::<ContainerAttributes>()?
-        .unwrap_or_default();
+    let attributes = ContainerAttributes::from_attributes(&attributes)?;
 
     match body {
         Body::Struct(body) => {
+            if let Some((_, span)) = attributes.tag_type {
+                return Err(Error::custom_at(
+                    "tag_type is only supported on enums",
+                    span,
+                ));
+            }
             derive_struct::DeriveStruct {
                 fields: body.fields,
                 attributes,
@@ -79,12 +89,16 @@ pub fn derive_borrow_decode(input: proc_macro::TokenStream) -> proc_macro::Token
 fn derive_borrow_decode_inner(input: TokenStream) -> Result<TokenStream> {
     let parse = Parse::new(input)?;
     let (mut generator, attributes, body) = parse.into_generator();
-    let attributes = attributes
-        .get_attribute::<ContainerAttributes>()?
-        .unwrap_or_default();
+    let attributes = ContainerAttributes::from_attributes(&attributes)?;
 
     match body {
         Body::Struct(body) => {
+            if let Some((_, span)) = attributes.tag_type {
+                return Err(Error::custom_at(
+                    "tag_type is only supported on enums",
+                    span,
+                ));
+            }
             derive_struct::DeriveStruct {
                 fields: body.fields,
                 attributes,
@@ -103,3 +117,78 @@ fn derive_borrow_decode_inner(input: TokenStream) -> Result<TokenStream> {
     generator.export_to_file("bincode", "BorrowDecode");
     generator.finish()
 }
+
+#[proc_macro_derive(DecodeInPlace, attributes(bincode))]
+pub fn derive_decode_in_place(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_decode_in_place_inner(input).unwrap_or_else(|e| e.into_token_stream())
+}
+
+fn derive_decode_in_place_inner(input: TokenStream) -> Result<TokenStream> {
+    let parse = Parse::new(input)?;
+    let (mut generator, attributes, body) = parse.into_generator();
+    let attributes = ContainerAttributes::from_attributes(&attributes)?;
+
+    match body {
+        Body::Struct(body) => {
+            if let Some((_, span)) = attributes.tag_type {
+                return Err(Error::custom_at(
+                    "tag_type is only supported on enums",
+                    span,
+                ));
+            }
+            derive_struct::DeriveStruct {
+                fields: body.fields,
+                attributes,
+            }
+            .generate_decode_in_place(&mut generator)?;
+        }
+        Body::Enum(body) => {
+            derive_enum::DeriveEnum {
+                variants: body.variants,
+                attributes,
+            }
+            .generate_decode_in_place(&mut generator)?;
+        }
+    }
+
+    generator.export_to_file("bincode", "DecodeInPlace");
+    generator.finish()
+}
+
+#[proc_macro_derive(MaxEncodedSize, attributes(bincode))]
+pub fn derive_max_encoded_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_max_encoded_size_inner(input).unwrap_or_else(|e| e.into_token_stream())
+}
+
+fn derive_max_encoded_size_inner(input: TokenStream) -> Result<TokenStream> {
+    let generics = MaxSizeGenerics::from_input(&input)?;
+    let parse = Parse::new(input)?;
+    let (mut generator, attributes, body) = parse.into_generator();
+    let attributes = ContainerAttributes::from_attributes(&attributes)?;
+
+    match body {
+        Body::Struct(body) => {
+            if let Some((_, span)) = attributes.tag_type {
+                return Err(Error::custom_at(
+                    "tag_type is only supported on enums",
+                    span,
+                ));
+            }
+            derive_struct::DeriveStruct {
+                fields: body.fields,
+                attributes,
+            }
+            .generate_max_size(&mut generator, &generics)?;
+        }
+        Body::Enum(body) => {
+            derive_enum::DeriveEnum {
+                variants: body.variants,
+                attributes,
+            }
+            .generate_max_size(&mut generator, &generics)?;
+        }
+    }
+
+    generator.export_to_file("bincode", "MaxEncodedSize");
+    generator.finish()
+}
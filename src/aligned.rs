@@ -0,0 +1,113 @@
+use crate::{
+    config::{InternalConfig, StaticConfig},
+    de::{read::BorrowReader, BorrowDecode, BorrowDecoder, Decode},
+    enc::{write::Writer, Encode, Encoder, MaxEncodedSize},
+    error::{DecodeError, EncodeError},
+};
+use core::ops::Deref;
+
+/// A slice of integers or floats that is encoded so it can be borrowed when decoding.
+///
+/// Slices like `&[u32]` and `&[f64]` can be borrowed with [BorrowDecode], but only if their values happen to be aligned in the input. An `AlignedSlice` writes up to `align_of::<T>() - 1` bytes of padding in front of its values, so they are aligned relative to the start of the encoded value. When the buffer that is decoded from is aligned as well, e.g. because it is a `&[u8]` that points into a `Vec<u64>`, the values can always be borrowed.
+///
+/// The encoding is the length of the slice, the amount of padding as a `u8`, the padding and then the values. This is not the same as the encoding of `&[T]`.
+///
+/// The padding is based on the position that the writer reports, so the values are only aligned relative to where that position starts counting. [encode_into_slice] counts from the start of the slice and [encode_into_std_write] from the first byte it writes, so a value that is written after other data is aligned relative to the start of that call, not to the start of the whole output. [encode_append_to_vec] counts from the start of the `Vec`, including the bytes that were already in it. Writers that do not report a position never write padding, and neither does [with_canonical_encoding], so the same value is always encoded to the same bytes. Decoding rejects padding that is not smaller than `align_of::<T>()`, and with [with_strict_decoding] also padding bytes that are not zero.
+///
+/// Borrowing only works if the config encodes `T` as its in-memory bytes, e.g. with [legacy] on a little endian target. Otherwise decoding fails with [DecodeError::NonNativeSlice]. If the values in the input are not aligned, decoding fails with [DecodeError::UnalignedSlice].
+///
+/// ```
+/// use bincode::AlignedSlice;
+///
+/// #[repr(align(16))]
+/// struct Buffer([u8; 64]);
+///
+/// let config = bincode::config::legacy();
+/// let values = [1.5f64, 2.5, 3.5];
+/// let mut buffer = Buffer([0; 64]);
+/// let len = bincode::encode_into_slice((5u8, AlignedSlice(&values[..])), &mut buffer.0, config).unwrap();
+///
+/// let ((byte, decoded), _): ((u8, AlignedSlice<f64>), usize) =
+///     bincode::borrow_decode_from_slice(&buffer.0[..len], config).unwrap();
+/// assert_eq!(byte, 5);
+/// assert_eq!(decoded.0, &values[..]);
+/// ```
+///
+/// [legacy]: config/fn.legacy.html
+/// [encode_into_slice]: fn.encode_into_slice.html
+/// [encode_into_std_write]: fn.encode_into_std_write.html
+/// [encode_append_to_vec]: fn.encode_append_to_vec.html
+/// [with_strict_decoding]: config/struct.Configuration.html#method.with_strict_decoding
+/// [with_canonical_encoding]: config/struct.Configuration.html#method.with_canonical_encoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignedSlice<'a, T>(pub &'a [T]);
+
+impl<'a, T> Deref for AlignedSlice<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.0
+    }
+}
+
+impl<'a, T> From<&'a [T]> for AlignedSlice<'a, T> {
+    fn from(slice: &'a [T]) -> Self {
+        Self(slice)
+    }
+}
+
+impl<T, C: StaticConfig> MaxEncodedSize<C> for AlignedSlice<'_, T> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+/// The bytes that are written as padding. This is as long as the largest alignment of the types that `AlignedSlice` is implemented for.
+const PADDING: [u8; 16] = [0; 16];
+
+macro_rules! impl_aligned_slice {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for AlignedSlice<'_, $ty> {
+                fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+                    crate::enc::encode_slice_len(encoder, self.0.len())?;
+                    let align = core::mem::align_of::<$ty>();
+                    // The padding depends on where the value is written, so canonical encoding leaves it out
+                    let position = if encoder.config().is_canonical() {
+                        None
+                    } else {
+                        encoder.writer().position_hint()
+                    };
+                    // The values start after the byte that holds the amount of padding
+                    let padding = match position {
+                        Some(position) => (align - (position + 1) % align) % align,
+                        None => 0,
+                    };
+                    (padding as u8).encode(encoder)?;
+                    crate::enc::ClaimedWriter(encoder).write(&PADDING[..padding])?;
+                    <$ty>::encode_slice(self.0, encoder)
+                }
+            }
+
+            impl<'a, 'de: 'a> BorrowDecode<'de> for AlignedSlice<'a, $ty> {
+                fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+                    let len = crate::de::decode_collection_len(decoder, usize::MAX)?;
+                    let padding = usize::from(u8::decode(decoder)?);
+                    let invalid_padding = DecodeError::InvalidSlicePadding {
+                        type_name: core::any::type_name::<$ty>(),
+                        padding,
+                    };
+                    if padding >= core::mem::align_of::<$ty>() {
+                        return Err(invalid_padding);
+                    }
+                    decoder.claim_bytes_read(padding)?;
+                    let bytes = decoder.borrow_reader().take_bytes(padding)?;
+                    if decoder.config().is_strict() && bytes.iter().any(|&byte| byte != 0) {
+                        return Err(invalid_padding);
+                    }
+                    crate::de::borrow_native_slice(decoder, len).map(AlignedSlice)
+                }
+            }
+        )*
+    };
+}
+
+impl_aligned_slice!(u16, u32, u64, u128, i16, i32, i64, i128, f32, f64);
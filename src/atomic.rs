@@ -1,4 +1,9 @@
-use crate::{de::Decode, enc::Encode, impl_borrow_decode};
+use crate::{
+    config::StaticConfig,
+    de::{Decode, DecodeInPlace},
+    enc::{Encode, MaxEncodedSize},
+    impl_borrow_decode,
+};
 use core::sync::atomic::Ordering;
 
 #[cfg(target_has_atomic = "ptr")]
@@ -35,6 +40,9 @@ impl Decode for AtomicBool {
 #[cfg(target_has_atomic = "8")]
 impl_borrow_decode!(AtomicBool);
 
+#[cfg(target_has_atomic = "8")]
+impl DecodeInPlace for AtomicBool {}
+
 #[cfg(target_has_atomic = "8")]
 impl Encode for AtomicU8 {
     fn encode<E: crate::enc::Encoder>(
@@ -54,6 +62,9 @@ impl Decode for AtomicU8 {
 #[cfg(target_has_atomic = "8")]
 impl_borrow_decode!(AtomicU8);
 
+#[cfg(target_has_atomic = "8")]
+impl DecodeInPlace for AtomicU8 {}
+
 #[cfg(target_has_atomic = "16")]
 impl Encode for AtomicU16 {
     fn encode<E: crate::enc::Encoder>(
@@ -73,6 +84,9 @@ impl Decode for AtomicU16 {
 #[cfg(target_has_atomic = "16")]
 impl_borrow_decode!(AtomicU16);
 
+#[cfg(target_has_atomic = "16")]
+impl DecodeInPlace for AtomicU16 {}
+
 #[cfg(target_has_atomic = "32")]
 impl Encode for AtomicU32 {
     fn encode<E: crate::enc::Encoder>(
@@ -92,6 +106,9 @@ impl Decode for AtomicU32 {
 #[cfg(target_has_atomic = "32")]
 impl_borrow_decode!(AtomicU32);
 
+#[cfg(target_has_atomic = "32")]
+impl DecodeInPlace for AtomicU32 {}
+
 #[cfg(target_has_atomic = "64")]
 impl Encode for AtomicU64 {
     fn encode<E: crate::enc::Encoder>(
@@ -111,6 +128,9 @@ impl Decode for AtomicU64 {
 #[cfg(target_has_atomic = "64")]
 impl_borrow_decode!(AtomicU64);
 
+#[cfg(target_has_atomic = "64")]
+impl DecodeInPlace for AtomicU64 {}
+
 #[cfg(target_has_atomic = "ptr")]
 impl Encode for AtomicUsize {
     fn encode<E: crate::enc::Encoder>(
@@ -130,6 +150,9 @@ impl Decode for AtomicUsize {
 #[cfg(target_has_atomic = "ptr")]
 impl_borrow_decode!(AtomicUsize);
 
+#[cfg(target_has_atomic = "ptr")]
+impl DecodeInPlace for AtomicUsize {}
+
 #[cfg(target_has_atomic = "8")]
 impl Encode for AtomicI8 {
     fn encode<E: crate::enc::Encoder>(
@@ -149,6 +172,9 @@ impl Decode for AtomicI8 {
 #[cfg(target_has_atomic = "8")]
 impl_borrow_decode!(AtomicI8);
 
+#[cfg(target_has_atomic = "8")]
+impl DecodeInPlace for AtomicI8 {}
+
 #[cfg(target_has_atomic = "16")]
 impl Encode for AtomicI16 {
     fn encode<E: crate::enc::Encoder>(
@@ -168,6 +194,9 @@ impl Decode for AtomicI16 {
 #[cfg(target_has_atomic = "16")]
 impl_borrow_decode!(AtomicI16);
 
+#[cfg(target_has_atomic = "16")]
+impl DecodeInPlace for AtomicI16 {}
+
 #[cfg(target_has_atomic = "32")]
 impl Encode for AtomicI32 {
     fn encode<E: crate::enc::Encoder>(
@@ -187,6 +216,9 @@ impl Decode for AtomicI32 {
 #[cfg(target_has_atomic = "32")]
 impl_borrow_decode!(AtomicI32);
 
+#[cfg(target_has_atomic = "32")]
+impl DecodeInPlace for AtomicI32 {}
+
 #[cfg(target_has_atomic = "64")]
 impl Encode for AtomicI64 {
     fn encode<E: crate::enc::Encoder>(
@@ -206,6 +238,9 @@ impl Decode for AtomicI64 {
 #[cfg(target_has_atomic = "64")]
 impl_borrow_decode!(AtomicI64);
 
+#[cfg(target_has_atomic = "64")]
+impl DecodeInPlace for AtomicI64 {}
+
 #[cfg(target_has_atomic = "ptr")]
 impl Encode for AtomicIsize {
     fn encode<E: crate::enc::Encoder>(
@@ -224,3 +259,61 @@ impl Decode for AtomicIsize {
 }
 #[cfg(target_has_atomic = "ptr")]
 impl_borrow_decode!(AtomicIsize);
+
+#[cfg(target_has_atomic = "ptr")]
+impl DecodeInPlace for AtomicIsize {}
+
+#[cfg(target_has_atomic = "8")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicBool {
+    const MAX_SIZE: Option<usize> = <bool as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "8")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicU8 {
+    const MAX_SIZE: Option<usize> = <u8 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "16")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicU16 {
+    const MAX_SIZE: Option<usize> = <u16 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "32")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicU32 {
+    const MAX_SIZE: Option<usize> = <u32 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicU64 {
+    const MAX_SIZE: Option<usize> = <u64 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicUsize {
+    const MAX_SIZE: Option<usize> = <usize as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "8")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicI8 {
+    const MAX_SIZE: Option<usize> = <i8 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "16")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicI16 {
+    const MAX_SIZE: Option<usize> = <i16 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "32")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicI32 {
+    const MAX_SIZE: Option<usize> = <i32 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicI64 {
+    const MAX_SIZE: Option<usize> = <i64 as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<C: StaticConfig> MaxEncodedSize<C> for AtomicIsize {
+    const MAX_SIZE: Option<usize> = <isize as MaxEncodedSize<C>>::MAX_SIZE;
+}
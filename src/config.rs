@@ -12,12 +12,55 @@
 //!     // pick one of:
 //!     .with_variable_int_encoding()
 //!     .with_fixed_int_encoding()
+//!     .with_leb128_int_encoding()
 //!     // pick one of:
 //!     .skip_fixed_array_length()
-//!     .write_fixed_array_length();
+//!     .write_fixed_array_length()
+//!     // pick one of:
+//!     .with_u32_discriminants()
+//!     .with_u8_discriminants()
+//!     .with_varint_discriminants()
+//!     // pick one of:
+//!     .with_u64_lengths()
+//!     .with_fixed_u32_lengths()
+//!     .with_fixed_u64_lengths()
+//!     .with_varint_lengths()
+//!     // pick one of:
+//!     .with_canonical_encoding()
+//!     .with_non_canonical_encoding()
+//!     // pick one of:
+//!     .with_strict_decoding()
+//!     .with_lenient_decoding()
+//!     // pick one of:
+//!     .with_last_key_wins()
+//!     .with_first_key_wins()
+//!     .with_duplicate_key_error()
+//!     // pick one of:
+//!     .with_depth_limit::<64>()
+//!     .with_no_depth_limit()
+//!     // pick one of:
+//!     .with_max_collection_len::<100_000>()
+//!     .with_no_max_collection_len()
+//!     // pick one of:
+//!     .with_max_string_len::<1_048_576>()
+//!     .with_no_max_string_len();
 //! ```
 //!
 //! See [Configuration] for more information on the configuration options.
+//!
+//! If the options are only known at runtime, e.g. because they are read from a config file or negotiated with a peer,
+//! use [DynamicConfig] instead. This has the same options as [Configuration], but stores them as values.
+//!
+//! ```
+//! use bincode::config::{DynamicConfig, Endian, IntEncoding};
+//!
+//! let mut config = DynamicConfig::standard();
+//! config.endian = Endian::Big;
+//! config.int_encoding = IntEncoding::Fixed;
+//!
+//! let encoded = bincode::encode_to_vec(5u32, config).unwrap();
+//! assert_eq!(encoded, [0, 0, 0, 5]);
+//! ```
 
 pub(crate) use self::internal::*;
 use core::marker::PhantomData;
@@ -28,74 +71,159 @@ use core::marker::PhantomData;
 /// The following methods are mutually exclusive and will overwrite each other. The last call to one of these methods determines the behavior of the configuration:
 ///
 /// - [with_little_endian] and [with_big_endian]
-/// - [with_fixed_int_encoding] and [with_variable_int_encoding]
+/// - [with_fixed_int_encoding], [with_variable_int_encoding] and [with_leb128_int_encoding]
 /// - [skip_fixed_array_length] and [write_fixed_array_length]
-///
+/// - [with_limit], [with_runtime_limit] and [with_no_limit]
+/// - [with_u32_discriminants], [with_u8_discriminants] and [with_varint_discriminants]
+/// - [with_u64_lengths], [with_fixed_u32_lengths], [with_fixed_u64_lengths] and [with_varint_lengths]
+/// - [with_canonical_encoding] and [with_non_canonical_encoding]
+/// - [with_strict_decoding] and [with_lenient_decoding]
+/// - [with_last_key_wins], [with_first_key_wins] and [with_duplicate_key_error]
+/// - [with_depth_limit] and [with_no_depth_limit]
+/// - [with_max_collection_len] and [with_no_max_collection_len]
+/// - [with_max_string_len] and [with_no_max_string_len]
 ///
 /// [with_little_endian]: #method.with_little_endian
 /// [with_big_endian]: #method.with_big_endian
 /// [with_fixed_int_encoding]: #method.with_fixed_int_encoding
 /// [with_variable_int_encoding]: #method.with_variable_int_encoding
+/// [with_leb128_int_encoding]: #method.with_leb128_int_encoding
 /// [skip_fixed_array_length]: #method.skip_fixed_array_length
 /// [write_fixed_array_length]: #method.write_fixed_array_length
+/// [with_limit]: #method.with_limit
+/// [with_runtime_limit]: #method.with_runtime_limit
+/// [with_no_limit]: #method.with_no_limit
+/// [with_u32_discriminants]: #method.with_u32_discriminants
+/// [with_u8_discriminants]: #method.with_u8_discriminants
+/// [with_varint_discriminants]: #method.with_varint_discriminants
+/// [with_u64_lengths]: #method.with_u64_lengths
+/// [with_fixed_u32_lengths]: #method.with_fixed_u32_lengths
+/// [with_fixed_u64_lengths]: #method.with_fixed_u64_lengths
+/// [with_varint_lengths]: #method.with_varint_lengths
+/// [with_canonical_encoding]: #method.with_canonical_encoding
+/// [with_non_canonical_encoding]: #method.with_non_canonical_encoding
+/// [with_strict_decoding]: #method.with_strict_decoding
+/// [with_lenient_decoding]: #method.with_lenient_decoding
+/// [with_last_key_wins]: #method.with_last_key_wins
+/// [with_first_key_wins]: #method.with_first_key_wins
+/// [with_duplicate_key_error]: #method.with_duplicate_key_error
+/// [with_depth_limit]: #method.with_depth_limit
+/// [with_no_depth_limit]: #method.with_no_depth_limit
+/// [with_max_collection_len]: #method.with_max_collection_len
+/// [with_no_max_collection_len]: #method.with_no_max_collection_len
+/// [with_max_string_len]: #method.with_max_string_len
+/// [with_no_max_string_len]: #method.with_no_max_string_len
 #[derive(Copy, Clone)]
-pub struct Configuration<E = LittleEndian, I = Varint, A = WriteFixedArrayLength, L = NoLimit> {
+pub struct Configuration<
+    E = LittleEndian,
+    I = Varint,
+    A = WriteFixedArrayLength,
+    L = NoLimit,
+    D = U32Discriminant,
+    S = U64Length,
+    M = NonCanonical,
+    V = LenientDecoding,
+    K = LastKeyWins,
+    R = NoDepthLimit,
+    C = NoMaxLen,
+    B = NoMaxLen,
+> {
     _e: PhantomData<E>,
     _i: PhantomData<I>,
     _a: PhantomData<A>,
-    _l: PhantomData<L>,
+    limit: L,
+    _d: PhantomData<D>,
+    _s: PhantomData<S>,
+    _m: PhantomData<M>,
+    _v: PhantomData<V>,
+    _k: PhantomData<K>,
+    _r: PhantomData<R>,
+    _c: PhantomData<C>,
+    _b: PhantomData<B>,
 }
 
 // When adding more features to configuration, follow these steps:
 // - Create 2 or more structs that can be used as a type (e.g. Limit and NoLimit)
 // - Add an `Internal...Config` to the `internal` module
-// - Make sure `Config` and `impl<T> Config for T` extend from this new trait
+// - Add an accessor for the option to `InternalConfig`
 // - Add a generic to `Configuration`
 // - Add this generic to `impl<...> Default for Configuration<...>`
 // - Add this generic to `const fn generate<...>()`
 // - Add this generic to _every_ function in `Configuration`
 // - Add your new methods
+// - Implement the new accessor in `impl<...> InternalConfig for Configuration<...>`
+// - Add a field to `DynamicConfig`, and implement the new accessor for it
 
 /// The default config for bincode 2.0. By default this will be:
 /// - Little endian
 /// - Variable int encoding
 /// - Write fixed array length
+/// - Enum discriminants encoded as u32
+/// - Lengths encoded as u64
+/// - Non-canonical encoding
+/// - Lenient decoding
+/// - The last of any duplicate map keys wins
+/// - No depth limit
+/// - No maximum collection or string length
 pub const fn standard() -> Configuration {
-    generate()
+    generate(NoLimit {})
 }
 
 /// Creates the "legacy" default config. This is the default config that was present in bincode 1.0
 /// - Little endian
 /// - Fixed int length encoding
 /// - Write fixed array length
+/// - Enum discriminants encoded as u32
+/// - Lengths encoded as u64
+/// - Non-canonical encoding
+/// - Lenient decoding
+/// - The last of any duplicate map keys wins
+/// - No depth limit
+/// - No maximum collection or string length
 pub const fn legacy() -> Configuration<LittleEndian, Fixint, WriteFixedArrayLength, NoLimit> {
-    generate()
+    generate(NoLimit {})
 }
 
-impl<E, I, A, L> Default for Configuration<E, I, A, L> {
+impl<E, I, A, L: Default, D, S, M, V, K, R, C, B> Default
+    for Configuration<E, I, A, L, D, S, M, V, K, R, C, B>
+{
     fn default() -> Self {
-        generate()
+        generate(L::default())
     }
 }
 
-const fn generate<E, I, A, L>() -> Configuration<E, I, A, L> {
+const fn generate<E, I, A, L, D, S, M, V, K, R, C, B>(
+    limit: L,
+) -> Configuration<E, I, A, L, D, S, M, V, K, R, C, B> {
     Configuration {
         _e: PhantomData,
         _i: PhantomData,
         _a: PhantomData,
-        _l: PhantomData,
+        limit,
+        _d: PhantomData,
+        _s: PhantomData,
+        _m: PhantomData,
+        _v: PhantomData,
+        _k: PhantomData,
+        _r: PhantomData,
+        _c: PhantomData,
+        _b: PhantomData,
     }
 }
 
-impl<E, I, A, L> Configuration<E, I, A, L> {
+impl<E, I, A, L: Copy, D, S, M, V, K, R, C, B> Configuration<E, I, A, L, D, S, M, V, K, R, C, B> {
     /// Makes bincode encode all integer types in big endian.
-    pub const fn with_big_endian(self) -> Configuration<BigEndian, I, A, L> {
-        generate()
+    pub const fn with_big_endian(
+        self,
+    ) -> Configuration<BigEndian, I, A, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Makes bincode encode all integer types in little endian.
-    pub const fn with_little_endian(self) -> Configuration<LittleEndian, I, A, L> {
-        generate()
+    pub const fn with_little_endian(
+        self,
+    ) -> Configuration<LittleEndian, I, A, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Makes bincode encode all integer types with a variable integer encoding.
@@ -155,8 +283,10 @@ impl<E, I, A, L> Configuration<E, I, A, L> {
     ///
     /// Note that u256 and the like are unsupported by this format; if and when they are added to the
     /// language, they may be supported via the extension point given by the 255 byte.
-    pub const fn with_variable_int_encoding(self) -> Configuration<E, Varint, A, L> {
-        generate()
+    pub const fn with_variable_int_encoding(
+        self,
+    ) -> Configuration<E, Varint, A, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Fixed-size integer encoding.
@@ -164,52 +294,717 @@ impl<E, I, A, L> Configuration<E, I, A, L> {
     /// * Fixed size integers are encoded directly
     /// * Enum discriminants are encoded as u32
     /// * Lengths and usize are encoded as u64
-    pub const fn with_fixed_int_encoding(self) -> Configuration<E, Fixint, A, L> {
-        generate()
+    pub const fn with_fixed_int_encoding(
+        self,
+    ) -> Configuration<E, Fixint, A, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Makes bincode encode all integer types with unsigned LEB128, the variable integer encoding that is used by e.g. protobuf and Go's `encoding/binary`.
+    ///
+    /// Every byte holds 7 bits of the value, starting with the least significant bits. The most significant bit of each byte is set if more bytes follow.
+    ///
+    /// Signed integers are first converted to unsigned integers with the zigzag algorithm, see [with_variable_int_encoding].
+    ///
+    /// * `u8` and `i8` are encoded directly as a single byte
+    /// * `usize` and `isize` are encoded as `u64` and `i64`
+    /// * The endianness of the configuration is ignored for integers, as LEB128 always starts with the least significant bits
+    ///
+    /// ```
+    /// let config = bincode::config::standard().with_leb128_int_encoding();
+    /// assert_eq!(bincode::encode_to_vec(300u32, config).unwrap(), [0xAC, 0x02]);
+    /// assert_eq!(bincode::encode_to_vec(-2i32, config).unwrap(), [3]);
+    /// ```
+    ///
+    /// [with_variable_int_encoding]: #method.with_variable_int_encoding
+    pub const fn with_leb128_int_encoding(
+        self,
+    ) -> Configuration<E, Leb128, A, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Skip writing the length of fixed size arrays (`[u8; N]`) before writing the array
     ///
     /// **NOTE:** This is not supported if you're using the `bincode::serde::*` functions, the `#[bincode(with_serde)]` attribute, or the `Compat` struct.
-    pub const fn skip_fixed_array_length(self) -> Configuration<E, I, SkipFixedArrayLength, L> {
-        generate()
+    pub const fn skip_fixed_array_length(
+        self,
+    ) -> Configuration<E, I, SkipFixedArrayLength, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Write the length of fixed size arrays (`[u8; N]`) before writing the array
-    pub const fn write_fixed_array_length(self) -> Configuration<E, I, WriteFixedArrayLength, L> {
-        generate()
+    pub const fn write_fixed_array_length(
+        self,
+    ) -> Configuration<E, I, WriteFixedArrayLength, L, D, S, M, V, K, R, C, B> {
+        generate(self.limit)
     }
 
     /// Sets the byte limit to `limit`.
-    pub const fn with_limit<const N: usize>(self) -> Configuration<E, I, A, Limit<N>> {
-        generate()
+    ///
+    /// When decoding, bincode will return `DecodeError::LimitExceeded` instead of allocating more than this amount of bytes.
+    /// When encoding, bincode will return `EncodeError::LimitExceeded` instead of writing more than this amount of bytes.
+    pub const fn with_limit<const N: usize>(
+        self,
+    ) -> Configuration<E, I, A, Limit<N>, D, S, M, V, K, R, C, B> {
+        generate(Limit {})
+    }
+
+    /// Sets the byte limit to `limit`, which is only known at runtime.
+    ///
+    /// This behaves exactly like [with_limit], but the limit is stored in the configuration instead of in its type.
+    ///
+    /// ```
+    /// # let max_message_size = 1024;
+    /// let config = bincode::config::standard().with_runtime_limit(max_message_size);
+    ///
+    /// let encoded = bincode::encode_to_vec(vec![0u8; 2048], bincode::config::standard()).unwrap();
+    /// let result: Result<(Vec<u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(bincode::error::DecodeError::LimitExceeded)));
+    /// ```
+    ///
+    /// [with_limit]: #method.with_limit
+    pub const fn with_runtime_limit(
+        self,
+        limit: usize,
+    ) -> Configuration<E, I, A, RuntimeLimit, D, S, M, V, K, R, C, B> {
+        generate(RuntimeLimit { limit })
     }
 
     /// Clear the byte limit.
-    pub const fn with_no_limit(self) -> Configuration<E, I, A, NoLimit> {
-        generate()
+    pub const fn with_no_limit(self) -> Configuration<E, I, A, NoLimit, D, S, M, V, K, R, C, B> {
+        generate(NoLimit {})
+    }
+
+    /// Encode enum discriminants as a `u32`, using the integer encoding of this configuration. This is the default.
+    pub const fn with_u32_discriminants(
+        self,
+    ) -> Configuration<E, I, A, L, U32Discriminant, S, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode enum discriminants as a single `u8`, regardless of the integer encoding of this configuration.
+    ///
+    /// Encoding an enum with a discriminant larger than `u8::MAX` will fail with `EncodeError::DiscriminantOutOfRange`.
+    pub const fn with_u8_discriminants(
+        self,
+    ) -> Configuration<E, I, A, L, U8Discriminant, S, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode enum discriminants as a variable-length `u32`, regardless of the integer encoding of this configuration. See [with_variable_int_encoding] for the format that is used.
+    ///
+    /// This is useful in combination with [with_fixed_int_encoding], as every discriminant below 251 will be encoded as a single byte.
+    ///
+    /// [with_variable_int_encoding]: #method.with_variable_int_encoding
+    /// [with_fixed_int_encoding]: #method.with_fixed_int_encoding
+    pub const fn with_varint_discriminants(
+        self,
+    ) -> Configuration<E, I, A, L, VarintDiscriminant, S, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode the length of collections, strings and slices as a `u64`, using the integer encoding of this configuration. This is the default.
+    pub const fn with_u64_lengths(
+        self,
+    ) -> Configuration<E, I, A, L, D, U64Length, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode the length of collections, strings and slices as a fixed-size `u32`, regardless of the integer encoding of this configuration.
+    ///
+    /// Encoding a collection with more than `u32::MAX` items will fail with `EncodeError::LengthOutOfRange`.
+    pub const fn with_fixed_u32_lengths(
+        self,
+    ) -> Configuration<E, I, A, L, D, FixedU32Length, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode the length of collections, strings and slices as a fixed-size `u64`, regardless of the integer encoding of this configuration.
+    pub const fn with_fixed_u64_lengths(
+        self,
+    ) -> Configuration<E, I, A, L, D, FixedU64Length, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode the length of collections, strings and slices as a variable-length `u64`, regardless of the integer encoding of this configuration. See [with_variable_int_encoding] for the format that is used.
+    ///
+    /// [with_variable_int_encoding]: #method.with_variable_int_encoding
+    pub const fn with_varint_lengths(
+        self,
+    ) -> Configuration<E, I, A, L, D, VarintLength, M, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Guarantee that equal values are always encoded to the same bytes, across runs and platforms. This is useful when the encoded bytes are hashed or signed.
+    ///
+    /// With this option:
+    /// - The entries of unordered collections like `HashMap` and `HashSet` are sorted by their encoded bytes. For maps, only the encoded bytes of the key are compared.
+    /// - All `NaN` values of `f32` and `f64` are encoded as the same quiet `NaN`, with the bit patterns `0x7FC0_0000` and `0x7FF8_0000_0000_0000` respectively.
+    /// - `AlignedSlice` is encoded without padding, because the padding depends on where in the output the value is written. Its values can then only be borrowed if they happen to be aligned.
+    ///
+    /// Note that serde has no way to tell unordered collections apart from ordered ones, so the `serde` functions only normalize `NaN` values.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use std::collections::HashSet;
+    ///
+    /// let config = bincode::config::standard().with_canonical_encoding();
+    /// let a: HashSet<u32> = (0..100).collect();
+    /// let b: HashSet<u32> = (0..100).rev().collect();
+    /// assert_eq!(
+    ///     bincode::encode_to_vec(&a, config).unwrap(),
+    ///     bincode::encode_to_vec(&b, config).unwrap()
+    /// );
+    /// # }
+    /// ```
+    pub const fn with_canonical_encoding(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, Canonical, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Encode unordered collections in their iteration order, and encode `NaN` values as-is. This is the default.
+    pub const fn with_non_canonical_encoding(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, NonCanonical, V, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Reject any input that is not encoded exactly the way bincode would encode it. Combined with [with_canonical_encoding], this guarantees that any value that decodes successfully re-encodes to the identical bytes.
+    ///
+    /// With this option, decoding fails when:
+    /// - A variable-length integer is encoded with more bytes than needed, with `DecodeError::NonCanonicalInteger`.
+    /// - The keys of a `HashMap` or `HashSet` are not sorted by their encoded bytes, or the keys of a `BTreeMap` or `BTreeSet` are not sorted by their `Ord` implementation, with `DecodeError::UnsortedKeys`. This includes duplicate keys.
+    /// - A `NaN` is not encoded as the canonical `NaN` described in [with_canonical_encoding], with `DecodeError::NonCanonicalNaN`.
+    /// - A `Duration` has more than `999_999_999` nanoseconds, with `DecodeError::InvalidDuration`.
+    ///
+    /// Note that serde has no way to tell unordered collections apart from ordered ones, so the `serde` functions do not check the order of keys.
+    ///
+    /// ```
+    /// # use bincode::error::{DecodeError, IntegerType};
+    /// let config = bincode::config::standard().with_strict_decoding();
+    ///
+    /// // 5 encoded as a `u16` with a 3 byte varint, instead of a single byte
+    /// let result: Result<(u16, usize), _> = bincode::decode_from_slice(&[251, 5, 0], config);
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(DecodeError::NonCanonicalInteger { integer_type: IntegerType::U16 })
+    /// ));
+    /// ```
+    ///
+    /// [with_canonical_encoding]: #method.with_canonical_encoding
+    pub const fn with_strict_decoding(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, StrictDecoding, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Accept input that is valid, but not encoded the way bincode would encode it, e.g. integers that are encoded with more bytes than needed. This is the default.
+    pub const fn with_lenient_decoding(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, LenientDecoding, K, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// When decoding a map or set that contains the same key more than once, keep the last entry. This is the default.
+    ///
+    /// This applies to `HashMap`, `HashSet`, `BTreeMap` and `BTreeSet`.
+    pub const fn with_last_key_wins(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, LastKeyWins, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// When decoding a map or set that contains the same key more than once, keep the first entry and ignore the others.
+    ///
+    /// This applies to `HashMap`, `HashSet`, `BTreeMap` and `BTreeSet`.
+    pub const fn with_first_key_wins(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, FirstKeyWins, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// When decoding a map or set that contains the same key more than once, fail with `DecodeError::DuplicateMapKey`.
+    ///
+    /// This applies to `HashMap`, `HashSet`, `BTreeMap` and `BTreeSet`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use bincode::error::DecodeError;
+    /// use std::collections::BTreeMap;
+    ///
+    /// // A map with 2 entries, both with key 1
+    /// let encoded = [2, 1, 10, 1, 20];
+    ///
+    /// let config = bincode::config::standard();
+    /// let (map, _): (BTreeMap<u8, u8>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    /// assert_eq!(map[&1], 20);
+    ///
+    /// let config = bincode::config::standard().with_duplicate_key_error();
+    /// let result: Result<(BTreeMap<u8, u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(DecodeError::DuplicateMapKey { index: 1 })));
+    /// # }
+    /// ```
+    pub const fn with_duplicate_key_error(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, DuplicateKeyError, R, C, B> {
+        generate(self.limit)
+    }
+
+    /// Sets the maximum nesting depth to `N`.
+    ///
+    /// Every struct, enum, collection and smart pointer that is being decoded counts as one level of nesting. When decoding a value that is nested more than `N` levels deep, bincode will return `DecodeError::DepthLimitExceeded` instead of recursing further. This prevents malicious input from overflowing the stack when decoding recursive types.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use bincode::error::DecodeError;
+    ///
+    /// // 4 nested `Box`es around a `u8`
+    /// let encoded = [5];
+    /// let config = bincode::config::standard().with_depth_limit::<4>();
+    ///
+    /// let result: Result<(Box<Box<Box<Box<u8>>>>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert_eq!(****result.unwrap().0, 5);
+    ///
+    /// let result: Result<(Box<Box<Box<Box<Box<u8>>>>>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(DecodeError::DepthLimitExceeded { limit: 4 })));
+    /// # }
+    /// ```
+    pub const fn with_depth_limit<const N: usize>(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, DepthLimit<N>, C, B> {
+        generate(self.limit)
+    }
+
+    /// Clear the maximum nesting depth. This is the default.
+    pub const fn with_no_depth_limit(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, NoDepthLimit, C, B> {
+        generate(self.limit)
+    }
+
+    /// Sets the maximum amount of entries of a collection to `N`.
+    ///
+    /// When decoding a `Vec`, `VecDeque`, `BinaryHeap`, map, set or serde sequence or map with more than `N` entries, bincode will return `DecodeError::CollectionTooLong` before decoding any of its entries.
+    ///
+    /// Strings and byte slices are limited by [with_max_string_len] instead. This includes `Vec<u8>`, `Box<[u8]>` and `VecDeque<u8>`, which have the same encoding as `&[u8]`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use bincode::error::DecodeError;
+    ///
+    /// let encoded = bincode::encode_to_vec(vec![0u32; 5], bincode::config::standard()).unwrap();
+    ///
+    /// let config = bincode::config::standard().with_max_collection_len::<4>();
+    /// let result: Result<(Vec<u32>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(DecodeError::CollectionTooLong { len: 5, max: 4 })));
+    /// # }
+    /// ```
+    ///
+    /// [with_max_string_len]: #method.with_max_string_len
+    pub const fn with_max_collection_len<const N: usize>(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, R, MaxLen<N>, B> {
+        generate(self.limit)
+    }
+
+    /// Clear the maximum amount of entries of a collection. This is the default.
+    pub const fn with_no_max_collection_len(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, R, NoMaxLen, B> {
+        generate(self.limit)
+    }
+
+    /// Sets the maximum length of a string or byte slice to `N` bytes.
+    ///
+    /// When decoding a `String`, `&str`, `CString`, `PathBuf`, `&[u8]`, `Vec<u8>`, `Box<[u8]>`, `VecDeque<u8>` or serde string or byte buffer that is longer than `N` bytes, bincode will return `DecodeError::StringTooLong` before reading its contents.
+    ///
+    /// A type that is deserialized through serde as a sequence, like serde's implementation for `Vec<u8>`, is limited by [with_max_collection_len] instead, because bincode can not tell that it holds bytes.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use bincode::error::DecodeError;
+    ///
+    /// let encoded = bincode::encode_to_vec("Hello", bincode::config::standard()).unwrap();
+    ///
+    /// let config = bincode::config::standard().with_max_string_len::<4>();
+    /// let result: Result<(String, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(DecodeError::StringTooLong { len: 5, max: 4 })));
+    /// let result: Result<(Vec<u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    /// assert!(matches!(result, Err(DecodeError::StringTooLong { len: 5, max: 4 })));
+    /// # }
+    /// ```
+    ///
+    /// [with_max_collection_len]: #method.with_max_collection_len
+    pub const fn with_max_string_len<const N: usize>(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, R, C, MaxLen<N>> {
+        generate(self.limit)
+    }
+
+    /// Clear the maximum length of a string or byte slice. This is the default.
+    pub const fn with_no_max_string_len(
+        self,
+    ) -> Configuration<E, I, A, L, D, S, M, V, K, R, C, NoMaxLen> {
+        generate(self.limit)
     }
 }
 
 /// Indicates a type is valid for controlling the bincode configuration
-pub trait Config:
-    InternalEndianConfig
-    + InternalArrayLengthConfig
-    + InternalIntEncodingConfig
-    + InternalLimitConfig
-    + Copy
-    + Clone
+pub trait Config: InternalConfig + Copy + Clone {}
+
+impl<T> Config for T where T: InternalConfig + Copy + Clone {}
+
+/// A [Config] whose options are known at compile time. This is implemented for every [Configuration], but not for [DynamicConfig].
+///
+/// This is used by [MaxEncodedSize] to calculate the maximum encoded size of a type at compile time.
+///
+/// [MaxEncodedSize]: ../enc/trait.MaxEncodedSize.html
+pub trait StaticConfig: Config + InternalStaticConfig {}
+
+impl<T> StaticConfig for T where T: Config + InternalStaticConfig {}
+
+impl<E, I, A, L, D, S, M, V, K, R, C, B> InternalStaticConfig
+    for Configuration<E, I, A, L, D, S, M, V, K, R, C, B>
+where
+    I: InternalIntEncodingConfig,
+    A: InternalArrayLengthConfig,
+    D: InternalDiscriminantConfig,
+    S: InternalLengthConfig,
+{
+    const INT_ENCODING: IntEncoding = I::INT_ENCODING;
+    const SKIP_FIXED_ARRAY_LENGTH: bool = A::SKIP_FIXED_ARRAY_LENGTH;
+    const DISCRIMINANT_ENCODING: DiscriminantEncoding = D::DISCRIMINANT_ENCODING;
+    const LENGTH_ENCODING: LengthEncoding = S::LENGTH_ENCODING;
+}
+
+impl<E, I, A, L, D, S, M, V, K, R, C, B> InternalConfig
+    for Configuration<E, I, A, L, D, S, M, V, K, R, C, B>
+where
+    E: InternalEndianConfig,
+    I: InternalIntEncodingConfig,
+    A: InternalArrayLengthConfig,
+    L: InternalLimitConfig,
+    D: InternalDiscriminantConfig,
+    S: InternalLengthConfig,
+    M: InternalCanonicalConfig,
+    V: InternalStrictConfig,
+    K: InternalDuplicateKeyConfig,
+    R: InternalDepthLimitConfig,
+    C: InternalMaxLenConfig,
+    B: InternalMaxLenConfig,
 {
+    #[inline(always)]
+    fn endian(&self) -> Endian {
+        E::ENDIAN
+    }
+
+    #[inline(always)]
+    fn int_encoding(&self) -> IntEncoding {
+        I::INT_ENCODING
+    }
+
+    #[inline(always)]
+    fn skips_fixed_array_length(&self) -> bool {
+        A::SKIP_FIXED_ARRAY_LENGTH
+    }
+
+    #[inline(always)]
+    fn limit(&self) -> Option<usize> {
+        self.limit.limit()
+    }
+
+    #[inline(always)]
+    fn discriminant_encoding(&self) -> DiscriminantEncoding {
+        D::DISCRIMINANT_ENCODING
+    }
+
+    #[inline(always)]
+    fn length_encoding(&self) -> LengthEncoding {
+        S::LENGTH_ENCODING
+    }
+
+    #[inline(always)]
+    fn is_canonical(&self) -> bool {
+        M::CANONICAL
+    }
+
+    #[inline(always)]
+    fn is_strict(&self) -> bool {
+        V::STRICT
+    }
+
+    #[inline(always)]
+    fn duplicate_key_policy(&self) -> DuplicateKeyPolicy {
+        K::DUPLICATE_KEY_POLICY
+    }
+
+    #[inline(always)]
+    fn depth_limit(&self) -> Option<usize> {
+        R::DEPTH_LIMIT
+    }
+
+    #[inline(always)]
+    fn max_collection_len(&self) -> Option<usize> {
+        C::MAX_LEN
+    }
+
+    #[inline(always)]
+    fn max_string_len(&self) -> Option<usize> {
+        B::MAX_LEN
+    }
+}
+
+/// A bincode configuration of which the options are chosen at runtime.
+///
+/// This supports the same options as [Configuration], but instead of encoding them in the type they are stored in the fields of this struct. This allows a single compiled code path to encode and decode with any combination of options, e.g. one that is read from a config file or negotiated during a handshake.
+///
+/// ```
+/// use bincode::config::{DynamicConfig, Endian, IntEncoding};
+///
+/// let mut config = DynamicConfig::legacy();
+/// config.endian = Endian::Big;
+///
+/// let encoded = bincode::encode_to_vec(5u16, config).unwrap();
+/// assert_eq!(encoded, [0, 5]);
+///
+/// // A `Configuration` can be converted into a `DynamicConfig` with the same options
+/// let config = DynamicConfig::from(bincode::config::standard().with_big_endian());
+/// assert_eq!(config.int_encoding, IntEncoding::Variable);
+/// ```
+///
+/// Because the options are only known at runtime, the compiler is not able to remove the checks for them like it does for [Configuration]. Prefer [Configuration] if the options are known at compile time.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DynamicConfig {
+    /// The endianness that integers are encoded in. See [Configuration::with_big_endian] and [Configuration::with_little_endian].
+    pub endian: Endian,
+    /// The way integers are encoded. See [Configuration::with_variable_int_encoding], [Configuration::with_fixed_int_encoding] and [Configuration::with_leb128_int_encoding].
+    pub int_encoding: IntEncoding,
+    /// If `true`, the length of fixed size arrays is not written. See [Configuration::skip_fixed_array_length].
+    pub skip_fixed_array_length: bool,
+    /// The maximum amount of bytes that may be read while decoding, or written while encoding. See [Configuration::with_limit].
+    pub limit: Option<usize>,
+    /// The way enum discriminants are encoded. See [Configuration::with_u8_discriminants] and [Configuration::with_varint_discriminants].
+    pub discriminant_encoding: DiscriminantEncoding,
+    /// The way the length of collections, strings and slices is encoded. See [Configuration::with_fixed_u32_lengths], [Configuration::with_fixed_u64_lengths] and [Configuration::with_varint_lengths].
+    pub length_encoding: LengthEncoding,
+    /// If `true`, equal values are always encoded to the same bytes. See [Configuration::with_canonical_encoding].
+    pub canonical: bool,
+    /// If `true`, input that is not encoded exactly the way bincode would encode it is rejected. See [Configuration::with_strict_decoding].
+    pub strict_decoding: bool,
+    /// What to do with duplicate keys in maps and sets. See [Configuration::with_first_key_wins] and [Configuration::with_duplicate_key_error].
+    pub duplicate_key_policy: DuplicateKeyPolicy,
+    /// The maximum nesting depth while decoding. See [Configuration::with_depth_limit].
+    pub depth_limit: Option<usize>,
+    /// The maximum amount of entries of a collection while decoding. See [Configuration::with_max_collection_len].
+    pub max_collection_len: Option<usize>,
+    /// The maximum length of a string or byte slice while decoding. See [Configuration::with_max_string_len].
+    pub max_string_len: Option<usize>,
+}
+
+impl DynamicConfig {
+    /// The same options as [standard]:
+    /// - Little endian
+    /// - Variable int encoding
+    /// - Write fixed array length
+    /// - Enum discriminants encoded as u32
+    /// - Lengths encoded as u64
+    /// - Non-canonical encoding
+    /// - Lenient decoding
+    /// - The last of any duplicate map keys wins
+    /// - No depth limit
+    /// - No maximum collection or string length
+    pub const fn standard() -> Self {
+        Self {
+            endian: Endian::Little,
+            int_encoding: IntEncoding::Variable,
+            skip_fixed_array_length: false,
+            limit: None,
+            discriminant_encoding: DiscriminantEncoding::U32,
+            length_encoding: LengthEncoding::U64,
+            canonical: false,
+            strict_decoding: false,
+            duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+            depth_limit: None,
+            max_collection_len: None,
+            max_string_len: None,
+        }
+    }
+
+    /// The same options as [legacy]:
+    /// - Little endian
+    /// - Fixed int length encoding
+    /// - Write fixed array length
+    /// - Enum discriminants encoded as u32
+    /// - Lengths encoded as u64
+    /// - Non-canonical encoding
+    /// - Lenient decoding
+    /// - The last of any duplicate map keys wins
+    /// - No depth limit
+    /// - No maximum collection or string length
+    pub const fn legacy() -> Self {
+        Self {
+            endian: Endian::Little,
+            int_encoding: IntEncoding::Fixed,
+            skip_fixed_array_length: false,
+            limit: None,
+            discriminant_encoding: DiscriminantEncoding::U32,
+            length_encoding: LengthEncoding::U64,
+            canonical: false,
+            strict_decoding: false,
+            duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+            depth_limit: None,
+            max_collection_len: None,
+            max_string_len: None,
+        }
+    }
 }
 
-impl<T> Config for T where
-    T: InternalEndianConfig
-        + InternalArrayLengthConfig
-        + InternalIntEncodingConfig
-        + InternalLimitConfig
-        + Copy
-        + Clone
+impl Default for DynamicConfig {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl<E, I, A, L, D, S, M, V, K, R, C, B> From<Configuration<E, I, A, L, D, S, M, V, K, R, C, B>>
+    for DynamicConfig
+where
+    Configuration<E, I, A, L, D, S, M, V, K, R, C, B>: Config,
 {
+    fn from(config: Configuration<E, I, A, L, D, S, M, V, K, R, C, B>) -> Self {
+        Self {
+            endian: config.endian(),
+            int_encoding: config.int_encoding(),
+            skip_fixed_array_length: config.skips_fixed_array_length(),
+            limit: config.limit(),
+            discriminant_encoding: config.discriminant_encoding(),
+            length_encoding: config.length_encoding(),
+            canonical: config.is_canonical(),
+            strict_decoding: config.is_strict(),
+            duplicate_key_policy: config.duplicate_key_policy(),
+            depth_limit: config.depth_limit(),
+            max_collection_len: config.max_collection_len(),
+            max_string_len: config.max_string_len(),
+        }
+    }
+}
+
+impl InternalConfig for DynamicConfig {
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    #[inline]
+    fn int_encoding(&self) -> IntEncoding {
+        self.int_encoding
+    }
+
+    #[inline]
+    fn skips_fixed_array_length(&self) -> bool {
+        self.skip_fixed_array_length
+    }
+
+    #[inline]
+    fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    #[inline]
+    fn discriminant_encoding(&self) -> DiscriminantEncoding {
+        self.discriminant_encoding
+    }
+
+    #[inline]
+    fn length_encoding(&self) -> LengthEncoding {
+        self.length_encoding
+    }
+
+    #[inline]
+    fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    #[inline]
+    fn is_strict(&self) -> bool {
+        self.strict_decoding
+    }
+
+    #[inline]
+    fn duplicate_key_policy(&self) -> DuplicateKeyPolicy {
+        self.duplicate_key_policy
+    }
+
+    #[inline]
+    fn depth_limit(&self) -> Option<usize> {
+        self.depth_limit
+    }
+
+    #[inline]
+    fn max_collection_len(&self) -> Option<usize> {
+        self.max_collection_len
+    }
+
+    #[inline]
+    fn max_string_len(&self) -> Option<usize> {
+        self.max_string_len
+    }
+}
+
+/// The endianness that integers are encoded in. This is the runtime counterpart of [BigEndian] and [LittleEndian].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Encode all integer types in little endian.
+    Little,
+    /// Encode all integer types in big endian.
+    Big,
+}
+
+/// The way integers are encoded. This is the runtime counterpart of [Fixint], [Varint] and [Leb128].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IntEncoding {
+    /// Use fixed-size integer encoding. See [Configuration::with_fixed_int_encoding].
+    Fixed,
+    /// Use variable integer encoding. See [Configuration::with_variable_int_encoding].
+    Variable,
+    /// Use unsigned LEB128 integer encoding. See [Configuration::with_leb128_int_encoding].
+    Leb128,
+}
+
+/// The way enum discriminants are encoded. This is the runtime counterpart of [U32Discriminant], [U8Discriminant] and [VarintDiscriminant].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiscriminantEncoding {
+    /// Encode discriminants as a `u32` with the configured integer encoding. See [Configuration::with_u32_discriminants].
+    U32,
+    /// Encode discriminants as a single `u8`. See [Configuration::with_u8_discriminants].
+    U8,
+    /// Encode discriminants as a variable-length `u32`. See [Configuration::with_varint_discriminants].
+    Varint,
+}
+
+/// The way the length of collections, strings and slices is encoded. This is the runtime counterpart of [U64Length], [FixedU32Length], [FixedU64Length] and [VarintLength].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LengthEncoding {
+    /// Encode lengths as a `u64` with the configured integer encoding. See [Configuration::with_u64_lengths].
+    U64,
+    /// Encode lengths as a fixed-size `u32`. See [Configuration::with_fixed_u32_lengths].
+    FixedU32,
+    /// Encode lengths as a fixed-size `u64`. See [Configuration::with_fixed_u64_lengths].
+    FixedU64,
+    /// Encode lengths as a variable-length `u64`. See [Configuration::with_varint_lengths].
+    Varint,
+}
+
+/// What to do with duplicate keys when decoding maps and sets. This is the runtime counterpart of [LastKeyWins], [FirstKeyWins] and [DuplicateKeyError].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the last entry. See [Configuration::with_last_key_wins].
+    LastWins,
+    /// Keep the first entry. See [Configuration::with_first_key_wins].
+    FirstWins,
+    /// Fail with `DecodeError::DuplicateMapKey`. See [Configuration::with_duplicate_key_error].
+    Error,
 }
 
 /// Encodes all integer types in big endian.
@@ -244,6 +1039,14 @@ impl InternalIntEncodingConfig for Varint {
     const INT_ENCODING: IntEncoding = IntEncoding::Variable;
 }
 
+/// Use unsigned LEB128 integer encoding.
+#[derive(Copy, Clone)]
+pub struct Leb128 {}
+
+impl InternalIntEncodingConfig for Leb128 {
+    const INT_ENCODING: IntEncoding = IntEncoding::Leb128;
+}
+
 /// Skip writing the length of fixed size arrays (`[u8; N]`) before writing the array.
 #[derive(Copy, Clone)]
 pub struct SkipFixedArrayLength {}
@@ -261,67 +1064,263 @@ impl InternalArrayLengthConfig for WriteFixedArrayLength {
 }
 
 /// Sets an unlimited byte limit.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub struct NoLimit {}
 impl InternalLimitConfig for NoLimit {
-    const LIMIT: Option<usize> = None;
+    #[inline(always)]
+    fn limit(&self) -> Option<usize> {
+        None
+    }
 }
 
 /// Sets the byte limit to N.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub struct Limit<const N: usize> {}
 impl<const N: usize> InternalLimitConfig for Limit<N> {
-    const LIMIT: Option<usize> = Some(N);
+    #[inline(always)]
+    fn limit(&self) -> Option<usize> {
+        Some(N)
+    }
+}
+
+/// Sets the byte limit to a value that is only known at runtime. See [Configuration::with_runtime_limit].
+#[derive(Copy, Clone)]
+pub struct RuntimeLimit {
+    limit: usize,
+}
+impl InternalLimitConfig for RuntimeLimit {
+    #[inline(always)]
+    fn limit(&self) -> Option<usize> {
+        Some(self.limit)
+    }
+}
+
+/// Encode enum discriminants as a `u32` with the configured integer encoding.
+#[derive(Copy, Clone)]
+pub struct U32Discriminant {}
+
+impl InternalDiscriminantConfig for U32Discriminant {
+    const DISCRIMINANT_ENCODING: DiscriminantEncoding = DiscriminantEncoding::U32;
+}
+
+/// Encode enum discriminants as a single `u8`.
+#[derive(Copy, Clone)]
+pub struct U8Discriminant {}
+
+impl InternalDiscriminantConfig for U8Discriminant {
+    const DISCRIMINANT_ENCODING: DiscriminantEncoding = DiscriminantEncoding::U8;
+}
+
+/// Encode enum discriminants as a variable-length `u32`.
+#[derive(Copy, Clone)]
+pub struct VarintDiscriminant {}
+
+impl InternalDiscriminantConfig for VarintDiscriminant {
+    const DISCRIMINANT_ENCODING: DiscriminantEncoding = DiscriminantEncoding::Varint;
+}
+
+/// Encode lengths as a `u64` with the configured integer encoding.
+#[derive(Copy, Clone)]
+pub struct U64Length {}
+
+impl InternalLengthConfig for U64Length {
+    const LENGTH_ENCODING: LengthEncoding = LengthEncoding::U64;
+}
+
+/// Encode lengths as a fixed-size `u32`.
+#[derive(Copy, Clone)]
+pub struct FixedU32Length {}
+
+impl InternalLengthConfig for FixedU32Length {
+    const LENGTH_ENCODING: LengthEncoding = LengthEncoding::FixedU32;
+}
+
+/// Encode lengths as a fixed-size `u64`.
+#[derive(Copy, Clone)]
+pub struct FixedU64Length {}
+
+impl InternalLengthConfig for FixedU64Length {
+    const LENGTH_ENCODING: LengthEncoding = LengthEncoding::FixedU64;
+}
+
+/// Encode lengths as a variable-length `u64`.
+#[derive(Copy, Clone)]
+pub struct VarintLength {}
+
+impl InternalLengthConfig for VarintLength {
+    const LENGTH_ENCODING: LengthEncoding = LengthEncoding::Varint;
+}
+
+/// Encode unordered collections in their iteration order, and encode `NaN` values as-is.
+#[derive(Copy, Clone)]
+pub struct NonCanonical {}
+
+impl InternalCanonicalConfig for NonCanonical {
+    const CANONICAL: bool = false;
+}
+
+/// Guarantee that equal values are always encoded to the same bytes.
+#[derive(Copy, Clone)]
+pub struct Canonical {}
+
+impl InternalCanonicalConfig for Canonical {
+    const CANONICAL: bool = true;
+}
+
+/// Accept input that is not encoded the way bincode would encode it.
+#[derive(Copy, Clone)]
+pub struct LenientDecoding {}
+
+impl InternalStrictConfig for LenientDecoding {
+    const STRICT: bool = false;
+}
+
+/// Reject input that is not encoded exactly the way bincode would encode it.
+#[derive(Copy, Clone)]
+pub struct StrictDecoding {}
+
+impl InternalStrictConfig for StrictDecoding {
+    const STRICT: bool = true;
+}
+
+/// Keep the last entry when decoding duplicate map keys.
+#[derive(Copy, Clone)]
+pub struct LastKeyWins {}
+
+impl InternalDuplicateKeyConfig for LastKeyWins {
+    const DUPLICATE_KEY_POLICY: DuplicateKeyPolicy = DuplicateKeyPolicy::LastWins;
+}
+
+/// Keep the first entry when decoding duplicate map keys.
+#[derive(Copy, Clone)]
+pub struct FirstKeyWins {}
+
+impl InternalDuplicateKeyConfig for FirstKeyWins {
+    const DUPLICATE_KEY_POLICY: DuplicateKeyPolicy = DuplicateKeyPolicy::FirstWins;
+}
+
+/// Fail when decoding duplicate map keys.
+#[derive(Copy, Clone)]
+pub struct DuplicateKeyError {}
+
+impl InternalDuplicateKeyConfig for DuplicateKeyError {
+    const DUPLICATE_KEY_POLICY: DuplicateKeyPolicy = DuplicateKeyPolicy::Error;
+}
+
+/// Sets an unlimited nesting depth.
+#[derive(Copy, Clone)]
+pub struct NoDepthLimit {}
+
+impl InternalDepthLimitConfig for NoDepthLimit {
+    const DEPTH_LIMIT: Option<usize> = None;
+}
+
+/// Sets the maximum nesting depth to N.
+#[derive(Copy, Clone)]
+pub struct DepthLimit<const N: usize> {}
+
+impl<const N: usize> InternalDepthLimitConfig for DepthLimit<N> {
+    const DEPTH_LIMIT: Option<usize> = Some(N);
+}
+
+/// Sets an unlimited collection or string length.
+#[derive(Copy, Clone)]
+pub struct NoMaxLen {}
+
+impl InternalMaxLenConfig for NoMaxLen {
+    const MAX_LEN: Option<usize> = None;
+}
+
+/// Sets the maximum collection or string length to N.
+#[derive(Copy, Clone)]
+pub struct MaxLen<const N: usize> {}
+
+impl<const N: usize> InternalMaxLenConfig for MaxLen<N> {
+    const MAX_LEN: Option<usize> = Some(N);
 }
 
 mod internal {
-    use super::Configuration;
+    use super::{DiscriminantEncoding, DuplicateKeyPolicy, Endian, IntEncoding, LengthEncoding};
 
     pub trait InternalEndianConfig {
         const ENDIAN: Endian;
     }
 
-    impl<E: InternalEndianConfig, I, A, L> InternalEndianConfig for Configuration<E, I, A, L> {
-        const ENDIAN: Endian = E::ENDIAN;
+    pub trait InternalIntEncodingConfig {
+        const INT_ENCODING: IntEncoding;
+    }
+
+    pub trait InternalArrayLengthConfig {
+        const SKIP_FIXED_ARRAY_LENGTH: bool;
     }
 
-    #[derive(PartialEq, Eq)]
-    pub enum Endian {
-        Little,
-        Big,
+    pub trait InternalLimitConfig {
+        fn limit(&self) -> Option<usize>;
     }
 
-    pub trait InternalIntEncodingConfig {
-        const INT_ENCODING: IntEncoding;
+    pub trait InternalDiscriminantConfig {
+        const DISCRIMINANT_ENCODING: DiscriminantEncoding;
     }
 
-    impl<E, I: InternalIntEncodingConfig, A, L> InternalIntEncodingConfig
-        for Configuration<E, I, A, L>
-    {
-        const INT_ENCODING: IntEncoding = I::INT_ENCODING;
+    pub trait InternalLengthConfig {
+        const LENGTH_ENCODING: LengthEncoding;
     }
 
-    #[derive(PartialEq, Eq)]
-    pub enum IntEncoding {
-        Fixed,
-        Variable,
+    pub trait InternalCanonicalConfig {
+        const CANONICAL: bool;
     }
 
-    pub trait InternalArrayLengthConfig {
-        const SKIP_FIXED_ARRAY_LENGTH: bool;
+    pub trait InternalStrictConfig {
+        const STRICT: bool;
     }
 
-    impl<E, I, A: InternalArrayLengthConfig, L> InternalArrayLengthConfig
-        for Configuration<E, I, A, L>
-    {
-        const SKIP_FIXED_ARRAY_LENGTH: bool = A::SKIP_FIXED_ARRAY_LENGTH;
+    pub trait InternalDuplicateKeyConfig {
+        const DUPLICATE_KEY_POLICY: DuplicateKeyPolicy;
     }
 
-    pub trait InternalLimitConfig {
-        const LIMIT: Option<usize>;
+    pub trait InternalDepthLimitConfig {
+        const DEPTH_LIMIT: Option<usize>;
     }
 
-    impl<E, I, A, L: InternalLimitConfig> InternalLimitConfig for Configuration<E, I, A, L> {
-        const LIMIT: Option<usize> = L::LIMIT;
+    pub trait InternalMaxLenConfig {
+        const MAX_LEN: Option<usize>;
+    }
+
+    /// Gives access to the options of a config. For `Configuration` these all return constants, so any checks on them get compiled away.
+    pub trait InternalConfig {
+        fn endian(&self) -> Endian;
+
+        fn int_encoding(&self) -> IntEncoding;
+
+        fn skips_fixed_array_length(&self) -> bool;
+
+        fn limit(&self) -> Option<usize>;
+
+        fn discriminant_encoding(&self) -> DiscriminantEncoding;
+
+        fn length_encoding(&self) -> LengthEncoding;
+
+        fn is_canonical(&self) -> bool;
+
+        fn is_strict(&self) -> bool;
+
+        fn duplicate_key_policy(&self) -> DuplicateKeyPolicy;
+
+        fn depth_limit(&self) -> Option<usize>;
+
+        fn max_collection_len(&self) -> Option<usize>;
+
+        fn max_string_len(&self) -> Option<usize>;
+    }
+
+    /// The options of a config that are needed to calculate the maximum encoded size of a type at compile time.
+    pub trait InternalStaticConfig {
+        const INT_ENCODING: IntEncoding;
+
+        const SKIP_FIXED_ARRAY_LENGTH: bool;
+
+        const DISCRIMINANT_ENCODING: DiscriminantEncoding;
+
+        const LENGTH_ENCODING: LengthEncoding;
     }
 }
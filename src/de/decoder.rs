@@ -24,6 +24,7 @@ pub struct DecoderImpl<R, C: Config> {
     reader: R,
     config: C,
     bytes_read: usize,
+    depth: usize,
 }
 
 impl<R: Reader, C: Config> DecoderImpl<R, C> {
@@ -33,6 +34,7 @@ impl<R: Reader, C: Config> DecoderImpl<R, C> {
             reader,
             config,
             bytes_read: 0,
+            depth: 0,
         }
     }
 }
@@ -62,8 +64,8 @@ impl<R: Reader, C: Config> Decoder for DecoderImpl<R, C> {
 
     #[inline]
     fn claim_bytes_read(&mut self, n: usize) -> Result<(), DecodeError> {
-        // C::LIMIT is a const so this check should get compiled away
-        if let Some(limit) = C::LIMIT {
+        // For `Configuration` the limit is a const so this check should get compiled away
+        if let Some(limit) = self.config.limit() {
             // Make sure we don't accidentally overflow `bytes_read`
             self.bytes_read = self
                 .bytes_read
@@ -81,10 +83,31 @@ impl<R: Reader, C: Config> Decoder for DecoderImpl<R, C> {
 
     #[inline]
     fn unclaim_bytes_read(&mut self, n: usize) {
-        // C::LIMIT is a const so this check should get compiled away
-        if C::LIMIT.is_some() {
+        // For `Configuration` the limit is a const so this check should get compiled away
+        if self.config.limit().is_some() {
             // We should always be claiming more than we unclaim, so this should never underflow
             self.bytes_read -= n;
         }
     }
+
+    #[inline]
+    fn claim_depth(&mut self) -> Result<(), DecodeError> {
+        // For `Configuration` the depth limit is a const so this check should get compiled away
+        if let Some(limit) = self.config.depth_limit() {
+            if self.depth >= limit {
+                return Err(DecodeError::DepthLimitExceeded { limit });
+            }
+            self.depth += 1;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn unclaim_depth(&mut self) {
+        // For `Configuration` the depth limit is a const so this check should get compiled away
+        if self.config.depth_limit().is_some() {
+            // We should always be claiming more than we unclaim, so this should never underflow
+            self.depth -= 1;
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::{BorrowDecode, BorrowDecoder, Decode, Decoder};
+use super::{BorrowDecode, BorrowDecoder, Decode, DecodeInPlace, Decoder};
 use crate::error::DecodeError;
 
 macro_rules! impl_tuple {
@@ -33,6 +33,23 @@ macro_rules! impl_tuple {
                 ))
             }
         }
+
+        impl<$first $(, $extra)*> DecodeInPlace for ($first, $($extra, )*)
+        where
+            $first: DecodeInPlace,
+        $(
+            $extra : DecodeInPlace,
+        )*
+        {
+            // The fields are bound to variables with the same names as their types
+            #[allow(non_snake_case)]
+            fn decode_in_place<DE: Decoder>(&mut self, decoder: &mut DE) -> Result<(), DecodeError> {
+                let ($first, $($extra, )*) = self;
+                $first.decode_in_place(decoder)?;
+                $($extra.decode_in_place(decoder)?;)*
+                Ok(())
+            }
+        }
     }
 }
 
@@ -1,17 +1,14 @@
 use super::{
     read::{BorrowReader, Reader},
-    BorrowDecode, BorrowDecoder, Decode, Decoder,
+    BorrowDecode, BorrowDecodeMaxLen, BorrowDecoder, Decode, DecodeInPlace, Decoder,
 };
 use crate::{
-    config::{
-        Endian, IntEncoding, InternalArrayLengthConfig, InternalEndianConfig,
-        InternalIntEncodingConfig,
-    },
+    config::{Endian, IntEncoding, InternalConfig},
     error::{DecodeError, IntegerType},
     impl_borrow_decode,
+    utils::{as_bytes_mut, Primitive, NATIVE_ENDIAN},
 };
 use core::{
-    any::TypeId,
     cell::{Cell, RefCell},
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
@@ -21,6 +18,197 @@ use core::{
     time::Duration,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Returns `true` if integers are encoded as their in-memory bytes with the config of `decoder`.
+fn ints_are_native<D: Decoder>(decoder: &D) -> bool {
+    decoder.config().int_encoding() == IntEncoding::Fixed
+        && decoder.config().endian() == NATIVE_ENDIAN
+}
+
+/// Returns `true` if floats are encoded as their in-memory bytes with the config of `decoder`. In strict mode every NaN has to be checked, so floats are decoded one by one instead.
+fn floats_are_native<D: Decoder>(decoder: &D) -> bool {
+    decoder.config().endian() == NATIVE_ENDIAN && !decoder.config().is_strict()
+}
+
+/// Single bytes are always encoded as themselves.
+fn bytes_are_native<D: Decoder>(_: &D) -> bool {
+    true
+}
+
+/// Reads `len` primitives as one block of bytes and appends them to `vec`.
+#[cfg(feature = "alloc")]
+fn decode_native_vec<T: Primitive, D: Decoder>(
+    decoder: &mut D,
+    vec: &mut Vec<T>,
+    len: usize,
+) -> Result<(), DecodeError> {
+    let size = core::mem::size_of::<T>();
+    let end = vec.len() + len;
+    while vec.len() < end {
+        let start = vec.len();
+        let remaining = end - start;
+        // Read all values at once if the reader holds them. Otherwise read them in chunks, so a large length in malicious input can not make us allocate more memory than there is input
+        let chunk = match decoder.reader().remaining_hint() {
+            Some(hint) if hint / size >= remaining => remaining,
+            _ => super::preallocation_len::<T, _>(decoder, remaining).max(1),
+        };
+        vec.try_reserve(chunk)
+            .map_err(crate::allocation_failed::<Vec<T>>)?;
+        vec.resize(start + chunk, T::default());
+        decoder.reader().read(as_bytes_mut(&mut vec[start..]))?;
+    }
+    Ok(())
+}
+
+/// Reads `N` primitives as one block of bytes.
+fn decode_native_array<T: Primitive, D: Decoder, const N: usize>(
+    decoder: &mut D,
+) -> Result<[T; N], DecodeError> {
+    let mut array = [T::default(); N];
+    decoder.reader().read(as_bytes_mut(&mut array))?;
+    Ok(array)
+}
+
+/// Fills `out` with varint-encoded integers. Runs of single-byte values are decoded in bulk with `from_byte`, everything else is decoded one by one.
+fn decode_varints<T: Primitive + Decode, D: Decoder, F: Fn(u8) -> T + Copy>(
+    decoder: &mut D,
+    out: &mut [T],
+    from_byte: F,
+) -> Result<(), DecodeError> {
+    let mut decoded = 0;
+    while decoded < out.len() {
+        decoded +=
+            crate::varint::varint_decode_batch(decoder.reader(), &mut out[decoded..], from_byte);
+        if decoded < out.len() {
+            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+            out[decoded] = T::decode(decoder)?;
+            decoded += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Decodes `len` varint-encoded integers and appends them to `vec`, see `decode_varints`.
+#[cfg(feature = "alloc")]
+fn decode_varint_vec<T: Primitive + Decode, D: Decoder, F: Fn(u8) -> T + Copy>(
+    decoder: &mut D,
+    vec: &mut Vec<T>,
+    len: usize,
+    from_byte: F,
+) -> Result<(), DecodeError> {
+    let end = vec.len() + len;
+    while vec.len() < end {
+        let start = vec.len();
+        // Every value takes up at least one byte, so the preallocation length is limited by the remaining input
+        let chunk = super::preallocation_len::<T, _>(decoder, end - start).max(1);
+        vec.try_reserve(chunk)
+            .map_err(crate::allocation_failed::<Vec<T>>)?;
+        vec.resize(start + chunk, T::default());
+        decode_varints(decoder, &mut vec[start..], from_byte)?;
+    }
+    Ok(())
+}
+
+/// Decodes `N` varint-encoded integers, see `decode_varints`.
+fn decode_varint_array<T: Primitive + Decode, D: Decoder, F: Fn(u8) -> T + Copy, const N: usize>(
+    decoder: &mut D,
+    from_byte: F,
+) -> Result<[T; N], DecodeError> {
+    let mut array = [T::default(); N];
+    decode_varints(decoder, &mut array, from_byte)?;
+    Ok(array)
+}
+
+/// Overrides the bulk decoding methods of `Decode` for a primitive. If `$is_native` returns `true` for the decoder, the values are read as one block of bytes.
+///
+/// Integers pass a `$from_byte` function that converts a single-byte varint to the integer, which is used to decode runs of small values in bulk with varint encoding.
+macro_rules! impl_decode_native {
+    ($is_native:ident) => {
+        #[cfg(feature = "alloc")]
+        fn decode_vec<D: Decoder>(
+            decoder: &mut D,
+            vec: &mut Vec<Self>,
+            len: usize,
+        ) -> Result<(), DecodeError> {
+            if $is_native(decoder) {
+                decode_native_vec(decoder, vec, len)
+            } else {
+                super::decode_vec_per_element(decoder, vec, len, Self::decode)
+            }
+        }
+
+        fn decode_array<D: Decoder, const N: usize>(
+            decoder: &mut D,
+        ) -> Result<[Self; N], DecodeError> {
+            if $is_native(decoder) {
+                decode_native_array(decoder)
+            } else {
+                super::decode_array_per_element(decoder, Self::decode)
+            }
+        }
+    };
+    ($is_native:ident, $from_byte:expr) => {
+        #[cfg(feature = "alloc")]
+        fn decode_vec<D: Decoder>(
+            decoder: &mut D,
+            vec: &mut Vec<Self>,
+            len: usize,
+        ) -> Result<(), DecodeError> {
+            match decoder.config().int_encoding() {
+                _ if $is_native(decoder) => decode_native_vec(decoder, vec, len),
+                IntEncoding::Variable => decode_varint_vec(decoder, vec, len, $from_byte),
+                _ => super::decode_vec_per_element(decoder, vec, len, Self::decode),
+            }
+        }
+
+        fn decode_array<D: Decoder, const N: usize>(
+            decoder: &mut D,
+        ) -> Result<[Self; N], DecodeError> {
+            match decoder.config().int_encoding() {
+                _ if $is_native(decoder) => decode_native_array(decoder),
+                IntEncoding::Variable => decode_varint_array(decoder, $from_byte),
+                _ => super::decode_array_per_element(decoder, Self::decode),
+            }
+        }
+    };
+}
+
+/// Like `impl_borrow_decode!`, but also forwards the bulk decoding methods to `Decode`.
+macro_rules! impl_borrow_decode_native {
+    ($ty:ty) => {
+        impl<'de> BorrowDecode<'de> for $ty {
+            fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+                Self::decode(decoder)
+            }
+
+            #[cfg(feature = "alloc")]
+            fn borrow_decode_vec<D: BorrowDecoder<'de>>(
+                decoder: &mut D,
+                vec: &mut Vec<Self>,
+                len: usize,
+            ) -> Result<(), DecodeError> {
+                Self::decode_vec(decoder, vec, len)
+            }
+
+            fn borrow_decode_array<D: BorrowDecoder<'de>, const N: usize>(
+                decoder: &mut D,
+            ) -> Result<[Self; N], DecodeError> {
+                Self::decode_array(decoder)
+            }
+
+            fn borrow_decode_vec_len<D: BorrowDecoder<'de>>(
+                decoder: &mut D,
+                max_len: usize,
+            ) -> Result<usize, DecodeError> {
+                Self::decode_vec_len(decoder, max_len)
+            }
+        }
+    };
+}
+
 impl Decode for bool {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         match u8::decode(decoder)? {
@@ -46,8 +234,14 @@ impl Decode for u8 {
             Ok(bytes[0])
         }
     }
+
+    fn decode_vec_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<usize, DecodeError> {
+        super::decode_string_len(decoder, max_len)
+    }
+
+    impl_decode_native!(bytes_are_native);
 }
-impl_borrow_decode!(u8);
+impl_borrow_decode_native!(u8);
 
 impl Decode for NonZeroU8 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -61,22 +255,27 @@ impl_borrow_decode!(NonZeroU8);
 impl Decode for u16 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(2)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_u16(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_u16(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_u16(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 2];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => u16::from_le_bytes(bytes),
                     Endian::Big => u16::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(ints_are_native, |byte| byte as u16);
 }
-impl_borrow_decode!(u16);
+impl_borrow_decode_native!(u16);
 
 impl Decode for NonZeroU16 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -90,22 +289,27 @@ impl_borrow_decode!(NonZeroU16);
 impl Decode for u32 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(4)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_u32(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_u32(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_u32(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 4];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => u32::from_le_bytes(bytes),
                     Endian::Big => u32::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(ints_are_native, |byte| byte as u32);
 }
-impl_borrow_decode!(u32);
+impl_borrow_decode_native!(u32);
 
 impl Decode for NonZeroU32 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -119,22 +323,27 @@ impl_borrow_decode!(NonZeroU32);
 impl Decode for u64 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(8)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_u64(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_u64(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_u64(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 8];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => u64::from_le_bytes(bytes),
                     Endian::Big => u64::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(ints_are_native, |byte| byte as u64);
 }
-impl_borrow_decode!(u64);
+impl_borrow_decode_native!(u64);
 
 impl Decode for NonZeroU64 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -148,22 +357,27 @@ impl_borrow_decode!(NonZeroU64);
 impl Decode for u128 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(16)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_u128(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_u128(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_u128(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 16];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => u128::from_le_bytes(bytes),
                     Endian::Big => u128::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(ints_are_native, |byte| byte as u128);
 }
-impl_borrow_decode!(u128);
+impl_borrow_decode_native!(u128);
 
 impl Decode for NonZeroU128 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -177,15 +391,18 @@ impl_borrow_decode!(NonZeroU128);
 impl Decode for usize {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(8)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_usize(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_usize(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_usize(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 8];
                 decoder.reader().read(&mut bytes)?;
 
-                let value = match D::C::ENDIAN {
+                let value = match endian {
                     Endian::Little => u64::from_le_bytes(bytes),
                     Endian::Big => u64::from_be_bytes(bytes),
                 };
@@ -215,8 +432,10 @@ impl Decode for i8 {
         decoder.reader().read(&mut bytes)?;
         Ok(bytes[0] as i8)
     }
+
+    impl_decode_native!(bytes_are_native);
 }
-impl_borrow_decode!(i8);
+impl_borrow_decode_native!(i8);
 
 impl Decode for NonZeroI8 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -230,22 +449,30 @@ impl_borrow_decode!(NonZeroI8);
 impl Decode for i16 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(2)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_i16(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_i16(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_i16(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 2];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => i16::from_le_bytes(bytes),
                     Endian::Big => i16::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(
+        ints_are_native,
+        |byte| crate::varint::zigzag_decode_byte(byte) as i16
+    );
 }
-impl_borrow_decode!(i16);
+impl_borrow_decode_native!(i16);
 
 impl Decode for NonZeroI16 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -259,22 +486,30 @@ impl_borrow_decode!(NonZeroI16);
 impl Decode for i32 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(4)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_i32(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_i32(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_i32(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 4];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => i32::from_le_bytes(bytes),
                     Endian::Big => i32::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(
+        ints_are_native,
+        |byte| crate::varint::zigzag_decode_byte(byte) as i32
+    );
 }
-impl_borrow_decode!(i32);
+impl_borrow_decode_native!(i32);
 
 impl Decode for NonZeroI32 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -288,22 +523,30 @@ impl_borrow_decode!(NonZeroI32);
 impl Decode for i64 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(8)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_i64(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_i64(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_i64(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 8];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => i64::from_le_bytes(bytes),
                     Endian::Big => i64::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(
+        ints_are_native,
+        |byte| crate::varint::zigzag_decode_byte(byte) as i64
+    );
 }
-impl_borrow_decode!(i64);
+impl_borrow_decode_native!(i64);
 
 impl Decode for NonZeroI64 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -317,22 +560,30 @@ impl_borrow_decode!(NonZeroI64);
 impl Decode for i128 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(16)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_i128(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_i128(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_i128(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 16];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => i128::from_le_bytes(bytes),
                     Endian::Big => i128::from_be_bytes(bytes),
                 })
             }
         }
     }
+
+    impl_decode_native!(
+        ints_are_native,
+        |byte| crate::varint::zigzag_decode_byte(byte) as i128
+    );
 }
-impl_borrow_decode!(i128);
+impl_borrow_decode_native!(i128);
 
 impl Decode for NonZeroI128 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -346,14 +597,17 @@ impl_borrow_decode!(NonZeroI128);
 impl Decode for isize {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(8)?;
-        match D::C::INT_ENCODING {
+        let endian = decoder.config().endian();
+        let strict = decoder.config().is_strict();
+        match decoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_decode_isize(decoder.reader(), D::C::ENDIAN)
+                crate::varint::varint_decode_isize(decoder.reader(), endian, strict)
             }
+            IntEncoding::Leb128 => crate::varint::leb128_decode_isize(decoder.reader(), strict),
             IntEncoding::Fixed => {
                 let mut bytes = [0u8; 8];
                 decoder.reader().read(&mut bytes)?;
-                Ok(match D::C::ENDIAN {
+                Ok(match endian {
                     Endian::Little => i64::from_le_bytes(bytes),
                     Endian::Big => i64::from_be_bytes(bytes),
                 } as isize)
@@ -377,26 +631,41 @@ impl Decode for f32 {
         decoder.claim_bytes_read(4)?;
         let mut bytes = [0u8; 4];
         decoder.reader().read(&mut bytes)?;
-        Ok(match D::C::ENDIAN {
+        let value = match decoder.config().endian() {
             Endian::Little => f32::from_le_bytes(bytes),
             Endian::Big => f32::from_be_bytes(bytes),
-        })
+        };
+        if decoder.config().is_strict() && value.is_nan() && value.to_bits() != 0x7FC0_0000 {
+            return Err(DecodeError::NonCanonicalNaN);
+        }
+        Ok(value)
     }
+
+    impl_decode_native!(floats_are_native);
 }
-impl_borrow_decode!(f32);
+impl_borrow_decode_native!(f32);
 
 impl Decode for f64 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         decoder.claim_bytes_read(8)?;
         let mut bytes = [0u8; 8];
         decoder.reader().read(&mut bytes)?;
-        Ok(match D::C::ENDIAN {
+        let value = match decoder.config().endian() {
             Endian::Little => f64::from_le_bytes(bytes),
             Endian::Big => f64::from_be_bytes(bytes),
-        })
+        };
+        if decoder.config().is_strict()
+            && value.is_nan()
+            && value.to_bits() != 0x7FF8_0000_0000_0000
+        {
+            return Err(DecodeError::NonCanonicalNaN);
+        }
+        Ok(value)
     }
+
+    impl_decode_native!(floats_are_native);
 }
-impl_borrow_decode!(f64);
+impl_borrow_decode_native!(f64);
 
 impl Decode for char {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -428,27 +697,129 @@ impl Decode for char {
 }
 impl_borrow_decode!(char);
 
+/// Implements `DecodeInPlace` for types without allocations, which are decoded in place by assigning a newly decoded value.
+macro_rules! impl_decode_in_place {
+    ($($ty:ty),*) => {
+        $(
+            impl DecodeInPlace for $ty {}
+        )*
+    };
+}
+
+impl_decode_in_place!(
+    bool,
+    char,
+    usize,
+    isize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    (),
+    Duration
+);
+
+/// Implements `DecodeInPlace` for the integer and float types, so `Vec`s and arrays of them are still decoded in bulk.
+macro_rules! impl_decode_in_place_native {
+    ($($ty:ty),*) => {
+        $(
+            impl DecodeInPlace for $ty {
+                #[cfg(feature = "alloc")]
+                fn decode_vec_in_place<D: Decoder>(
+                    decoder: &mut D,
+                    vec: &mut Vec<Self>,
+                    len: usize,
+                ) -> Result<(), DecodeError> {
+                    vec.clear();
+                    Self::decode_vec(decoder, vec, len)
+                }
+
+                fn decode_array_in_place<D: Decoder, const N: usize>(
+                    decoder: &mut D,
+                    array: &mut [Self; N],
+                ) -> Result<(), DecodeError> {
+                    *array = Self::decode_array(decoder)?;
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_decode_in_place_native!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
 impl<'a, 'de: 'a> BorrowDecode<'de> for &'a [u8] {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = super::decode_slice_len(decoder)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<'a, 'de: 'a> BorrowDecodeMaxLen<'de> for &'a [u8] {
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        let len = super::decode_string_len(decoder, max_len)?;
         decoder.claim_bytes_read(len)?;
         decoder.borrow_reader().take_bytes(len)
     }
 }
 
+/// Borrows slices of integers and floats directly from the input. See `super::borrow_native_slice`.
+macro_rules! impl_borrow_decode_slice {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a, 'de: 'a> BorrowDecode<'de> for &'a [$ty] {
+                fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+                    Self::borrow_decode_max_len(decoder, usize::MAX)
+                }
+            }
+
+            impl<'a, 'de: 'a> BorrowDecodeMaxLen<'de> for &'a [$ty] {
+                fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+                    decoder: &mut D,
+                    max_len: usize,
+                ) -> Result<Self, DecodeError> {
+                    let len = super::decode_collection_len(decoder, max_len)?;
+                    super::borrow_native_slice(decoder, len)
+                }
+            }
+        )*
+    };
+}
+
+impl_borrow_decode_slice!(u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
 impl<'a, 'de: 'a> BorrowDecode<'de> for &'a str {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let slice = <&[u8]>::borrow_decode(decoder)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<'a, 'de: 'a> BorrowDecodeMaxLen<'de> for &'a str {
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        let slice = <&[u8]>::borrow_decode_max_len(decoder, max_len)?;
         core::str::from_utf8(slice).map_err(|inner| DecodeError::Utf8 { inner })
     }
 }
 
 impl<T, const N: usize> Decode for [T; N]
 where
-    T: Decode + Sized + 'static,
+    T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        if !D::C::SKIP_FIXED_ARRAY_LENGTH {
+        if !decoder.config().skips_fixed_array_length() {
             let length = super::decode_slice_len(decoder)?;
             if length != N {
                 return Err(DecodeError::ArrayLengthMismatch {
@@ -459,37 +830,36 @@ where
         }
 
         decoder.claim_bytes_read(core::mem::size_of::<[T; N]>())?;
+        T::decode_array(decoder)
+    }
+}
 
-        // Optimize for `[u8; N]`
-        if TypeId::of::<u8>() == TypeId::of::<T>() {
-            let mut buf = [0u8; N];
-            decoder.reader().read(&mut buf)?;
-            let ptr = &mut buf as *mut _ as *mut [T; N];
-
-            // Safety: we know that T is a u8, so it is perfectly safe to
-            // translate an array of u8 into an array of T
-            let res = unsafe { ptr.read() };
-            Ok(res)
-        } else {
-            let result = super::impl_core::collect_into_array(&mut (0..N).map(|_| {
-                // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-                decoder.unclaim_bytes_read(core::mem::size_of::<T>());
-                T::decode(decoder)
-            }));
-
-            // result is only None if N does not match the values of `(0..N)`, which it always should
-            // So this unwrap should never occur
-            result.unwrap()
+impl<T, const N: usize> DecodeInPlace for [T; N]
+where
+    T: DecodeInPlace,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        if !decoder.config().skips_fixed_array_length() {
+            let length = super::decode_slice_len(decoder)?;
+            if length != N {
+                return Err(DecodeError::ArrayLengthMismatch {
+                    found: length,
+                    required: N,
+                });
+            }
         }
+
+        decoder.claim_bytes_read(core::mem::size_of::<[T; N]>())?;
+        T::decode_array_in_place(decoder, self)
     }
 }
 
 impl<'de, T, const N: usize> BorrowDecode<'de> for [T; N]
 where
-    T: BorrowDecode<'de> + Sized + 'static,
+    T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        if !D::C::SKIP_FIXED_ARRAY_LENGTH {
+        if !decoder.config().skips_fixed_array_length() {
             let length = super::decode_slice_len(decoder)?;
             if length != N {
                 return Err(DecodeError::ArrayLengthMismatch {
@@ -500,28 +870,7 @@ where
         }
 
         decoder.claim_bytes_read(core::mem::size_of::<[T; N]>())?;
-
-        // Optimize for `[u8; N]`
-        if TypeId::of::<u8>() == TypeId::of::<T>() {
-            let mut buf = [0u8; N];
-            decoder.reader().read(&mut buf)?;
-            let ptr = &mut buf as *mut _ as *mut [T; N];
-
-            // Safety: we know that T is a u8, so it is perfectly safe to
-            // translate an array of u8 into an array of T
-            let res = unsafe { ptr.read() };
-            Ok(res)
-        } else {
-            let result = super::impl_core::collect_into_array(&mut (0..N).map(|_| {
-                // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-                decoder.unclaim_bytes_read(core::mem::size_of::<T>());
-                T::borrow_decode(decoder)
-            }));
-
-            // result is only None if N does not match the values of `(0..N)`, which it always should
-            // So this unwrap should never occur
-            result.unwrap()
-        }
+        T::borrow_decode_array(decoder)
     }
 }
 
@@ -537,6 +886,8 @@ impl<T> Decode for core::marker::PhantomData<T> {
         Ok(core::marker::PhantomData)
     }
 }
+
+impl<T> DecodeInPlace for core::marker::PhantomData<T> {}
 impl<'de, T> BorrowDecode<'de> for core::marker::PhantomData<T> {
     fn borrow_decode<D: BorrowDecoder<'de>>(_: &mut D) -> Result<Self, DecodeError> {
         Ok(core::marker::PhantomData)
@@ -558,6 +909,27 @@ where
     }
 }
 
+impl<T> DecodeInPlace for Option<T>
+where
+    T: DecodeInPlace,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        match super::decode_option_variant(decoder, core::any::type_name::<Option<T>>())? {
+            Some(_) => match self {
+                Some(value) => value.decode_in_place(decoder),
+                None => {
+                    *self = Some(T::decode(decoder)?);
+                    Ok(())
+                }
+            },
+            None => {
+                *self = None;
+                Ok(())
+            }
+        }
+    }
+}
+
 impl<'de, T> BorrowDecode<'de> for Option<T>
 where
     T: BorrowDecode<'de>,
@@ -595,7 +967,7 @@ where
     U: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let is_ok = u32::decode(decoder)?;
+        let is_ok = super::decode_variant_index(decoder)?;
         match is_ok {
             0 => {
                 let t = T::decode(decoder)?;
@@ -614,13 +986,20 @@ where
     }
 }
 
+impl<T, U> DecodeInPlace for Result<T, U>
+where
+    T: Decode,
+    U: Decode,
+{
+}
+
 impl<'de, T, U> BorrowDecode<'de> for Result<T, U>
 where
     T: BorrowDecode<'de>,
     U: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let is_ok = u32::decode(decoder)?;
+        let is_ok = super::decode_variant_index(decoder)?;
         match is_ok {
             0 => {
                 let t = T::borrow_decode(decoder)?;
@@ -649,6 +1028,8 @@ where
     }
 }
 
+impl<T> DecodeInPlace for Cell<T> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for Cell<T>
 where
     T: BorrowDecode<'de>,
@@ -669,6 +1050,8 @@ where
     }
 }
 
+impl<T> DecodeInPlace for RefCell<T> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for RefCell<T>
 where
     T: BorrowDecode<'de>,
@@ -684,7 +1067,9 @@ impl Decode for Duration {
         const NANOS_PER_SEC: u64 = 1_000_000_000;
         let secs: u64 = Decode::decode(decoder)?;
         let nanos: u32 = Decode::decode(decoder)?;
-        if secs.checked_add(u64::from(nanos) / NANOS_PER_SEC).is_none() {
+        if secs.checked_add(u64::from(nanos) / NANOS_PER_SEC).is_none()
+            || (decoder.config().is_strict() && u64::from(nanos) >= NANOS_PER_SEC)
+        {
             return Err(DecodeError::InvalidDuration { secs, nanos });
         }
         Ok(Duration::new(secs, nanos))
@@ -702,6 +1087,8 @@ where
         Ok(min..max)
     }
 }
+
+impl<T> DecodeInPlace for Range<T> where T: Decode {}
 impl<'de, T> BorrowDecode<'de> for Range<T>
 where
     T: BorrowDecode<'de>,
@@ -724,6 +1111,8 @@ where
     }
 }
 
+impl<T> DecodeInPlace for RangeInclusive<T> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for RangeInclusive<T>
 where
     T: BorrowDecode<'de>,
@@ -740,7 +1129,7 @@ where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        match u32::decode(decoder)? {
+        match super::decode_variant_index(decoder)? {
             0 => Ok(Bound::Unbounded),
             1 => Ok(Bound::Included(T::decode(decoder)?)),
             2 => Ok(Bound::Excluded(T::decode(decoder)?)),
@@ -753,12 +1142,14 @@ where
     }
 }
 
+impl<T> DecodeInPlace for Bound<T> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for Bound<T>
 where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        match u32::decode(decoder)? {
+        match super::decode_variant_index(decoder)? {
             0 => Ok(Bound::Unbounded),
             1 => Ok(Bound::Included(T::borrow_decode(decoder)?)),
             2 => Ok(Bound::Excluded(T::borrow_decode(decoder)?)),
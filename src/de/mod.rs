@@ -7,7 +7,7 @@ mod impls;
 
 use self::read::{BorrowReader, Reader};
 use crate::{
-    config::{Config, InternalLimitConfig},
+    config::{Config, DiscriminantEncoding, Endian, IntEncoding, InternalConfig, LengthEncoding},
     error::DecodeError,
     utils::Sealed,
 };
@@ -85,6 +85,35 @@ pub use self::decoder::DecoderImpl;
 pub trait Decode: Sized {
     /// Attempt to decode this type with the given [Decode].
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError>;
+
+    /// Decode `len` values of this type and append them to `vec`. This is called by `Vec<T>` and the collections that are decoded through it, after the length has been decoded and claimed.
+    ///
+    /// The default implementation decodes the values one by one. The integer and float types override this to read all values at once, if they are encoded as their in-memory bytes.
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn decode_vec<D: Decoder>(
+        decoder: &mut D,
+        vec: &mut alloc::vec::Vec<Self>,
+        len: usize,
+    ) -> Result<(), DecodeError> {
+        decode_vec_per_element(decoder, vec, len, Self::decode)
+    }
+
+    /// Decode `N` values of this type into an array. This is called by `[T; N]`, after the length has been decoded and claimed.
+    ///
+    /// The default implementation decodes the values one by one. The integer and float types override this like [decode_vec](Decode::decode_vec).
+    #[doc(hidden)]
+    fn decode_array<D: Decoder, const N: usize>(decoder: &mut D) -> Result<[Self; N], DecodeError> {
+        decode_array_per_element(decoder, Self::decode)
+    }
+
+    /// Decode the length of a `Vec` of this type and check it against `max_len` and the limits of the config. This is called by `Vec<T>` and `VecDeque<T>`.
+    ///
+    /// The default implementation checks the length against the maximum collection length. `u8` overrides this to check it against the maximum string length, because a `Vec<u8>` has the same encoding as a byte slice.
+    #[doc(hidden)]
+    fn decode_vec_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<usize, DecodeError> {
+        decode_collection_len(decoder, max_len)
+    }
 }
 
 /// Trait that makes a type able to be decoded, akin to serde's `Deserialize` trait.
@@ -95,6 +124,230 @@ pub trait Decode: Sized {
 pub trait BorrowDecode<'de>: Sized {
     /// Attempt to decode this type with the given [BorrowDecode].
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError>;
+
+    /// Decode `len` values of this type and append them to `vec`. This is the [BorrowDecode] counterpart of `Decode::decode_vec`.
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn borrow_decode_vec<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        vec: &mut alloc::vec::Vec<Self>,
+        len: usize,
+    ) -> Result<(), DecodeError> {
+        decode_vec_per_element(decoder, vec, len, Self::borrow_decode)
+    }
+
+    /// Decode `N` values of this type into an array. This is the [BorrowDecode] counterpart of `Decode::decode_array`.
+    #[doc(hidden)]
+    fn borrow_decode_array<D: BorrowDecoder<'de>, const N: usize>(
+        decoder: &mut D,
+    ) -> Result<[Self; N], DecodeError> {
+        decode_array_per_element(decoder, Self::borrow_decode)
+    }
+
+    /// Decode the length of a `Vec` of this type. This is the [BorrowDecode] counterpart of `Decode::decode_vec_len`.
+    #[doc(hidden)]
+    fn borrow_decode_vec_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<usize, DecodeError> {
+        decode_collection_len(decoder, max_len)
+    }
+}
+
+/// Decodes `len` values one by one with `decode` and appends them to `vec`. This is the default implementation of `Decode::decode_vec` and `BorrowDecode::borrow_decode_vec`.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_vec_per_element<T, D, F>(
+    decoder: &mut D,
+    vec: &mut alloc::vec::Vec<T>,
+    len: usize,
+    mut decode: F,
+) -> Result<(), DecodeError>
+where
+    D: Decoder,
+    F: FnMut(&mut D) -> Result<T, DecodeError>,
+{
+    vec.try_reserve(preallocation_len::<T, _>(decoder, len))
+        .map_err(crate::allocation_failed::<alloc::vec::Vec<T>>)?;
+    for _ in 0..len {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+
+        vec.try_reserve(1)
+            .map_err(crate::allocation_failed::<alloc::vec::Vec<T>>)?;
+        vec.push(decode(decoder)?);
+    }
+    Ok(())
+}
+
+/// Decodes `len` values into `vec`, reusing its existing entries. This is the default implementation of `DecodeInPlace::decode_vec_in_place`.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_vec_in_place_per_element<T, D>(
+    decoder: &mut D,
+    vec: &mut alloc::vec::Vec<T>,
+    len: usize,
+) -> Result<(), DecodeError>
+where
+    T: DecodeInPlace,
+    D: Decoder,
+{
+    vec.truncate(len);
+    for item in vec.iter_mut() {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+        item.decode_in_place(decoder)?;
+    }
+    let remaining = len - vec.len();
+    T::decode_vec(decoder, vec, remaining)
+}
+
+/// Decodes `N` values one by one with `decode`. This is the default implementation of `Decode::decode_array` and `BorrowDecode::borrow_decode_array`.
+pub(crate) fn decode_array_per_element<T, D, F, const N: usize>(
+    decoder: &mut D,
+    mut decode: F,
+) -> Result<[T; N], DecodeError>
+where
+    D: Decoder,
+    F: FnMut(&mut D) -> Result<T, DecodeError>,
+{
+    let result = impl_core::collect_into_array(&mut (0..N).map(|_| {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+        decode(decoder)
+    }));
+
+    // result is only None if N does not match the values of `(0..N)`, which it always should
+    // So this unwrap should never occur
+    result.unwrap()
+}
+
+/// Borrows `len` values of type `T` from the input. This fails if the config does not encode `T` as its in-memory bytes, or if the values are not aligned in the input.
+pub(crate) fn borrow_native_slice<'de, T, D>(
+    decoder: &mut D,
+    len: usize,
+) -> Result<&'de [T], DecodeError>
+where
+    T: crate::utils::Primitive,
+    D: BorrowDecoder<'de>,
+{
+    let config = decoder.config();
+    let is_native = config.endian() == crate::utils::NATIVE_ENDIAN
+        && (T::IS_FLOAT
+            || core::mem::size_of::<T>() == 1
+            || config.int_encoding() == IntEncoding::Fixed);
+    if !is_native {
+        return Err(DecodeError::NonNativeSlice {
+            type_name: core::any::type_name::<T>(),
+        });
+    }
+
+    decoder.claim_container_read::<T>(len)?;
+    let size = len
+        .checked_mul(core::mem::size_of::<T>())
+        .ok_or(DecodeError::UnexpectedEnd {
+            additional: usize::MAX,
+        })?;
+    let bytes = decoder.borrow_reader().take_bytes(size)?;
+    let slice = crate::utils::try_from_bytes::<T>(bytes).ok_or(DecodeError::UnalignedSlice {
+        type_name: core::any::type_name::<T>(),
+        align: core::mem::align_of::<T>(),
+    })?;
+
+    if decoder.config().is_strict() && slice.iter().any(|value| value.is_non_canonical_nan()) {
+        return Err(DecodeError::NonCanonicalNaN);
+    }
+    Ok(slice)
+}
+
+/// Trait that makes a collection or string able to be decoded with a maximum length.
+///
+/// This is used by the `#[bincode(max_len = N)]` field attribute of the `Decode` derive. The length is checked before any of the entries are decoded, so no memory is allocated for collections that are too long.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bincode::de::DecodeMaxLen;
+/// use bincode::error::DecodeError;
+///
+/// let encoded = bincode::encode_to_vec(vec![1u32, 2, 3], bincode::config::standard()).unwrap();
+/// let mut decoder = bincode::de::DecoderImpl::new(
+///     bincode::de::read::SliceReader::new(&encoded),
+///     bincode::config::standard(),
+/// );
+/// let result = Vec::<u32>::decode_max_len(&mut decoder, 2);
+/// assert!(matches!(result, Err(DecodeError::CollectionTooLong { len: 3, max: 2 })));
+/// # }
+/// ```
+pub trait DecodeMaxLen: Decode {
+    /// Attempt to decode this type, failing with `DecodeError::CollectionTooLong` if it has more than `max_len` entries, or with `DecodeError::StringTooLong` if it is a string or byte slice that is longer than `max_len` bytes.
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError>;
+}
+
+/// Trait that makes a collection or string able to be borrow-decoded with a maximum length. See [DecodeMaxLen] for more information.
+pub trait BorrowDecodeMaxLen<'de>: BorrowDecode<'de> {
+    /// Attempt to decode this type, failing with `DecodeError::CollectionTooLong` if it has more than `max_len` entries, or with `DecodeError::StringTooLong` if it is a string or byte slice that is longer than `max_len` bytes.
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError>;
+}
+
+/// Trait that makes a type able to be decoded into an existing value, so the allocations of e.g. its `Vec`s and `String`s can be reused.
+///
+/// The encoding is the same as for [Decode], so any value that can be decoded with `Decode` can also be decoded in place. This is used by [decode_from_slice_into].
+///
+/// If decoding fails, the value is left in a valid but unspecified state.
+///
+/// This trait will be automatically implemented if you enable the `derive` feature and add `#[derive(bincode::DecodeInPlace)]` to a type that also implements `Decode`. For enums, the fields of the current variant are decoded in place if the encoded value is of the same variant.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// let config = bincode::config::standard();
+/// let mut value: Vec<String> = Vec::with_capacity(100);
+///
+/// let encoded = bincode::encode_to_vec(vec!["a", "b"], config).unwrap();
+/// bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+/// assert_eq!(value, vec!["a", "b"]);
+/// assert!(value.capacity() >= 100);
+/// # }
+/// ```
+///
+/// [decode_from_slice_into]: ../fn.decode_from_slice_into.html
+pub trait DecodeInPlace: Decode {
+    /// Decode a value into `self`, replacing its previous value.
+    ///
+    /// The default implementation decodes a new value with [Decode::decode] and assigns it to `self`. This is fine for types that do not own any allocations.
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        *self = Self::decode(decoder)?;
+        Ok(())
+    }
+
+    /// Decode `len` values of this type into `vec`, replacing its previous entries. This is called by `Vec<T>`, after the length has been decoded and claimed.
+    ///
+    /// The default implementation decodes the existing entries in place and appends the remaining values. The integer and float types override this to read all values at once, if they are encoded as their in-memory bytes.
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    fn decode_vec_in_place<D: Decoder>(
+        decoder: &mut D,
+        vec: &mut alloc::vec::Vec<Self>,
+        len: usize,
+    ) -> Result<(), DecodeError> {
+        decode_vec_in_place_per_element(decoder, vec, len)
+    }
+
+    /// Decode `N` values of this type into `array`, replacing its previous entries. This is called by `[T; N]`, after the length has been decoded and claimed.
+    ///
+    /// The default implementation decodes the entries in place one by one. The integer and float types override this like [decode_vec_in_place](DecodeInPlace::decode_vec_in_place).
+    #[doc(hidden)]
+    fn decode_array_in_place<D: Decoder, const N: usize>(
+        decoder: &mut D,
+        array: &mut [Self; N],
+    ) -> Result<(), DecodeError> {
+        for item in array.iter_mut() {
+            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+            decoder.unclaim_bytes_read(core::mem::size_of::<Self>());
+            item.decode_in_place(decoder)?;
+        }
+        Ok(())
+    }
 }
 
 /// Helper macro to implement `BorrowDecode` for any type that implements `Decode`.
@@ -132,7 +385,7 @@ pub trait Decoder: Sealed {
     /// Claim that we're going to read a container which contains `len` entries of `T`.
     /// This will correctly handle overflowing if `len * size_of::<T>() > usize::max_value`
     fn claim_container_read<T>(&mut self, len: usize) -> Result<(), DecodeError> {
-        if <Self::C as InternalLimitConfig>::LIMIT.is_some() {
+        if self.config().limit().is_some() {
             match len.checked_mul(core::mem::size_of::<T>()) {
                 Some(val) => self.claim_bytes_read(val),
                 None => Err(DecodeError::LimitExceeded),
@@ -202,6 +455,34 @@ pub trait Decoder: Sealed {
     /// }
     /// ```
     fn unclaim_bytes_read(&mut self, n: usize);
+
+    /// Claim that we're going to decode a value that is nested one level deeper than the current value.
+    /// This can be used to validate `Configuration::with_depth_limit::<N>()`.
+    ///
+    /// Every call to this function that returns `Ok(())` should be followed by a call to [unclaim_depth] once the nested value is decoded.
+    ///
+    /// ```
+    /// # use bincode::de::{Decode, Decoder};
+    /// # use bincode::error::DecodeError;
+    /// struct Node(Option<Box<Node>>);
+    ///
+    /// impl Decode for Node {
+    ///     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+    ///         decoder.claim_depth()?;
+    ///         let next = Option::<Box<Node>>::decode(decoder)?;
+    ///         decoder.unclaim_depth();
+    ///         Ok(Node(next))
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [unclaim_depth]: #tymethod.unclaim_depth
+    fn claim_depth(&mut self) -> Result<(), DecodeError>;
+
+    /// Notify the decoder that a nested value claimed with [claim_depth] is done decoding.
+    ///
+    /// [claim_depth]: #tymethod.claim_depth
+    fn unclaim_depth(&mut self);
 }
 
 /// Any source that can decode basic types. This type is most notably implemented for [Decoder].
@@ -240,6 +521,16 @@ where
     fn unclaim_bytes_read(&mut self, n: usize) {
         T::unclaim_bytes_read(self, n)
     }
+
+    #[inline]
+    fn claim_depth(&mut self) -> Result<(), DecodeError> {
+        T::claim_depth(self)
+    }
+
+    #[inline]
+    fn unclaim_depth(&mut self) {
+        T::unclaim_depth(self)
+    }
 }
 
 impl<'a, 'de, T> BorrowDecoder<'de> for &'a mut T
@@ -271,10 +562,136 @@ pub(crate) fn decode_option_variant<D: Decoder>(
     }
 }
 
+/// Decodes the discriminant of an enum variant from the given decoder, using the discriminant encoding of the decoder's config.
+///
+/// This is used by the `Decode` derive, and should be used when manually implementing `Decode` for an enum. See [encode_variant_index] for more information.
+///
+/// Note that this does not validate the discriminant; if it does not match any variant, `DecodeError::UnexpectedVariant` should be returned.
+///
+/// [encode_variant_index]: ../enc/fn.encode_variant_index.html
+#[inline]
+pub fn decode_variant_index<D: Decoder>(decoder: &mut D) -> Result<u32, DecodeError> {
+    match decoder.config().discriminant_encoding() {
+        DiscriminantEncoding::U32 => u32::decode(decoder),
+        DiscriminantEncoding::U8 => u8::decode(decoder).map(u32::from),
+        DiscriminantEncoding::Varint => {
+            decoder.claim_bytes_read(core::mem::size_of::<u32>())?;
+            let endian = decoder.config().endian();
+            let strict = decoder.config().is_strict();
+            crate::varint::varint_decode_u32(decoder.reader(), endian, strict)
+        }
+    }
+}
+
+/// Decodes the tag of an enum with a `tag_type` or `#[repr]` attribute, which is encoded as exactly `size` bytes in the endianness of the decoder's config.
+///
+/// This is used by the `Decode` and `BorrowDecode` derives, and is the counterpart of `encode_fixed_tag`.
+#[doc(hidden)]
+#[inline]
+pub fn decode_fixed_tag<D: Decoder>(decoder: &mut D, size: usize) -> Result<u32, DecodeError> {
+    decoder.claim_bytes_read(size)?;
+    let mut bytes = [0u8; 4];
+    match decoder.config().endian() {
+        Endian::Big => {
+            decoder.reader().read(&mut bytes[4 - size..])?;
+            Ok(u32::from_be_bytes(bytes))
+        }
+        Endian::Little => {
+            decoder.reader().read(&mut bytes[..size])?;
+            Ok(u32::from_le_bytes(bytes))
+        }
+    }
+}
+
 /// Decodes the length of any slice, container, etc from the decoder
 #[inline]
 pub(crate) fn decode_slice_len<D: Decoder>(decoder: &mut D) -> Result<usize, DecodeError> {
-    let v = u64::decode(decoder)?;
+    let endian = decoder.config().endian();
+    let strict = decoder.config().is_strict();
+    let v = match decoder.config().length_encoding() {
+        LengthEncoding::U64 => u64::decode(decoder)?,
+        LengthEncoding::FixedU32 => {
+            decoder.claim_bytes_read(4)?;
+            let mut bytes = [0u8; 4];
+            decoder.reader().read(&mut bytes)?;
+            u64::from(match endian {
+                Endian::Little => u32::from_le_bytes(bytes),
+                Endian::Big => u32::from_be_bytes(bytes),
+            })
+        }
+        LengthEncoding::FixedU64 => {
+            decoder.claim_bytes_read(8)?;
+            let mut bytes = [0u8; 8];
+            decoder.reader().read(&mut bytes)?;
+            match endian {
+                Endian::Little => u64::from_le_bytes(bytes),
+                Endian::Big => u64::from_be_bytes(bytes),
+            }
+        }
+        LengthEncoding::Varint => {
+            decoder.claim_bytes_read(8)?;
+            crate::varint::varint_decode_u64(decoder.reader(), endian, strict)?
+        }
+    };
 
     v.try_into().map_err(|_| DecodeError::OutsideUsizeRange(v))
 }
+
+/// Decodes the length of a collection, and checks it against `max_len` and the maximum collection length of the config
+// May not be used in all feature combinations
+#[allow(dead_code)]
+pub(crate) fn decode_collection_len<D: Decoder>(
+    decoder: &mut D,
+    max_len: usize,
+) -> Result<usize, DecodeError> {
+    let len = decode_slice_len(decoder)?;
+    let max = match decoder.config().max_collection_len() {
+        Some(max) => max.min(max_len),
+        None => max_len,
+    };
+    if len > max {
+        Err(DecodeError::CollectionTooLong { len, max })
+    } else {
+        Ok(len)
+    }
+}
+
+/// Decodes the length of a string or byte slice, and checks it against `max_len` and the maximum string length of the config
+pub(crate) fn decode_string_len<D: Decoder>(
+    decoder: &mut D,
+    max_len: usize,
+) -> Result<usize, DecodeError> {
+    let len = decode_slice_len(decoder)?;
+    let max = match decoder.config().max_string_len() {
+        Some(max) => max.min(max_len),
+        None => max_len,
+    };
+    if len > max {
+        Err(DecodeError::StringTooLong { len, max })
+    } else {
+        Ok(len)
+    }
+}
+
+/// The maximum amount of bytes that a collection allocates before any of its entries are decoded.
+const MAX_PREALLOCATION_BYTES: usize = 1024 * 1024;
+
+/// Returns how many entries of `T` a collection of `len` entries should reserve before its entries are decoded.
+///
+/// `len` is read from the input, so a few bytes of malicious input could otherwise make us allocate gigabytes of memory.
+/// Instead we reserve at most `MAX_PREALLOCATION_BYTES`, and no more entries than there are bytes left in the reader.
+/// The collection will grow when more entries are actually decoded.
+// May not be used in all feature combinations
+#[allow(dead_code)]
+pub(crate) fn preallocation_len<T, D: Decoder>(decoder: &mut D, len: usize) -> usize {
+    let size = core::mem::size_of::<T>();
+    if size == 0 {
+        // Zero-sized types never allocate
+        return len;
+    }
+    let len = len.min(MAX_PREALLOCATION_BYTES / size);
+    match decoder.reader().remaining_hint() {
+        Some(remaining) => len.min(remaining),
+        None => len,
+    }
+}
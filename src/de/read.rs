@@ -29,6 +29,14 @@ pub trait Reader {
     /// must be provided so that subsequent reads or peek-reads do not return the same bytes
     #[inline]
     fn consume(&mut self, _: usize) {}
+
+    /// Returns the amount of bytes that are left in this reader, if this is known.
+    ///
+    /// This is only used as a hint to limit how much memory is allocated before decoding a collection, so returning `None` or a wrong value will not make decoding fail.
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl<T> Reader for &mut T
@@ -49,6 +57,11 @@ where
     fn consume(&mut self, n: usize) {
         (*self).consume(n)
     }
+
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        (**self).remaining_hint()
+    }
 }
 
 /// A reader for borrowed data. Implementors of this must also implement the [Reader] trait. See the module documentation for more information.
@@ -95,6 +108,11 @@ impl<'storage> Reader for SliceReader<'storage> {
     fn consume(&mut self, n: usize) {
         self.slice = self.slice.get(n..).unwrap_or_default();
     }
+
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        Some(self.slice.len())
+    }
 }
 
 impl<'storage> BorrowReader<'storage> for SliceReader<'storage> {
@@ -0,0 +1,64 @@
+use core::ops::Deref;
+
+/// A fixed-size array that holds an encoded value. This is returned by [encode_to_array].
+///
+/// Only the first [len] bytes of the array are part of the encoded value, the remaining bytes are always `0`. Unlike a buffer passed to [encode_into_slice], an `EncodedArray` owns its bytes, so it can be moved and returned without an allocator.
+///
+/// ```
+/// let encoded = bincode::encode_to_array::<8, _, _>((5u8, 300u32), bincode::config::standard()).unwrap();
+/// assert_eq!(encoded.as_slice(), &[5, 251, 44, 1]);
+/// assert_eq!(encoded.len(), 4);
+///
+/// let (array, len) = encoded.into_parts();
+/// assert_eq!(array, [5, 251, 44, 1, 0, 0, 0, 0]);
+/// assert_eq!(len, 4);
+/// ```
+///
+/// [encode_to_array]: ../fn.encode_to_array.html
+/// [encode_into_slice]: ../fn.encode_into_slice.html
+/// [len]: #method.len
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncodedArray<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> EncodedArray<N> {
+    pub(crate) const fn new(buffer: [u8; N], len: usize) -> Self {
+        Self { buffer, len }
+    }
+
+    /// Return the encoded bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Return the amount of encoded bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the encoded value takes up no bytes, e.g. for `()`.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the whole array and the amount of bytes at the start of it that are part of the encoded value.
+    pub const fn into_parts(self) -> ([u8; N], usize) {
+        (self.buffer, self.len)
+    }
+}
+
+impl<const N: usize> Deref for EncodedArray<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for EncodedArray<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
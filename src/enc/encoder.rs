@@ -1,5 +1,5 @@
 use super::{write::Writer, Encoder};
-use crate::{config::Config, utils::Sealed};
+use crate::{config::Config, error::EncodeError, utils::Sealed};
 
 /// An Encoder that writes bytes into a given writer `W`.
 ///
@@ -9,6 +9,8 @@ use crate::{config::Config, utils::Sealed};
 /// The ByteOrder that is chosen will impact the endianness that
 /// is used to write integers to the writer.
 ///
+/// If the config has a limit, the encoder will return [EncodeError::LimitExceeded] when more bytes would be written than the limit allows.
+///
 /// ```
 /// # use bincode::enc::{write::SliceWriter, EncoderImpl, Encode};
 /// let slice: &mut [u8] = &mut [0, 0, 0, 0];
@@ -20,15 +22,22 @@ use crate::{config::Config, utils::Sealed};
 /// assert_eq!(encoder.into_writer().bytes_written(), 4);
 /// assert_eq!(slice, [0, 0, 0, 5]);
 /// ```
+///
+/// [EncodeError::LimitExceeded]: ../error/enum.EncodeError.html#variant.LimitExceeded
 pub struct EncoderImpl<W: Writer, C: Config> {
     writer: W,
     config: C,
+    bytes_written: usize,
 }
 
 impl<W: Writer, C: Config> EncoderImpl<W, C> {
     /// Create a new Encoder
     pub fn new(writer: W, config: C) -> EncoderImpl<W, C> {
-        EncoderImpl { writer, config }
+        EncoderImpl {
+            writer,
+            config,
+            bytes_written: 0,
+        }
     }
 
     /// Return the underlying writer
@@ -49,6 +58,25 @@ impl<W: Writer, C: Config> Encoder for EncoderImpl<W, C> {
     fn config(&self) -> &Self::C {
         &self.config
     }
+
+    #[inline]
+    fn claim_bytes_written(&mut self, n: usize) -> Result<(), EncodeError> {
+        // For `Configuration` the limit is a const so this check should get compiled away
+        if let Some(limit) = self.config.limit() {
+            // Make sure we don't accidentally overflow `bytes_written`
+            self.bytes_written = self
+                .bytes_written
+                .checked_add(n)
+                .ok_or(EncodeError::LimitExceeded)?;
+            if self.bytes_written > limit {
+                Err(EncodeError::LimitExceeded)
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<W: Writer, C: Config> Sealed for EncoderImpl<W, C> {}
@@ -1,10 +1,8 @@
-use super::{write::Writer, Encode, Encoder};
+use super::{write::Writer, ClaimedWriter, Encode, EncodeMaxLen, Encoder};
 use crate::{
-    config::{
-        Endian, IntEncoding, InternalArrayLengthConfig, InternalEndianConfig,
-        InternalIntEncodingConfig,
-    },
+    config::{Endian, IntEncoding, InternalConfig},
     error::EncodeError,
+    utils::{as_bytes, NATIVE_ENDIAN},
 };
 use core::{
     cell::{Cell, RefCell},
@@ -17,6 +15,38 @@ use core::{
     time::Duration,
 };
 
+/// Returns `true` if integers are encoded as their in-memory bytes with the config of `encoder`.
+fn ints_are_native<E: Encoder>(encoder: &E) -> bool {
+    encoder.config().int_encoding() == IntEncoding::Fixed
+        && encoder.config().endian() == NATIVE_ENDIAN
+}
+
+/// Returns `true` if floats are encoded as their in-memory bytes with the config of `encoder`. With canonical encoding every NaN has to be replaced, so floats are encoded one by one instead.
+fn floats_are_native<E: Encoder>(encoder: &E) -> bool {
+    encoder.config().endian() == NATIVE_ENDIAN && !encoder.config().is_canonical()
+}
+
+/// Single bytes are always encoded as themselves.
+fn bytes_are_native<E: Encoder>(_: &E) -> bool {
+    true
+}
+
+/// Overrides `Encode::encode_slice` for a primitive. If `$is_native` returns `true` for the encoder, the slice is written as one block of bytes.
+macro_rules! impl_encode_native {
+    ($is_native:ident) => {
+        fn encode_slice<E: Encoder>(slice: &[Self], encoder: &mut E) -> Result<(), EncodeError> {
+            if $is_native(encoder) {
+                ClaimedWriter(encoder).write(as_bytes(slice))
+            } else {
+                for item in slice {
+                    item.encode(encoder)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
 impl Encode for () {
     fn encode<E: Encoder>(&self, _: &mut E) -> Result<(), EncodeError> {
         Ok(())
@@ -37,8 +67,14 @@ impl Encode for bool {
 
 impl Encode for u8 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        encoder.writer().write(&[*self])
+        ClaimedWriter(encoder).write(&[*self])
     }
+
+    fn check_slice_len(len: usize, max_len: usize) -> Result<(), EncodeError> {
+        super::check_string_len(len, max_len)
+    }
+
+    impl_encode_native!(bytes_are_native);
 }
 
 impl Encode for NonZeroU8 {
@@ -49,16 +85,22 @@ impl Encode for NonZeroU8 {
 
 impl Encode for u16 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_u16(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_u16(&mut ClaimedWriter(encoder), endian, *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_u16(&mut ClaimedWriter(encoder), *self)
+            }
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroU16 {
@@ -69,16 +111,22 @@ impl Encode for NonZeroU16 {
 
 impl Encode for u32 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_u32(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_u32(&mut ClaimedWriter(encoder), endian, *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_u32(&mut ClaimedWriter(encoder), *self)
+            }
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroU32 {
@@ -89,16 +137,22 @@ impl Encode for NonZeroU32 {
 
 impl Encode for u64 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_u64(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_u64(&mut ClaimedWriter(encoder), endian, *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_u64(&mut ClaimedWriter(encoder), *self)
+            }
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroU64 {
@@ -109,16 +163,22 @@ impl Encode for NonZeroU64 {
 
 impl Encode for u128 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_u128(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_u128(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_u128(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroU128 {
@@ -129,13 +189,17 @@ impl Encode for NonZeroU128 {
 
 impl Encode for usize {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_usize(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_usize(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_usize(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&(*self as u64).to_be_bytes()),
-                Endian::Little => encoder.writer().write(&(*self as u64).to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&(*self as u64).to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&(*self as u64).to_le_bytes()),
             },
         }
     }
@@ -149,8 +213,10 @@ impl Encode for NonZeroUsize {
 
 impl Encode for i8 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        encoder.writer().write(&[*self as u8])
+        ClaimedWriter(encoder).write(&[*self as u8])
     }
+
+    impl_encode_native!(bytes_are_native);
 }
 
 impl Encode for NonZeroI8 {
@@ -161,16 +227,22 @@ impl Encode for NonZeroI8 {
 
 impl Encode for i16 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_i16(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_i16(&mut ClaimedWriter(encoder), endian, *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_i16(&mut ClaimedWriter(encoder), *self)
+            }
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroI16 {
@@ -181,16 +253,22 @@ impl Encode for NonZeroI16 {
 
 impl Encode for i32 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_i32(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_i32(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_i32(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroI32 {
@@ -201,16 +279,22 @@ impl Encode for NonZeroI32 {
 
 impl Encode for i64 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_i64(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_i64(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_i64(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroI64 {
@@ -221,16 +305,22 @@ impl Encode for NonZeroI64 {
 
 impl Encode for i128 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_i128(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_i128(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_i128(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-                Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&self.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&self.to_le_bytes()),
             },
         }
     }
+
+    impl_encode_native!(ints_are_native);
 }
 
 impl Encode for NonZeroI128 {
@@ -241,13 +331,17 @@ impl Encode for NonZeroI128 {
 
 impl Encode for isize {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::INT_ENCODING {
+        let endian = encoder.config().endian();
+        match encoder.config().int_encoding() {
             IntEncoding::Variable => {
-                crate::varint::varint_encode_isize(encoder.writer(), E::C::ENDIAN, *self)
+                crate::varint::varint_encode_isize(&mut ClaimedWriter(encoder), endian, *self)
+            }
+            IntEncoding::Leb128 => {
+                crate::varint::leb128_encode_isize(&mut ClaimedWriter(encoder), *self)
             }
-            IntEncoding::Fixed => match E::C::ENDIAN {
-                Endian::Big => encoder.writer().write(&(*self as i64).to_be_bytes()),
-                Endian::Little => encoder.writer().write(&(*self as i64).to_le_bytes()),
+            IntEncoding::Fixed => match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&(*self as i64).to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&(*self as i64).to_le_bytes()),
             },
         }
     }
@@ -261,48 +355,51 @@ impl Encode for NonZeroIsize {
 
 impl Encode for f32 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::ENDIAN {
-            Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-            Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+        let value = if encoder.config().is_canonical() && self.is_nan() {
+            f32::from_bits(0x7FC0_0000)
+        } else {
+            *self
+        };
+        match encoder.config().endian() {
+            Endian::Big => ClaimedWriter(encoder).write(&value.to_be_bytes()),
+            Endian::Little => ClaimedWriter(encoder).write(&value.to_le_bytes()),
         }
     }
+
+    impl_encode_native!(floats_are_native);
 }
 
 impl Encode for f64 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        match E::C::ENDIAN {
-            Endian::Big => encoder.writer().write(&self.to_be_bytes()),
-            Endian::Little => encoder.writer().write(&self.to_le_bytes()),
+        let value = if encoder.config().is_canonical() && self.is_nan() {
+            f64::from_bits(0x7FF8_0000_0000_0000)
+        } else {
+            *self
+        };
+        match encoder.config().endian() {
+            Endian::Big => ClaimedWriter(encoder).write(&value.to_be_bytes()),
+            Endian::Little => ClaimedWriter(encoder).write(&value.to_le_bytes()),
         }
     }
+
+    impl_encode_native!(floats_are_native);
 }
 
 impl Encode for char {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        encode_utf8(encoder.writer(), *self)
+        encode_utf8(&mut ClaimedWriter(encoder), *self)
     }
 }
 
-// BlockedTODO: https://github.com/rust-lang/rust/issues/37653
-//
-// We'll want to implement encoding for both &[u8] and &[T: Encode],
-// but those implementations overlap because u8 also implements Encode
-// impl Encode for &'_ [u8] {
-//     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-//         encoder.writer().write(*self)
-//     }
-// }
-
+// `&[u8]` and `&[T: Encode]` can not have separate implementations, because they overlap.
+// Instead `u8` overrides `Encode::encode_slice` to write the whole slice at once.
 impl<T> Encode for [T]
 where
     T: Encode,
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         super::encode_slice_len(encoder, self.len())?;
-        for item in self {
-            item.encode(encoder)?;
-        }
-        Ok(())
+        T::encode_slice(self, encoder)
     }
 }
 
@@ -340,24 +437,46 @@ fn encode_utf8(writer: &mut impl Writer, c: char) -> Result<(), EncodeError> {
     }
 }
 
+impl<T> EncodeMaxLen for [T]
+where
+    T: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        T::check_slice_len(self.len(), max_len)?;
+        self.encode(encoder)
+    }
+}
+
 impl Encode for str {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.as_bytes().encode(encoder)
     }
 }
 
+impl EncodeMaxLen for str {
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        super::check_string_len(self.len(), max_len)?;
+        self.encode(encoder)
+    }
+}
+
 impl<T, const N: usize> Encode for [T; N]
 where
     T: Encode,
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        if !E::C::SKIP_FIXED_ARRAY_LENGTH {
+        if !encoder.config().skips_fixed_array_length() {
             super::encode_slice_len(encoder, N)?;
         }
-        for item in self.iter() {
-            item.encode(encoder)?;
-        }
-        Ok(())
+        T::encode_slice(self, encoder)
     }
 }
 
@@ -382,11 +501,11 @@ where
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         match self {
             Ok(val) => {
-                0u32.encode(encoder)?;
+                super::encode_variant_index(encoder, 0)?;
                 val.encode(encoder)
             }
             Err(err) => {
-                1u32.encode(encoder)?;
+                super::encode_variant_index(encoder, 1)?;
                 err.encode(encoder)
             }
         }
@@ -454,14 +573,14 @@ where
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         match self {
             Self::Unbounded => {
-                0u32.encode(encoder)?;
+                super::encode_variant_index(encoder, 0)?;
             }
             Self::Included(val) => {
-                1u32.encode(encoder)?;
+                super::encode_variant_index(encoder, 1)?;
                 val.encode(encoder)?;
             }
             Self::Excluded(val) => {
-                2u32.encode(encoder)?;
+                super::encode_variant_index(encoder, 2)?;
                 val.encode(encoder)?;
             }
         }
@@ -477,3 +596,16 @@ where
         T::encode(self, encoder)
     }
 }
+
+impl<T> EncodeMaxLen for &T
+where
+    T: EncodeMaxLen + ?Sized,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        T::encode_max_len(self, encoder, max_len)
+    }
+}
@@ -0,0 +1,365 @@
+use crate::config::{
+    Configuration, DiscriminantEncoding, IntEncoding, LengthEncoding, StaticConfig,
+};
+use core::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    ops::{Bound, Range, RangeInclusive},
+    time::Duration,
+};
+
+/// Trait that gives the maximum amount of bytes that a type is encoded to with the config `C`. This can be used to size a buffer for [encode_into_slice] at compile time, e.g. on embedded targets without an allocator.
+///
+/// The size depends on the integer encoding, the length and discriminant encoding, and whether fixed array lengths are written. Because of this, it can only be calculated for a [StaticConfig]. If `C` is not given, the size for [standard] is returned.
+///
+/// Types that can hold any amount of data, like `Vec` and `String`, have no maximum size and return `None`. When deriving this trait, fields with a `#[bincode(max_len = N)]` attribute are bounded by their length though, see [MaxEncodedEntrySize].
+///
+/// This trait will be automatically implemented if you enable the `derive` feature and add `#[derive(bincode::MaxEncodedSize)]` to your type.
+///
+/// ```
+/// use bincode::config::Configuration;
+/// use bincode::enc::{max_encoded_size, MaxEncodedSize};
+///
+/// type Packet = (u8, u32, Option<[u16; 4]>);
+///
+/// // With the standard config, `u32` is varint-encoded in at most 5 bytes, `u16` in at most 3 bytes, and the array length takes 1 byte
+/// assert_eq!(<Packet as MaxEncodedSize>::MAX_SIZE, Some(1 + 5 + 1 + 1 + 4 * 3));
+/// // With fixed int encoding, the array length is a `u64`
+/// type Legacy = Configuration<bincode::config::LittleEndian, bincode::config::Fixint>;
+/// assert_eq!(<Packet as MaxEncodedSize<Legacy>>::MAX_SIZE, Some(1 + 4 + 1 + 8 + 4 * 2));
+///
+/// let mut buffer = [0u8; max_encoded_size::<Packet, Configuration>()];
+/// let packet: Packet = (u8::MAX, u32::MAX, Some([u16::MAX; 4]));
+/// bincode::encode_into_slice(packet, &mut buffer, bincode::config::standard()).unwrap();
+/// ```
+///
+/// [encode_into_slice]: ../fn.encode_into_slice.html
+/// [standard]: ../config/fn.standard.html
+pub trait MaxEncodedSize<C: StaticConfig = Configuration> {
+    /// The maximum amount of bytes that a value of this type is encoded to, or `None` if there is no upper bound.
+    const MAX_SIZE: Option<usize>;
+}
+
+/// Trait that gives the maximum amount of bytes that one entry of a collection or string is encoded to with the config `C`.
+///
+/// This is used by the `MaxEncodedSize` derive for fields with a `#[bincode(max_len = N)]` attribute, which are encoded to at most the length and `N` entries. It is implemented for the same types as [EncodeMaxLen].
+///
+/// ```
+/// use bincode::enc::MaxEncodedEntrySize;
+///
+/// // With the standard config, `u32` is varint-encoded in at most 5 bytes
+/// assert_eq!(<[u32] as MaxEncodedEntrySize>::MAX_ENTRY_SIZE, Some(5));
+/// // Strings are limited by their length in bytes
+/// assert_eq!(<str as MaxEncodedEntrySize>::MAX_ENTRY_SIZE, Some(1));
+/// ```
+///
+/// [EncodeMaxLen]: trait.EncodeMaxLen.html
+pub trait MaxEncodedEntrySize<C: StaticConfig = Configuration> {
+    /// The maximum amount of bytes that one entry of this type is encoded to, or `None` if there is no upper bound.
+    const MAX_ENTRY_SIZE: Option<usize>;
+}
+
+/// Returns the maximum amount of bytes that a value of type `T` is encoded to with the config `C`. Unlike [MaxEncodedSize::MAX_SIZE] this can be used directly as the length of an array.
+///
+/// # Panics
+///
+/// Fails to compile when used in a const context, or panics otherwise, if `T` has no maximum encoded size.
+pub const fn max_encoded_size<T, C>() -> usize
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    match T::MAX_SIZE {
+        Some(size) => size,
+        None => panic!("This type has no maximum encoded size"),
+    }
+}
+
+/// Adds two maximum sizes. Used by the `MaxEncodedSize` derive.
+#[doc(hidden)]
+pub const fn max_size_add(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+/// Returns the maximum amount of bytes that a value of type `T` with at most `max_len` entries is encoded to. Used by the `MaxEncodedSize` derive for fields with a `#[bincode(max_len = N)]` attribute.
+#[doc(hidden)]
+pub const fn max_len_max_size<T, C>(max_len: usize) -> Option<usize>
+where
+    T: MaxEncodedEntrySize<C> + ?Sized,
+    C: StaticConfig,
+{
+    max_size_add(
+        Some(len_size::<C>(max_len)),
+        max_size_mul(T::MAX_ENTRY_SIZE, max_len),
+    )
+}
+
+/// Returns the largest of two maximum sizes. Used by the `MaxEncodedSize` derive.
+#[doc(hidden)]
+pub const fn max_size_max(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) if a > b => Some(a),
+        (Some(_), Some(b)) => Some(b),
+        _ => None,
+    }
+}
+
+pub(crate) const fn max_size_mul(a: Option<usize>, n: usize) -> Option<usize> {
+    match a {
+        Some(a) => a.checked_mul(n),
+        None => None,
+    }
+}
+
+/// Returns the maximum amount of bytes that the discriminant `index` of an enum variant is encoded to. Used by the `MaxEncodedSize` derive.
+#[doc(hidden)]
+pub const fn variant_index_max_size<C: StaticConfig>(index: u32) -> usize {
+    match C::DISCRIMINANT_ENCODING {
+        DiscriminantEncoding::U32 => match C::INT_ENCODING {
+            IntEncoding::Fixed => 4,
+            IntEncoding::Variable => varint_size(index as u64),
+            IntEncoding::Leb128 => leb128_size(index as u64),
+        },
+        DiscriminantEncoding::U8 => 1,
+        DiscriminantEncoding::Varint => varint_size(index as u64),
+    }
+}
+
+/// Returns the amount of bytes that the length `len` of a collection is encoded to.
+pub(crate) const fn len_size<C: StaticConfig>(len: usize) -> usize {
+    match C::LENGTH_ENCODING {
+        LengthEncoding::U64 => match C::INT_ENCODING {
+            IntEncoding::Fixed => 8,
+            IntEncoding::Variable => varint_size(len as u64),
+            IntEncoding::Leb128 => leb128_size(len as u64),
+        },
+        LengthEncoding::FixedU32 => 4,
+        LengthEncoding::FixedU64 => 8,
+        LengthEncoding::Varint => varint_size(len as u64),
+    }
+}
+
+const fn varint_size(value: u64) -> usize {
+    if value <= 250 {
+        1
+    } else if value <= u16::MAX as u64 {
+        3
+    } else if value <= u32::MAX as u64 {
+        5
+    } else {
+        9
+    }
+}
+
+const fn leb128_size(mut value: u64) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        size += 1;
+    }
+    size
+}
+
+const fn int_max_size<C: StaticConfig>(fixed: usize, varint: usize, leb128: usize) -> usize {
+    match C::INT_ENCODING {
+        IntEncoding::Fixed => fixed,
+        IntEncoding::Variable => varint,
+        IntEncoding::Leb128 => leb128,
+    }
+}
+
+macro_rules! impl_fixed_size {
+    ($size:expr => $($ty:ty),*) => {
+        $(
+            impl<C: StaticConfig> MaxEncodedSize<C> for $ty {
+                const MAX_SIZE: Option<usize> = Some($size);
+            }
+        )*
+    };
+}
+
+impl_fixed_size!(0 => ());
+impl_fixed_size!(1 => bool, u8, NonZeroU8, i8, NonZeroI8);
+impl_fixed_size!(4 => f32, char);
+impl_fixed_size!(8 => f64);
+
+impl<T: ?Sized, C: StaticConfig> MaxEncodedSize<C> for PhantomData<T> {
+    const MAX_SIZE: Option<usize> = Some(0);
+}
+
+macro_rules! impl_int_size {
+    ($fixed:expr, $varint:expr, $leb128:expr => $($ty:ty),*) => {
+        $(
+            impl<C: StaticConfig> MaxEncodedSize<C> for $ty {
+                const MAX_SIZE: Option<usize> = Some(int_max_size::<C>($fixed, $varint, $leb128));
+            }
+        )*
+    };
+}
+
+impl_int_size!(2, 3, 3 => u16, NonZeroU16, i16, NonZeroI16);
+impl_int_size!(4, 5, 5 => u32, NonZeroU32, i32, NonZeroI32);
+// usize and isize are encoded as u64 and i64
+impl_int_size!(8, 9, 10 => u64, NonZeroU64, i64, NonZeroI64, usize, NonZeroUsize, isize, NonZeroIsize);
+impl_int_size!(16, 17, 19 => u128, NonZeroU128, i128, NonZeroI128);
+
+impl<T, C, const N: usize> MaxEncodedSize<C> for [T; N]
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = if C::SKIP_FIXED_ARRAY_LENGTH {
+        max_size_mul(T::MAX_SIZE, N)
+    } else {
+        max_size_add(Some(len_size::<C>(N)), max_size_mul(T::MAX_SIZE, N))
+    };
+}
+
+impl<T, C> MaxEncodedSize<C> for [T]
+where
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for str {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for [T]
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<C: StaticConfig> MaxEncodedEntrySize<C> for str {
+    const MAX_ENTRY_SIZE: Option<usize> = Some(1);
+}
+
+impl<T, C> MaxEncodedSize<C> for Option<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = max_size_add(Some(1), T::MAX_SIZE);
+}
+
+impl<T, U, C> MaxEncodedSize<C> for Result<T, U>
+where
+    T: MaxEncodedSize<C>,
+    U: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = max_size_max(
+        max_size_add(Some(variant_index_max_size::<C>(0)), T::MAX_SIZE),
+        max_size_add(Some(variant_index_max_size::<C>(1)), U::MAX_SIZE),
+    );
+}
+
+impl<T, C> MaxEncodedSize<C> for Cell<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C> MaxEncodedSize<C> for RefCell<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for Duration {
+    const MAX_SIZE: Option<usize> = max_size_add(
+        <u64 as MaxEncodedSize<C>>::MAX_SIZE,
+        <u32 as MaxEncodedSize<C>>::MAX_SIZE,
+    );
+}
+
+impl<T, C> MaxEncodedSize<C> for Range<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = max_size_mul(T::MAX_SIZE, 2);
+}
+
+impl<T, C> MaxEncodedSize<C> for RangeInclusive<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = max_size_mul(T::MAX_SIZE, 2);
+}
+
+impl<T, C> MaxEncodedSize<C> for Bound<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = max_size_max(
+        max_size_add(Some(variant_index_max_size::<C>(1)), T::MAX_SIZE),
+        max_size_add(Some(variant_index_max_size::<C>(2)), T::MAX_SIZE),
+    );
+}
+
+impl<T, C> MaxEncodedSize<C> for &T
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for &T
+where
+    T: MaxEncodedEntrySize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_ENTRY_SIZE;
+}
+
+macro_rules! impl_tuple_size {
+    ($($name:ident),+) => {
+        impl<$($name,)+ _C> MaxEncodedSize<_C> for ($($name,)+)
+        where
+            $($name: MaxEncodedSize<_C>,)+
+            _C: StaticConfig,
+        {
+            const MAX_SIZE: Option<usize> = {
+                let size = Some(0);
+                $(let size = max_size_add(size, $name::MAX_SIZE);)+
+                size
+            };
+        }
+    };
+}
+
+impl_tuple_size!(A);
+impl_tuple_size!(A, B);
+impl_tuple_size!(A, B, C);
+impl_tuple_size!(A, B, C, D);
+impl_tuple_size!(A, B, C, D, E);
+impl_tuple_size!(A, B, C, D, E, F);
+impl_tuple_size!(A, B, C, D, E, F, G);
+impl_tuple_size!(A, B, C, D, E, F, G, H);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_tuple_size!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
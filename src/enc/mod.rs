@@ -1,15 +1,25 @@
 //! Encoder-based structs and traits.
 
+mod encoded_array;
 mod encoder;
 mod impl_tuples;
 mod impls;
+pub(crate) mod max_size;
 
 use self::write::Writer;
-use crate::{config::Config, error::EncodeError, utils::Sealed};
+use crate::{
+    config::{Config, DiscriminantEncoding, Endian, InternalConfig, LengthEncoding},
+    error::EncodeError,
+    utils::Sealed,
+};
 
 pub mod write;
 
+pub use self::encoded_array::EncodedArray;
 pub use self::encoder::EncoderImpl;
+pub use self::max_size::{max_encoded_size, MaxEncodedEntrySize, MaxEncodedSize};
+#[doc(hidden)]
+pub use self::max_size::{max_len_max_size, max_size_add, max_size_max, variant_index_max_size};
 
 /// Any source that can be encoded. This trait should be implemented for all types that you want to be able to use with any of the `encode_with` methods.
 ///
@@ -51,6 +61,58 @@ pub use self::encoder::EncoderImpl;
 pub trait Encode {
     /// Encode a given type.
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError>;
+
+    /// Encode all values of `slice` one after another, without their length. This is called by slices, arrays, `Vec<T>` and `VecDeque<T>`.
+    ///
+    /// The default implementation encodes the values one by one. The integer and float types override this to write all values at once, if they are encoded as their in-memory bytes.
+    #[doc(hidden)]
+    fn encode_slice<E: Encoder>(slice: &[Self], encoder: &mut E) -> Result<(), EncodeError>
+    where
+        Self: Sized,
+    {
+        for item in slice {
+            item.encode(encoder)?;
+        }
+        Ok(())
+    }
+
+    /// Check the length of a slice of this type against `max_len`. This is called by the [EncodeMaxLen] implementations of slices.
+    ///
+    /// The default implementation fails with `EncodeError::CollectionTooLong`. `u8` overrides this to fail with `EncodeError::StringTooLong`, because a byte slice is limited like a string when it is decoded.
+    #[doc(hidden)]
+    fn check_slice_len(len: usize, max_len: usize) -> Result<(), EncodeError>
+    where
+        Self: Sized,
+    {
+        check_collection_len(len, max_len)
+    }
+}
+
+/// Trait that makes a collection or string able to be encoded with a maximum length.
+///
+/// This is used by the `#[bincode(max_len = N)]` field attribute of the `Encode` derive, so that a value which would be rejected when decoding is not encoded in the first place.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bincode::enc::EncodeMaxLen;
+/// use bincode::error::EncodeError;
+///
+/// let mut buffer = [0u8; 16];
+/// let mut encoder = bincode::enc::EncoderImpl::new(
+///     bincode::enc::write::SliceWriter::new(&mut buffer),
+///     bincode::config::standard(),
+/// );
+/// let result = vec![1u32, 2, 3].encode_max_len(&mut encoder, 2);
+/// assert!(matches!(result, Err(EncodeError::CollectionTooLong { len: 3, max: 2 })));
+/// # }
+/// ```
+pub trait EncodeMaxLen: Encode {
+    /// Encode this type, failing with `EncodeError::CollectionTooLong` if it has more than `max_len` entries, or with `EncodeError::StringTooLong` if it is a string or byte slice that is longer than `max_len` bytes.
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError>;
 }
 
 /// Helper trait to encode basic types into.
@@ -66,6 +128,28 @@ pub trait Encoder: Sealed {
 
     /// Returns a reference to the config
     fn config(&self) -> &Self::C;
+
+    /// Claim that `n` bytes are going to be written to the writer.
+    /// This is used to validate `Configuration::Limit<N>()`, and should be called before writing to [writer](Encoder::writer) directly.
+    ///
+    /// ```
+    /// # use bincode::enc::{write::Writer, Encode, Encoder};
+    /// # use bincode::error::EncodeError;
+    /// struct Magic;
+    ///
+    /// impl Encode for Magic {
+    ///     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+    ///         encoder.claim_bytes_written(4)?;
+    ///         encoder.writer().write(b"MAGC")
+    ///     }
+    /// }
+    ///
+    /// let mut buffer = [0u8; 8];
+    /// let config = bincode::config::standard().with_limit::<2>();
+    /// let result = bincode::encode_into_slice(Magic, &mut buffer, config);
+    /// assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+    /// ```
+    fn claim_bytes_written(&mut self, n: usize) -> Result<(), EncodeError>;
 }
 
 impl<'a, T> Encoder for &'a mut T
@@ -83,6 +167,46 @@ where
     fn config(&self) -> &Self::C {
         T::config(self)
     }
+
+    #[inline]
+    fn claim_bytes_written(&mut self, n: usize) -> Result<(), EncodeError> {
+        T::claim_bytes_written(self, n)
+    }
+}
+
+/// A [Writer] that writes to the writer of an [Encoder], after claiming the bytes with [Encoder::claim_bytes_written].
+///
+/// This is used by the `Encode` implementations in this crate, so that the limit of the config is enforced for every byte that is written.
+pub(crate) struct ClaimedWriter<'a, E: Encoder>(pub &'a mut E);
+
+impl<E: Encoder> Writer for ClaimedWriter<'_, E> {
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        self.0.claim_bytes_written(bytes.len())?;
+        self.0.writer().write(bytes)
+    }
+}
+
+/// Checks the amount of entries of a collection against `max_len`
+// May not be used in all feature combinations
+#[allow(dead_code)]
+#[inline]
+pub(crate) fn check_collection_len(len: usize, max_len: usize) -> Result<(), EncodeError> {
+    if len > max_len {
+        Err(EncodeError::CollectionTooLong { len, max: max_len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the length of a string against `max_len`
+#[inline]
+pub(crate) fn check_string_len(len: usize, max_len: usize) -> Result<(), EncodeError> {
+    if len > max_len {
+        Err(EncodeError::StringTooLong { len, max: max_len })
+    } else {
+        Ok(())
+    }
 }
 
 /// Encode the variant of the given option. Will not encode the option itself.
@@ -97,8 +221,89 @@ pub(crate) fn encode_option_variant<E: Encoder, T>(
     }
 }
 
+/// Encodes the discriminant of an enum variant into the given encoder, using the discriminant encoding of the encoder's config.
+///
+/// This is used by the `Encode` derive, and should be used when manually implementing `Encode` for an enum. Use [decode_variant_index] to decode the discriminant.
+///
+/// ```
+/// # use bincode::enc::{Encode, Encoder};
+/// # use bincode::error::EncodeError;
+/// enum Shape {
+///     Circle(f32),
+///     Square(f32),
+/// }
+///
+/// impl Encode for Shape {
+///     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+///         match self {
+///             Self::Circle(radius) => {
+///                 bincode::enc::encode_variant_index(encoder, 0)?;
+///                 radius.encode(encoder)
+///             }
+///             Self::Square(width) => {
+///                 bincode::enc::encode_variant_index(encoder, 1)?;
+///                 width.encode(encoder)
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [decode_variant_index]: ../de/fn.decode_variant_index.html
+#[inline]
+pub fn encode_variant_index<E: Encoder>(encoder: &mut E, index: u32) -> Result<(), EncodeError> {
+    match encoder.config().discriminant_encoding() {
+        DiscriminantEncoding::U32 => index.encode(encoder),
+        DiscriminantEncoding::U8 => {
+            let index: u8 = index
+                .try_into()
+                .map_err(|_| EncodeError::DiscriminantOutOfRange { index })?;
+            index.encode(encoder)
+        }
+        DiscriminantEncoding::Varint => {
+            let endian = encoder.config().endian();
+            crate::varint::varint_encode_u32(&mut ClaimedWriter(encoder), endian, index)
+        }
+    }
+}
+
+/// Encodes the tag of an enum with a `tag_type` or `#[repr]` attribute as exactly `size` bytes, in the endianness of the encoder's config. The `IntEncoding` of the config is not used, so the width of the tag is fixed.
+///
+/// This is used by the `Encode` derive. Use `decode_fixed_tag` to decode the tag.
+#[doc(hidden)]
+#[inline]
+pub fn encode_fixed_tag<E: Encoder>(
+    encoder: &mut E,
+    tag: u32,
+    size: usize,
+) -> Result<(), EncodeError> {
+    match encoder.config().endian() {
+        Endian::Big => ClaimedWriter(encoder).write(&tag.to_be_bytes()[4 - size..]),
+        Endian::Little => ClaimedWriter(encoder).write(&tag.to_le_bytes()[..size]),
+    }
+}
+
 /// Encodes the length of any slice, container, etc into the given encoder
 #[inline]
 pub(crate) fn encode_slice_len<E: Encoder>(encoder: &mut E, len: usize) -> Result<(), EncodeError> {
-    (len as u64).encode(encoder)
+    let endian = encoder.config().endian();
+    match encoder.config().length_encoding() {
+        LengthEncoding::U64 => (len as u64).encode(encoder),
+        LengthEncoding::FixedU32 => {
+            let len: u32 = len
+                .try_into()
+                .map_err(|_| EncodeError::LengthOutOfRange { length: len })?;
+            match endian {
+                Endian::Big => ClaimedWriter(encoder).write(&len.to_be_bytes()),
+                Endian::Little => ClaimedWriter(encoder).write(&len.to_le_bytes()),
+            }
+        }
+        LengthEncoding::FixedU64 => match endian {
+            Endian::Big => ClaimedWriter(encoder).write(&(len as u64).to_be_bytes()),
+            Endian::Little => ClaimedWriter(encoder).write(&(len as u64).to_le_bytes()),
+        },
+        LengthEncoding::Varint => {
+            crate::varint::varint_encode_u64(&mut ClaimedWriter(encoder), endian, len as u64)
+        }
+    }
 }
@@ -10,6 +10,16 @@ use crate::error::EncodeError;
 pub trait Writer {
     /// Write `bytes` to the underlying writer. Exactly `bytes.len()` bytes must be written, or else an error should be returned.
     fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError>;
+
+    /// Returns the amount of bytes that have been written so far, if this is known.
+    ///
+    /// This is only used to align the values of an [AlignedSlice] in the output, so returning `None` will not make encoding fail.
+    ///
+    /// [AlignedSlice]: ../../struct.AlignedSlice.html
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl<T: Writer> Writer for &mut T {
@@ -17,6 +27,11 @@ impl<T: Writer> Writer for &mut T {
     fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
         (**self).write(bytes)
     }
+
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        (**self).position_hint()
+    }
 }
 
 /// A helper struct that implements `Writer` for a `&[u8]` slice.
@@ -64,4 +79,47 @@ impl<'storage> Writer for SliceWriter<'storage> {
 
         Ok(())
     }
+
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        Some(self.bytes_written())
+    }
+}
+
+/// A helper struct that implements `Writer` by only counting the amount of bytes that are written to it. This is used by [encoded_size] to calculate the size of a value without allocating.
+///
+/// ```
+/// use bincode::enc::write::{Writer, SizeWriter};
+///
+/// let mut writer = SizeWriter::default();
+/// writer.write(&[1, 2, 3, 4, 5]).unwrap();
+/// writer.write(&[6, 7]).unwrap();
+///
+/// assert_eq!(writer.bytes_written(), 7);
+/// ```
+///
+/// [encoded_size]: ../../fn.encoded_size.html
+#[derive(Default)]
+pub struct SizeWriter {
+    bytes_written: usize,
+}
+
+impl SizeWriter {
+    /// Return the amount of bytes written so far.
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+}
+
+impl Writer for SizeWriter {
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        self.bytes_written += bytes.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        Some(self.bytes_written)
+    }
 }
@@ -7,6 +7,41 @@ pub enum EncodeError {
     /// The writer ran out of storage.
     UnexpectedEnd,
 
+    /// The given configuration limit was exceeded
+    LimitExceeded,
+
+    /// The discriminant of an enum variant does not fit in the discriminant encoding of the config, e.g. because it is larger than `u8::MAX` while [with_u8_discriminants] is used.
+    ///
+    /// [with_u8_discriminants]: ../config/struct.Configuration.html#method.with_u8_discriminants
+    DiscriminantOutOfRange {
+        /// The discriminant that was being encoded
+        index: u32,
+    },
+
+    /// The length of a collection, string or slice does not fit in the length encoding of the config, e.g. because it is larger than `u32::MAX` while [with_fixed_u32_lengths] is used.
+    ///
+    /// [with_fixed_u32_lengths]: ../config/struct.Configuration.html#method.with_fixed_u32_lengths
+    LengthOutOfRange {
+        /// The length that was being encoded
+        length: usize,
+    },
+
+    /// The encoder tried to encode a collection with more entries than allowed by a `#[bincode(max_len = N)]` attribute.
+    CollectionTooLong {
+        /// The amount of entries of the collection
+        len: usize,
+        /// The maximum amount of entries
+        max: usize,
+    },
+
+    /// The encoder tried to encode a string or byte slice that is longer than allowed by a `#[bincode(max_len = N)]` attribute.
+    StringTooLong {
+        /// The length of the string or byte slice in bytes
+        len: usize,
+        /// The maximum length in bytes
+        max: usize,
+    },
+
     /// The RefCell<T> is already borrowed
     RefCellAlreadyBorrowed {
         /// The inner borrow error
@@ -80,6 +115,40 @@ pub enum DecodeError {
     /// The given configuration limit was exceeded
     LimitExceeded,
 
+    /// The value that is being decoded is nested deeper than the configured depth limit. See [with_depth_limit].
+    ///
+    /// [with_depth_limit]: ../config/struct.Configuration.html#method.with_depth_limit
+    DepthLimitExceeded {
+        /// The configured depth limit
+        limit: usize,
+    },
+
+    /// The decoder tried to decode a collection with more entries than the configured maximum, or than allowed by a `#[bincode(max_len = N)]` attribute. See [with_max_collection_len].
+    ///
+    /// [with_max_collection_len]: ../config/struct.Configuration.html#method.with_max_collection_len
+    CollectionTooLong {
+        /// The amount of entries of the collection
+        len: usize,
+        /// The configured maximum amount of entries
+        max: usize,
+    },
+
+    /// The decoder tried to decode a string or byte slice that is longer than the configured maximum, or than allowed by a `#[bincode(max_len = N)]` attribute. See [with_max_string_len].
+    ///
+    /// [with_max_string_len]: ../config/struct.Configuration.html#method.with_max_string_len
+    StringTooLong {
+        /// The length of the string or byte slice in bytes
+        len: usize,
+        /// The configured maximum length in bytes
+        max: usize,
+    },
+
+    /// The decoder could not allocate enough memory to decode a collection of type `type_name`.
+    AllocationFailed {
+        /// The name of the collection that could not be allocated
+        type_name: &'static str,
+    },
+
     /// Invalid type was found. The decoder tried to read type `expected`, but found type `found` instead.
     InvalidIntegerType {
         /// The type that was being read from the reader
@@ -88,6 +157,41 @@ pub enum DecodeError {
         found: IntegerType,
     },
 
+    /// The decoder tried to decode a LEB128 encoded integer of type `expected`, but the encoded value does not fit in that type.
+    Leb128Overflow {
+        /// The type that was being read from the reader
+        expected: IntegerType,
+    },
+
+    /// The decoder tried to decode a variable-length integer of type `integer_type`, but it was encoded with more bytes than needed. This is only returned with [with_strict_decoding].
+    ///
+    /// [with_strict_decoding]: ../config/struct.Configuration.html#method.with_strict_decoding
+    NonCanonicalInteger {
+        /// The type that was being read from the reader
+        integer_type: IntegerType,
+    },
+
+    /// The decoder tried to decode a `f32` or `f64`, but found a `NaN` that is not the canonical `NaN`. This is only returned with [with_strict_decoding].
+    ///
+    /// [with_strict_decoding]: ../config/struct.Configuration.html#method.with_strict_decoding
+    NonCanonicalNaN,
+
+    /// The key of the entry at `index` of a map or set is not greater than the key of the previous entry, so the entries are either unsorted or contain a duplicate key. This is only returned with [with_strict_decoding].
+    ///
+    /// [with_strict_decoding]: ../config/struct.Configuration.html#method.with_strict_decoding
+    UnsortedKeys {
+        /// The index of the entry that was out of order
+        index: usize,
+    },
+
+    /// The entry at `index` of a map or set has the same key as an earlier entry. This is only returned with [with_duplicate_key_error].
+    ///
+    /// [with_duplicate_key_error]: ../config/struct.Configuration.html#method.with_duplicate_key_error
+    DuplicateMapKey {
+        /// The index of the entry with the duplicate key
+        index: usize,
+    },
+
     /// The decoder tried to decode any of the `NonZero*` types but the value is zero
     NonZeroTypeIsZero {
         /// The type that was being read from the reader
@@ -126,6 +230,35 @@ pub enum DecodeError {
         found: usize,
     },
 
+    /// The decoder tried to borrow a slice of `type_name`, but the config does not encode it as its in-memory bytes. Borrowing a slice of integers requires [with_fixed_int_encoding] and the endianness of the target, borrowing a slice of floats only requires the endianness of the target.
+    ///
+    /// [with_fixed_int_encoding]: ../config/struct.Configuration.html#method.with_fixed_int_encoding
+    NonNativeSlice {
+        /// The type of the values in the slice
+        type_name: &'static str,
+    },
+
+    /// The decoder tried to borrow a slice of `type_name`, but the values in the input are not aligned to `align` bytes. Use [AlignedSlice] to encode slices so they can be borrowed.
+    ///
+    /// [AlignedSlice]: ../struct.AlignedSlice.html
+    UnalignedSlice {
+        /// The type of the values in the slice
+        type_name: &'static str,
+        /// The alignment that `type_name` requires
+        align: usize,
+    },
+
+    /// The decoder tried to borrow an [AlignedSlice] of `type_name`, but the padding in front of its values is invalid. Either `padding` is not smaller than the alignment of `type_name`, or the padding bytes are not zero. The padding bytes are only checked with [with_strict_decoding].
+    ///
+    /// [AlignedSlice]: ../struct.AlignedSlice.html
+    /// [with_strict_decoding]: ../config/struct.Configuration.html#method.with_strict_decoding
+    InvalidSlicePadding {
+        /// The type of the values in the slice
+        type_name: &'static str,
+        /// The amount of padding that was found
+        padding: usize,
+    },
+
     /// The encoded value is outside of the range of the target usize type.
     ///
     /// This can happen if an usize was encoded on an architecture with a larger
@@ -134,6 +267,14 @@ pub enum DecodeError {
     /// cause this error.
     OutsideUsizeRange(u64),
 
+    /// The value was decoded successfully, but the input contained more bytes than were needed. This is returned by the `_exact` decode functions, e.g. [decode_from_slice_exact].
+    ///
+    /// [decode_from_slice_exact]: ../fn.decode_from_slice_exact.html
+    TrailingBytes {
+        /// The amount of bytes that were left in the input
+        remaining: usize,
+    },
+
     /// Tried to decode an enum with no variants
     EmptyEnum {
         /// The type that was being decoded
@@ -141,6 +282,10 @@ pub enum DecodeError {
     },
 
     /// The decoder tried to decode a Duration and overflowed the number of seconds.
+    ///
+    /// With [with_strict_decoding], this is also returned when the number of nanoseconds is not less than one second.
+    ///
+    /// [with_strict_decoding]: ../config/struct.Configuration.html#method.with_strict_decoding
     InvalidDuration {
         /// The number of seconds in the duration.
         secs: u64,
@@ -198,8 +343,8 @@ impl core::fmt::Display for DecodeError {
 }
 
 impl DecodeError {
-    /// If the current error is `InvalidIntegerType`, change the `expected` and
-    /// `found` values from `Ux` to `Ix`. This is needed to have correct error
+    /// If the current error is `InvalidIntegerType` or `NonCanonicalInteger`, change
+    /// the integer types from `Ux` to `Ix`. This is needed to have correct error
     /// reporting in src/varint/decode_signed.rs since this calls
     /// src/varint/decode_unsigned.rs and needs to correct the `expected` and
     /// `found` types.
@@ -209,6 +354,9 @@ impl DecodeError {
                 expected: expected.into_signed(),
                 found: found.into_signed(),
             },
+            Self::NonCanonicalInteger { integer_type } => Self::NonCanonicalInteger {
+                integer_type: integer_type.into_signed(),
+            },
             other => other,
         }
     }
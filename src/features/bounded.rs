@@ -0,0 +1,362 @@
+use crate::{
+    config::StaticConfig,
+    de::{BorrowDecodeMaxLen, BorrowDecoder, Decode, DecodeInPlace, DecodeMaxLen, Decoder},
+    enc::{
+        max_size::len_size, max_size::max_size_mul, max_size_add, Encode, Encoder, MaxEncodedSize,
+    },
+    error::{DecodeError, EncodeError},
+    BorrowDecode,
+};
+use alloc::{string::String, vec::Vec};
+use core::ops::Deref;
+
+/// A `Vec<T>` that holds at most `N` entries.
+///
+/// The bound is enforced when constructing the vec, and when decoding it the length is checked before any memory is allocated. A `BoundedVec` with more than `N` entries fails to decode with [DecodeError::CollectionTooLong], or with [DecodeError::StringTooLong] if it is a `BoundedVec<u8, N>`, which is limited like a byte slice.
+///
+/// The encoding of a `BoundedVec` is the same as the encoding of a `Vec`.
+///
+/// ```
+/// use bincode::BoundedVec;
+/// use bincode::error::DecodeError;
+///
+/// let vec = BoundedVec::<u32, 2>::try_from(vec![1, 2]).unwrap();
+/// assert_eq!(vec.len(), 2);
+/// assert!(BoundedVec::<u32, 2>::try_from(vec![1, 2, 3]).is_err());
+///
+/// let encoded = bincode::encode_to_vec(vec![1u32, 2, 3], bincode::config::standard()).unwrap();
+/// let result = bincode::decode_from_slice::<BoundedVec<u32, 2>, _>(&encoded, bincode::config::standard());
+/// assert!(matches!(result, Err(DecodeError::CollectionTooLong { len: 3, max: 2 })));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoundedVec<T, const N: usize>(Vec<T>);
+
+impl<T, const N: usize> BoundedVec<T, N> {
+    /// Create a new, empty `BoundedVec`.
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Append an entry to the back of the vec. If the vec already holds `N` entries, the entry is returned instead.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.0.len() >= N {
+            Err(value)
+        } else {
+            self.0.push(value);
+            Ok(())
+        }
+    }
+
+    /// Remove the last entry of the vec and return it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    /// Remove all entries of the vec.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Return the inner `Vec<T>`.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Default for BoundedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for BoundedVec<T, N> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for BoundedVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> TryFrom<Vec<T>> for BoundedVec<T, N> {
+    /// The vec is returned if it holds more than `N` entries.
+    type Error = Vec<T>;
+
+    fn try_from(vec: Vec<T>) -> Result<Self, Vec<T>> {
+        if vec.len() > N {
+            Err(vec)
+        } else {
+            Ok(Self(vec))
+        }
+    }
+}
+
+impl<T, const N: usize> From<BoundedVec<T, N>> for Vec<T> {
+    fn from(vec: BoundedVec<T, N>) -> Self {
+        vec.0
+    }
+}
+
+impl<T, const N: usize> Decode for BoundedVec<T, N>
+where
+    T: Decode,
+{
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Vec::decode_max_len(decoder, N).map(Self)
+    }
+}
+
+impl<T, const N: usize> DecodeInPlace for BoundedVec<T, N> where T: Decode {}
+
+impl<'de, T, const N: usize> BorrowDecode<'de> for BoundedVec<T, N>
+where
+    T: BorrowDecode<'de>,
+{
+    fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Vec::borrow_decode_max_len(decoder, N).map(Self)
+    }
+}
+
+impl<T, const N: usize> Encode for BoundedVec<T, N>
+where
+    T: Encode,
+{
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<T, C, const N: usize> MaxEncodedSize<C> for BoundedVec<T, N>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> =
+        max_size_add(Some(len_size::<C>(N)), max_size_mul(T::MAX_SIZE, N));
+}
+
+/// A `String` that is at most `N` bytes long.
+///
+/// The bound is enforced when constructing the string, and when decoding it the length is checked before any memory is allocated. A `BoundedString` that is longer than `N` bytes fails to decode with [DecodeError::StringTooLong].
+///
+/// The encoding of a `BoundedString` is the same as the encoding of a `String`.
+///
+/// When a `BoundedString` is decoded through serde, the length can only be checked once the deserializer hands over the string. The serde functions of bincode do this without a copy if the string is borrowed from a slice or already in the buffer of the reader, but other readers, e.g. the one of `bincode::serde::decode_from_std_read`, read the whole string into memory first. Decode it with [Decode] or set [with_max_string_len] to limit the length for those readers.
+///
+/// ```
+/// use bincode::BoundedString;
+/// use bincode::error::DecodeError;
+///
+/// let string = BoundedString::<3>::try_from("abc").unwrap();
+/// assert_eq!(string.as_str(), "abc");
+/// assert!(BoundedString::<3>::try_from("abcd").is_err());
+///
+/// let encoded = bincode::encode_to_vec("abcd", bincode::config::standard()).unwrap();
+/// let result = bincode::decode_from_slice::<BoundedString<3>, _>(&encoded, bincode::config::standard());
+/// assert!(matches!(result, Err(DecodeError::StringTooLong { len: 4, max: 3 })));
+/// ```
+///
+/// [with_max_string_len]: config/struct.Configuration.html#method.with_max_string_len
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoundedString<const N: usize>(String);
+
+impl<const N: usize> BoundedString<N> {
+    /// Create a new, empty `BoundedString`.
+    pub const fn new() -> Self {
+        Self(String::new())
+    }
+
+    /// Append a character to the end of the string. If the string would become longer than `N` bytes, the character is returned instead.
+    pub fn try_push(&mut self, ch: char) -> Result<(), char> {
+        if self.0.len() + ch.len_utf8() > N {
+            Err(ch)
+        } else {
+            self.0.push(ch);
+            Ok(())
+        }
+    }
+
+    /// Remove all contents of the string.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Return the inner `String`.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<const N: usize> Deref for BoundedString<N> {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<str> for BoundedString<N> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> TryFrom<String> for BoundedString<N> {
+    /// The string is returned if it is longer than `N` bytes.
+    type Error = String;
+
+    fn try_from(string: String) -> Result<Self, String> {
+        if string.len() > N {
+            Err(string)
+        } else {
+            Ok(Self(string))
+        }
+    }
+}
+
+impl<'a, const N: usize> TryFrom<&'a str> for BoundedString<N> {
+    /// The string slice is returned if it is longer than `N` bytes.
+    type Error = &'a str;
+
+    fn try_from(s: &'a str) -> Result<Self, &'a str> {
+        if s.len() > N {
+            Err(s)
+        } else {
+            Ok(Self(String::from(s)))
+        }
+    }
+}
+
+impl<const N: usize> From<BoundedString<N>> for String {
+    fn from(string: BoundedString<N>) -> Self {
+        string.0
+    }
+}
+
+impl<const N: usize> Decode for BoundedString<N> {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        String::decode_max_len(decoder, N).map(Self)
+    }
+}
+
+impl<const N: usize> DecodeInPlace for BoundedString<N> {}
+
+impl<'de, const N: usize> BorrowDecode<'de> for BoundedString<N> {
+    fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        String::borrow_decode_max_len(decoder, N).map(Self)
+    }
+}
+
+impl<const N: usize> Encode for BoundedString<N> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<C: StaticConfig, const N: usize> MaxEncodedSize<C> for BoundedString<N> {
+    const MAX_SIZE: Option<usize> = max_size_add(Some(len_size::<C>(N)), Some(N));
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::{BoundedString, BoundedVec};
+    use alloc::{string::String, vec::Vec};
+    use core::{fmt, marker::PhantomData};
+    use serde::de::{Deserialize, Deserializer, Error, Expected, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<T, const N: usize> Serialize for BoundedVec<T, N>
+    where
+        T: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize(serializer)
+        }
+    }
+
+    struct BoundedVecVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for BoundedVecVisitor<T, N>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = BoundedVec<T, N>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a sequence of at most {} entries", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // Check the length up front, so no memory is allocated for sequences that are too long
+            let len = seq.size_hint().unwrap_or(0);
+            if len > N {
+                return Err(A::Error::invalid_length(len, &self));
+            }
+            let mut vec = Vec::with_capacity(len);
+            while let Some(value) = seq.next_element()? {
+                if vec.len() >= N {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                vec.push(value);
+            }
+            Ok(BoundedVec(vec))
+        }
+    }
+
+    impl<'de, T, const N: usize> Deserialize<'de> for BoundedVec<T, N>
+    where
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(BoundedVecVisitor(PhantomData))
+        }
+    }
+
+    impl<const N: usize> Serialize for BoundedString<N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize(serializer)
+        }
+    }
+
+    struct MaxStringLen<const N: usize>;
+
+    impl<const N: usize> Expected for MaxStringLen<N> {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a string of at most {} bytes", N)
+        }
+    }
+
+    struct BoundedStringVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for BoundedStringVisitor<N> {
+        type Value = BoundedString<N>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            MaxStringLen::<N>.fmt(formatter)
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+            // Check the length before the string is copied
+            if value.len() > N {
+                return Err(E::invalid_length(value.len(), &MaxStringLen::<N>));
+            }
+            Ok(BoundedString(String::from(value)))
+        }
+
+        fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+            BoundedString::try_from(value)
+                .map_err(|value| E::invalid_length(value.len(), &MaxStringLen::<N>))
+        }
+    }
+
+    impl<'de, const N: usize> Deserialize<'de> for BoundedString<N> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_str(BoundedStringVisitor)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    de::{BorrowDecoder, Decode, Decoder},
-    enc::{self, Encode, Encoder},
+    config::{DuplicateKeyPolicy, InternalConfig, StaticConfig},
+    de::{BorrowDecodeMaxLen, BorrowDecoder, Decode, DecodeInPlace, DecodeMaxLen, Decoder},
+    enc::{self, max_size_add, Encode, EncodeMaxLen, Encoder, MaxEncodedEntrySize, MaxEncodedSize},
     error::{DecodeError, EncodeError},
     impl_borrow_decode, BorrowDecode, Config,
 };
@@ -9,30 +10,91 @@ use alloc::sync::Arc;
 use alloc::{
     borrow::{Cow, ToOwned},
     boxed::Box,
-    collections::*,
+    collections::{btree_map::Entry, *},
     rc::Rc,
     string::String,
     vec::Vec,
 };
 
-#[derive(Default)]
-pub(crate) struct VecWriter {
+/// Maps the error of a failed `try_reserve` of collection `T` to a [DecodeError].
+pub(crate) fn allocation_failed<T>(_: TryReserveError) -> DecodeError {
+    DecodeError::AllocationFailed {
+        type_name: core::any::type_name::<T>(),
+    }
+}
+
+/// Decodes the bytes of a string or byte buffer. Unlike `Vec<u8>`, their length is limited by `max_len` and the maximum string length of the config.
+pub(crate) fn decode_string_bytes<D: Decoder>(
+    decoder: &mut D,
+    max_len: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let len = crate::de::decode_string_len(decoder, max_len)?;
+    decoder.claim_container_read::<u8>(len)?;
+    let mut bytes = Vec::new();
+    u8::decode_vec(decoder, &mut bytes, len)?;
+    Ok(bytes)
+}
+
+/// A writer that appends the encoded bytes to a `Vec<u8>`.
+///
+/// A `VecWriter` can be created from an existing `Vec<u8>`, in which case the bytes are appended after its current contents and its capacity is reused.
+///
+/// ```
+/// use bincode::enc::{Encode, EncoderImpl};
+/// use bincode::VecWriter;
+///
+/// let mut encoder = EncoderImpl::new(VecWriter::from(vec![1, 2]), bincode::config::standard());
+/// 3u8.encode(&mut encoder).unwrap();
+/// "abc".encode(&mut encoder).unwrap();
+/// assert_eq!(encoder.into_writer().into_inner(), vec![1, 2, 3, 3, b'a', b'b', b'c']);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug, Default)]
+pub struct VecWriter {
     inner: Vec<u8>,
 }
 
 impl VecWriter {
-    // May not be used in all feature combinations
-    #[allow(dead_code)]
-    pub(crate) fn collect(self) -> Vec<u8> {
+    /// Create a new `VecWriter` with an empty `Vec<u8>`.
+    pub const fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    /// Create a new `VecWriter` with an empty `Vec<u8>` that can hold at least `capacity` bytes without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Return the bytes that have been written so far, including the contents of the `Vec<u8>` the writer was created from.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Return the inner `Vec<u8>`.
+    pub fn into_inner(self) -> Vec<u8> {
         self.inner
     }
 }
 
+impl From<Vec<u8>> for VecWriter {
+    fn from(inner: Vec<u8>) -> Self {
+        Self { inner }
+    }
+}
+
 impl enc::write::Writer for VecWriter {
+    #[inline]
     fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
         self.inner.extend_from_slice(bytes);
         Ok(())
     }
+
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        Some(self.inner.len())
+    }
 }
 
 /// Encode the given value into a `Vec<u8>` with the given `Config`. See the [config] module for more information.
@@ -40,28 +102,85 @@ impl enc::write::Writer for VecWriter {
 /// [config]: config/index.html
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 pub fn encode_to_vec<E: enc::Encode, C: Config>(val: E, config: C) -> Result<Vec<u8>, EncodeError> {
-    let writer = VecWriter::default();
+    // Calculate the size up front, so the vec only has to be allocated once
+    let size = crate::encoded_size(&val, config)?;
+    let writer = VecWriter::with_capacity(size);
     let mut encoder = enc::EncoderImpl::<_, C>::new(writer, config);
     val.encode(&mut encoder)?;
     Ok(encoder.into_writer().inner)
 }
 
+/// Encode the given value to the end of `dst` with the given `Config`. Returns the amount of bytes that have been appended.
+///
+/// The capacity of `dst` is reused, so encoding many values into the same `Vec<u8>` only allocates when it has to grow. If encoding fails, `dst` is restored to its original length.
+///
+/// ```
+/// let config = bincode::config::standard();
+/// let mut buffer = Vec::new();
+/// assert_eq!(bincode::encode_append_to_vec(5u8, &mut buffer, config).unwrap(), 1);
+/// assert_eq!(bincode::encode_append_to_vec("abc", &mut buffer, config).unwrap(), 4);
+/// assert_eq!(buffer, [5, 3, b'a', b'b', b'c']);
+///
+/// // Reuse the buffer for the next batch
+/// buffer.clear();
+/// bincode::encode_append_to_vec(300u32, &mut buffer, config).unwrap();
+/// assert_eq!(buffer, [251, 44, 1]);
+/// ```
+///
+/// See the [config] module for more information.
+///
+/// [config]: config/index.html
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_append_to_vec<E: enc::Encode, C: Config>(
+    val: E,
+    dst: &mut Vec<u8>,
+    config: C,
+) -> Result<usize, EncodeError> {
+    let start = dst.len();
+    let mut encoder = enc::EncoderImpl::<_, C>::new(VecWriter::from(core::mem::take(dst)), config);
+    let result = val.encode(&mut encoder);
+    *dst = encoder.into_writer().inner;
+    match result {
+        Ok(()) => Ok(dst.len() - start),
+        Err(e) => {
+            dst.truncate(start);
+            Err(e)
+        }
+    }
+}
+
 impl<T> Decode for BinaryHeap<T>
 where
     T: Decode + Ord,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<T> DecodeInPlace for BinaryHeap<T> where T: Decode + Ord {}
+
+impl<T> DecodeMaxLen for BinaryHeap<T>
+where
+    T: Decode + Ord,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<T>(len)?;
 
-        let mut map = BinaryHeap::with_capacity(len);
+        let mut map = BinaryHeap::new();
+        map.try_reserve(crate::de::preallocation_len::<T, _>(decoder, len))
+            .map_err(allocation_failed::<Self>)?;
         for _ in 0..len {
             // See the documentation on `unclaim_bytes_read` as to why we're doing this here
             decoder.unclaim_bytes_read(core::mem::size_of::<T>());
 
+            map.try_reserve(1).map_err(allocation_failed::<Self>)?;
             let key = T::decode(decoder)?;
             map.push(key);
         }
+        decoder.unclaim_depth();
         Ok(map)
     }
 }
@@ -70,17 +189,34 @@ where
     T: BorrowDecode<'de> + Ord,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<'de, T> BorrowDecodeMaxLen<'de> for BinaryHeap<T>
+where
+    T: BorrowDecode<'de> + Ord,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<T>(len)?;
 
-        let mut map = BinaryHeap::with_capacity(len);
+        let mut map = BinaryHeap::new();
+        map.try_reserve(crate::de::preallocation_len::<T, _>(decoder, len))
+            .map_err(allocation_failed::<Self>)?;
         for _ in 0..len {
             // See the documentation on `unclaim_bytes_read` as to why we're doing this here
             decoder.unclaim_bytes_read(core::mem::size_of::<T>());
 
+            map.try_reserve(1).map_err(allocation_failed::<Self>)?;
             let key = T::borrow_decode(decoder)?;
             map.push(key);
         }
+        decoder.unclaim_depth();
         Ok(map)
     }
 }
@@ -98,45 +234,131 @@ where
     }
 }
 
+impl<T> EncodeMaxLen for BinaryHeap<T>
+where
+    T: Encode + Ord,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        enc::check_collection_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
 impl<K, V> Decode for BTreeMap<K, V>
 where
     K: Decode + Ord,
     V: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<K, V> DecodeMaxLen for BTreeMap<K, V>
+where
+    K: Decode + Ord,
+    V: Decode,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<(K, V)>(len)?;
 
         let mut map = BTreeMap::new();
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
+        decode_btree_map_entries(decoder, &mut map, len, K::decode, V::decode)?;
+        decoder.unclaim_depth();
+        Ok(map)
+    }
+}
 
-            let key = K::decode(decoder)?;
-            let value = V::decode(decoder)?;
-            map.insert(key, value);
+impl<K, V> DecodeInPlace for BTreeMap<K, V>
+where
+    K: Decode + Ord,
+    V: Decode,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<(K, V)>(len)?;
+
+        self.clear();
+        decode_btree_map_entries(decoder, self, len, K::decode, V::decode)?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
+
+/// Decodes `len` entries with `decode_key` and `decode_value`, and inserts them into `map`. The length has to be decoded and claimed already.
+///
+/// This checks the order of the keys in strict mode and applies the duplicate key policy of the config. It is shared by the `Decode` and `BorrowDecode` implementations.
+fn decode_btree_map_entries<K, V, D, FK, FV>(
+    decoder: &mut D,
+    map: &mut BTreeMap<K, V>,
+    len: usize,
+    mut decode_key: FK,
+    mut decode_value: FV,
+) -> Result<(), DecodeError>
+where
+    K: Ord,
+    D: Decoder,
+    FK: FnMut(&mut D) -> Result<K, DecodeError>,
+    FV: FnMut(&mut D) -> Result<V, DecodeError>,
+{
+    for index in 0..len {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
+
+        let key = decode_key(decoder)?;
+        if decoder.config().is_strict() && map.keys().next_back() >= Some(&key) {
+            return Err(DecodeError::UnsortedKeys { index });
+        }
+        let value = decode_value(decoder)?;
+        match map.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match decoder.config().duplicate_key_policy() {
+                DuplicateKeyPolicy::LastWins => {
+                    entry.insert(value);
+                }
+                DuplicateKeyPolicy::FirstWins => {}
+                DuplicateKeyPolicy::Error => return Err(DecodeError::DuplicateMapKey { index }),
+            },
         }
-        Ok(map)
     }
+    Ok(())
 }
+
 impl<'de, K, V> BorrowDecode<'de> for BTreeMap<K, V>
 where
     K: BorrowDecode<'de> + Ord,
     V: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<'de, K, V> BorrowDecodeMaxLen<'de> for BTreeMap<K, V>
+where
+    K: BorrowDecode<'de> + Ord,
+    V: BorrowDecode<'de>,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<(K, V)>(len)?;
 
         let mut map = BTreeMap::new();
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
-
-            let key = K::borrow_decode(decoder)?;
-            let value = V::borrow_decode(decoder)?;
-            map.insert(key, value);
-        }
+        decode_btree_map_entries(decoder, &mut map, len, K::borrow_decode, V::borrow_decode)?;
+        decoder.unclaim_depth();
         Ok(map)
     }
 }
@@ -156,42 +378,126 @@ where
     }
 }
 
+impl<K, V> EncodeMaxLen for BTreeMap<K, V>
+where
+    K: Encode + Ord,
+    V: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        enc::check_collection_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
 impl<T> Decode for BTreeSet<T>
 where
     T: Decode + Ord,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<T> DecodeMaxLen for BTreeSet<T>
+where
+    T: Decode + Ord,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<T>(len)?;
 
-        let mut map = BTreeSet::new();
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+        let mut set = BTreeSet::new();
+        decode_btree_set_items(decoder, &mut set, len, T::decode)?;
+        decoder.unclaim_depth();
+        Ok(set)
+    }
+}
 
-            let key = T::decode(decoder)?;
-            map.insert(key);
+impl<T> DecodeInPlace for BTreeSet<T>
+where
+    T: Decode + Ord,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<T>(len)?;
+
+        self.clear();
+        decode_btree_set_items(decoder, self, len, T::decode)?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
+
+/// Decodes `len` items with `decode` and inserts them into `set`. The length has to be decoded and claimed already.
+///
+/// This checks the order of the items in strict mode and applies the duplicate key policy of the config. It is shared by the `Decode` and `BorrowDecode` implementations.
+fn decode_btree_set_items<T, D, F>(
+    decoder: &mut D,
+    set: &mut BTreeSet<T>,
+    len: usize,
+    mut decode: F,
+) -> Result<(), DecodeError>
+where
+    T: Ord,
+    D: Decoder,
+    F: FnMut(&mut D) -> Result<T, DecodeError>,
+{
+    for index in 0..len {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+
+        let key = decode(decoder)?;
+        if decoder.config().is_strict() && set.iter().next_back() >= Some(&key) {
+            return Err(DecodeError::UnsortedKeys { index });
+        }
+        match decoder.config().duplicate_key_policy() {
+            DuplicateKeyPolicy::LastWins => {
+                set.replace(key);
+            }
+            DuplicateKeyPolicy::FirstWins => {
+                set.insert(key);
+            }
+            DuplicateKeyPolicy::Error => {
+                if !set.insert(key) {
+                    return Err(DecodeError::DuplicateMapKey { index });
+                }
+            }
         }
-        Ok(map)
     }
+    Ok(())
 }
+
 impl<'de, T> BorrowDecode<'de> for BTreeSet<T>
 where
     T: BorrowDecode<'de> + Ord,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
-        decoder.claim_container_read::<T>(len)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
 
-        let mut map = BTreeSet::new();
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+impl<'de, T> BorrowDecodeMaxLen<'de> for BTreeSet<T>
+where
+    T: BorrowDecode<'de> + Ord,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            let key = T::borrow_decode(decoder)?;
-            map.insert(key);
-        }
-        Ok(map)
+        let mut set = BTreeSet::new();
+        decode_btree_set_items(decoder, &mut set, len, T::borrow_decode)?;
+        decoder.unclaim_depth();
+        Ok(set)
     }
 }
 
@@ -208,23 +514,62 @@ where
     }
 }
 
+impl<T> EncodeMaxLen for BTreeSet<T>
+where
+    T: Encode + Ord,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        enc::check_collection_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
 impl<T> Decode for VecDeque<T>
 where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<T> DecodeMaxLen for VecDeque<T>
+where
+    T: Decode,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::decode_vec_len(decoder, max_len)?;
         decoder.claim_container_read::<T>(len)?;
 
-        let mut map = VecDeque::with_capacity(len);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+        // Converting a `Vec` into a `VecDeque` does not reallocate
+        let mut vec = Vec::new();
+        T::decode_vec(decoder, &mut vec, len)?;
+        decoder.unclaim_depth();
+        Ok(VecDeque::from(vec))
+    }
+}
 
-            let key = T::decode(decoder)?;
-            map.push_back(key);
-        }
-        Ok(map)
+impl<T> DecodeInPlace for VecDeque<T>
+where
+    T: DecodeInPlace,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::decode_vec_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<T>(len)?;
+
+        // Converting between `Vec` and `VecDeque` keeps the entries and the allocation, so they can be reused
+        let mut vec = Vec::from(core::mem::take(self));
+        let result = T::decode_vec_in_place(decoder, &mut vec, len);
+        *self = VecDeque::from(vec);
+        result?;
+        decoder.unclaim_depth();
+        Ok(())
     }
 }
 impl<'de, T> BorrowDecode<'de> for VecDeque<T>
@@ -232,18 +577,27 @@ where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
-        decoder.claim_container_read::<T>(len)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
 
-        let mut map = VecDeque::with_capacity(len);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+impl<'de, T> BorrowDecodeMaxLen<'de> for VecDeque<T>
+where
+    T: BorrowDecode<'de>,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::borrow_decode_vec_len(decoder, max_len)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            let key = T::borrow_decode(decoder)?;
-            map.push_back(key);
-        }
-        Ok(map)
+        // Converting a `Vec` into a `VecDeque` does not reallocate
+        let mut vec = Vec::new();
+        T::borrow_decode_vec(decoder, &mut vec, len)?;
+        decoder.unclaim_depth();
+        Ok(VecDeque::from(vec))
     }
 }
 
@@ -253,10 +607,23 @@ where
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         crate::enc::encode_slice_len(encoder, self.len())?;
-        for item in self.iter() {
-            item.encode(encoder)?;
-        }
-        Ok(())
+        let (front, back) = self.as_slices();
+        T::encode_slice(front, encoder)?;
+        T::encode_slice(back, encoder)
+    }
+}
+
+impl<T> EncodeMaxLen for VecDeque<T>
+where
+    T: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        T::check_slice_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
     }
 }
 
@@ -265,35 +632,65 @@ where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
-        decoder.claim_container_read::<T>(len)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
 
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+impl<T> DecodeMaxLen for Vec<T>
+where
+    T: Decode,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::decode_vec_len(decoder, max_len)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            vec.push(T::decode(decoder)?);
-        }
+        let mut vec = Vec::new();
+        T::decode_vec(decoder, &mut vec, len)?;
+        decoder.unclaim_depth();
         Ok(vec)
     }
 }
 
+impl<T> DecodeInPlace for Vec<T>
+where
+    T: DecodeInPlace,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::decode_vec_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<T>(len)?;
+
+        T::decode_vec_in_place(decoder, self, len)?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
+
 impl<'de, T> BorrowDecode<'de> for Vec<T>
 where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
-        decoder.claim_container_read::<T>(len)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
 
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+impl<'de, T> BorrowDecodeMaxLen<'de> for Vec<T>
+where
+    T: BorrowDecode<'de>,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = T::borrow_decode_vec_len(decoder, max_len)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            vec.push(T::borrow_decode(decoder)?);
-        }
+        let mut vec = Vec::new();
+        T::borrow_decode_vec(decoder, &mut vec, len)?;
+        decoder.unclaim_depth();
         Ok(vec)
     }
 }
@@ -304,22 +701,62 @@ where
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         crate::enc::encode_slice_len(encoder, self.len())?;
-        for item in self.iter() {
-            item.encode(encoder)?;
-        }
-        Ok(())
+        T::encode_slice(self, encoder)
+    }
+}
+
+impl<T> EncodeMaxLen for Vec<T>
+where
+    T: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        T::check_slice_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
     }
 }
 
 impl Decode for String {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let bytes = Vec::<u8>::decode(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+impl_borrow_decode!(String);
+
+impl DecodeMaxLen for String {
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        let bytes = decode_string_bytes(decoder, max_len)?;
         String::from_utf8(bytes).map_err(|e| DecodeError::Utf8 {
             inner: e.utf8_error(),
         })
     }
 }
-impl_borrow_decode!(String);
+
+impl DecodeInPlace for String {
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        let len = crate::de::decode_string_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<u8>(len)?;
+
+        let mut bytes = core::mem::take(self).into_bytes();
+        u8::decode_vec_in_place(decoder, &mut bytes, len)?;
+        *self = String::from_utf8(bytes).map_err(|e| DecodeError::Utf8 {
+            inner: e.utf8_error(),
+        })?;
+        Ok(())
+    }
+}
+
+impl<'de> BorrowDecodeMaxLen<'de> for String {
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        Self::decode_max_len(decoder, max_len)
+    }
+}
 
 impl Decode for Box<str> {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -328,27 +765,71 @@ impl Decode for Box<str> {
 }
 impl_borrow_decode!(Box<str>);
 
+impl DecodeInPlace for Box<str> {}
+
+impl DecodeMaxLen for Box<str> {
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        String::decode_max_len(decoder, max_len).map(String::into_boxed_str)
+    }
+}
+
+impl<'de> BorrowDecodeMaxLen<'de> for Box<str> {
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        Self::decode_max_len(decoder, max_len)
+    }
+}
+
 impl Encode for String {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.as_bytes().encode(encoder)
     }
 }
 
+impl EncodeMaxLen for String {
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        enc::check_string_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
 impl<T> Decode for Box<T>
 where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Box::new(t))
     }
 }
+
+impl<T> DecodeInPlace for Box<T>
+where
+    T: DecodeInPlace,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        (**self).decode_in_place(decoder)?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
 impl<'de, T> BorrowDecode<'de> for Box<T>
 where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::borrow_decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Box::new(t))
     }
 }
@@ -362,6 +843,19 @@ where
     }
 }
 
+impl<T> EncodeMaxLen for Box<T>
+where
+    T: EncodeMaxLen + ?Sized,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        T::encode_max_len(self, encoder, max_len)
+    }
+}
+
 impl<T> Decode for Box<[T]>
 where
     T: Decode,
@@ -372,6 +866,8 @@ where
     }
 }
 
+impl<T> DecodeInPlace for Box<[T]> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for Box<[T]>
 where
     T: BorrowDecode<'de> + 'de,
@@ -382,6 +878,29 @@ where
     }
 }
 
+impl<T> DecodeMaxLen for Box<[T]>
+where
+    T: Decode,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        let vec = Vec::decode_max_len(decoder, max_len)?;
+        Ok(vec.into_boxed_slice())
+    }
+}
+
+impl<'de, T> BorrowDecodeMaxLen<'de> for Box<[T]>
+where
+    T: BorrowDecode<'de> + 'de,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        let vec = Vec::borrow_decode_max_len(decoder, max_len)?;
+        Ok(vec.into_boxed_slice())
+    }
+}
+
 impl<'cow, T> Decode for Cow<'cow, T>
 where
     T: ToOwned + ?Sized,
@@ -392,6 +911,14 @@ where
         Ok(Cow::Owned(t))
     }
 }
+
+impl<'cow, T> DecodeInPlace for Cow<'cow, T>
+where
+    T: ToOwned + ?Sized,
+    <T as ToOwned>::Owned: Decode,
+{
+}
+
 impl<'cow, T> BorrowDecode<'cow> for Cow<'cow, T>
 where
     T: ToOwned + ?Sized,
@@ -418,17 +945,23 @@ where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Rc::new(t))
     }
 }
 
+impl<T> DecodeInPlace for Rc<T> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for Rc<T>
 where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::borrow_decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Rc::new(t))
     }
 }
@@ -452,6 +985,8 @@ where
     }
 }
 
+impl<T> DecodeInPlace for Rc<[T]> where T: Decode {}
+
 impl<'de, T> BorrowDecode<'de> for Rc<[T]>
 where
     T: BorrowDecode<'de> + 'de,
@@ -468,11 +1003,16 @@ where
     T: Decode,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Arc::new(t))
     }
 }
 
+#[cfg(target_has_atomic = "ptr")]
+impl<T> DecodeInPlace for Arc<T> where T: Decode {}
+
 #[cfg(target_has_atomic = "ptr")]
 impl Decode for Arc<str> {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
@@ -481,13 +1021,18 @@ impl Decode for Arc<str> {
     }
 }
 
+#[cfg(target_has_atomic = "ptr")]
+impl DecodeInPlace for Arc<str> {}
+
 #[cfg(target_has_atomic = "ptr")]
 impl<'de, T> BorrowDecode<'de> for Arc<T>
 where
     T: BorrowDecode<'de>,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
         let t = T::borrow_decode(decoder)?;
+        decoder.unclaim_depth();
         Ok(Arc::new(t))
     }
 }
@@ -521,6 +1066,9 @@ where
     }
 }
 
+#[cfg(target_has_atomic = "ptr")]
+impl<T> DecodeInPlace for Arc<[T]> where T: Decode {}
+
 #[cfg(target_has_atomic = "ptr")]
 impl<'de, T> BorrowDecode<'de> for Arc<[T]>
 where
@@ -531,3 +1079,113 @@ where
         Ok(vec.into())
     }
 }
+
+impl<T, C: StaticConfig> MaxEncodedSize<C> for BinaryHeap<T> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for BinaryHeap<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<K, V, C: StaticConfig> MaxEncodedSize<C> for BTreeMap<K, V> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<K, V, C> MaxEncodedEntrySize<C> for BTreeMap<K, V>
+where
+    K: MaxEncodedSize<C>,
+    V: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = max_size_add(K::MAX_SIZE, V::MAX_SIZE);
+}
+
+impl<T, C: StaticConfig> MaxEncodedSize<C> for BTreeSet<T> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for BTreeSet<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C: StaticConfig> MaxEncodedSize<C> for VecDeque<T> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for VecDeque<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C: StaticConfig> MaxEncodedSize<C> for Vec<T> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for Vec<T>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for String {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<C: StaticConfig> MaxEncodedEntrySize<C> for String {
+    const MAX_ENTRY_SIZE: Option<usize> = Some(1);
+}
+
+impl<T, C> MaxEncodedSize<C> for Box<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C> MaxEncodedEntrySize<C> for Box<T>
+where
+    T: MaxEncodedEntrySize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_ENTRY_SIZE;
+}
+
+impl<T, C> MaxEncodedSize<C> for Rc<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, C> MaxEncodedSize<C> for Arc<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<'cow, T, C> MaxEncodedSize<C> for Cow<'cow, T>
+where
+    T: ToOwned + MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
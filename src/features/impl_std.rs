@@ -1,13 +1,22 @@
 use crate::{
-    config::Config,
-    de::{read::Reader, BorrowDecode, BorrowDecoder, Decode, Decoder, DecoderImpl},
-    enc::{write::Writer, Encode, Encoder, EncoderImpl},
+    config::{Config, DuplicateKeyPolicy, InternalConfig, StaticConfig},
+    de::{
+        read::{BorrowReader, Reader},
+        BorrowDecode, BorrowDecodeMaxLen, BorrowDecoder, Decode, DecodeInPlace, DecodeMaxLen,
+        Decoder, DecoderImpl,
+    },
+    enc::{
+        max_size_add, max_size_max, variant_index_max_size,
+        write::{SizeWriter, Writer},
+        Encode, EncodeMaxLen, Encoder, EncoderImpl, MaxEncodedEntrySize, MaxEncodedSize,
+    },
     error::{DecodeError, EncodeError},
+    features::impl_alloc::{allocation_failed, decode_string_bytes},
     impl_borrow_decode,
 };
-use core::time::Duration;
+use core::{ops::Range, time::Duration};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet},
     ffi::{CStr, CString},
     hash::Hash,
     io::Read,
@@ -15,6 +24,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{Mutex, RwLock},
     time::SystemTime,
+    vec::Vec,
 };
 
 /// Decode type `D` from the given reader with the given `Config`. The reader can be any type that implements `std::io::Read`, e.g. `std::fs::File`.
@@ -90,6 +100,11 @@ pub fn encode_into_std_write<E: Encode, C: Config, W: std::io::Write>(
     dst: &mut W,
     config: C,
 ) -> Result<usize, EncodeError> {
+    if config.limit().is_some() {
+        // Make sure the limit is not exceeded before anything is written to `dst`
+        let mut encoder = EncoderImpl::<_, C>::new(SizeWriter::default(), config);
+        val.encode(&mut encoder)?;
+    }
     let writer = IoWriter::new(dst);
     let mut encoder = EncoderImpl::<_, C>::new(writer, config);
     val.encode(&mut encoder)?;
@@ -126,6 +141,11 @@ impl<'storage, W: std::io::Write> Writer for IoWriter<'storage, W> {
         self.bytes_written += bytes.len();
         Ok(())
     }
+
+    #[inline]
+    fn position_hint(&self) -> Option<usize> {
+        Some(self.bytes_written)
+    }
 }
 
 impl<'a> Encode for &'a CStr {
@@ -142,7 +162,7 @@ impl Encode for CString {
 
 impl Decode for CString {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let vec = std::vec::Vec::decode(decoder)?;
+        let vec = decode_string_bytes(decoder, usize::MAX)?;
         CString::new(vec).map_err(|inner| DecodeError::CStringNulError {
             position: inner.nul_position(),
         })
@@ -150,6 +170,8 @@ impl Decode for CString {
 }
 impl_borrow_decode!(CString);
 
+impl DecodeInPlace for CString {}
+
 impl<T> Encode for Mutex<T>
 where
     T: Encode,
@@ -171,6 +193,8 @@ where
         Ok(Mutex::new(t))
     }
 }
+
+impl<T> DecodeInPlace for Mutex<T> where T: Decode {}
 impl<'de, T> BorrowDecode<'de> for Mutex<T>
 where
     T: BorrowDecode<'de>,
@@ -202,6 +226,8 @@ where
         Ok(RwLock::new(t))
     }
 }
+
+impl<T> DecodeInPlace for RwLock<T> where T: Decode {}
 impl<'de, T> BorrowDecode<'de> for RwLock<T>
 where
     T: BorrowDecode<'de>,
@@ -235,6 +261,8 @@ impl Decode for SystemTime {
 }
 impl_borrow_decode!(SystemTime);
 
+impl DecodeInPlace for SystemTime {}
+
 impl Encode for &'_ Path {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         match self.to_str() {
@@ -265,15 +293,17 @@ impl Decode for PathBuf {
 }
 impl_borrow_decode!(PathBuf);
 
+impl DecodeInPlace for PathBuf {}
+
 impl Encode for IpAddr {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         match self {
             IpAddr::V4(v4) => {
-                0u32.encode(encoder)?;
+                crate::enc::encode_variant_index(encoder, 0)?;
                 v4.encode(encoder)
             }
             IpAddr::V6(v6) => {
-                1u32.encode(encoder)?;
+                crate::enc::encode_variant_index(encoder, 1)?;
                 v6.encode(encoder)
             }
         }
@@ -282,7 +312,7 @@ impl Encode for IpAddr {
 
 impl Decode for IpAddr {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        match u32::decode(decoder)? {
+        match crate::de::decode_variant_index(decoder)? {
             0 => Ok(IpAddr::V4(Ipv4Addr::decode(decoder)?)),
             1 => Ok(IpAddr::V6(Ipv6Addr::decode(decoder)?)),
             found => Err(DecodeError::UnexpectedVariant {
@@ -295,9 +325,11 @@ impl Decode for IpAddr {
 }
 impl_borrow_decode!(IpAddr);
 
+impl DecodeInPlace for IpAddr {}
+
 impl Encode for Ipv4Addr {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        encoder.writer().write(&self.octets())
+        crate::enc::ClaimedWriter(encoder).write(&self.octets())
     }
 }
 
@@ -310,9 +342,11 @@ impl Decode for Ipv4Addr {
 }
 impl_borrow_decode!(Ipv4Addr);
 
+impl DecodeInPlace for Ipv4Addr {}
+
 impl Encode for Ipv6Addr {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
-        encoder.writer().write(&self.octets())
+        crate::enc::ClaimedWriter(encoder).write(&self.octets())
     }
 }
 
@@ -325,15 +359,17 @@ impl Decode for Ipv6Addr {
 }
 impl_borrow_decode!(Ipv6Addr);
 
+impl DecodeInPlace for Ipv6Addr {}
+
 impl Encode for SocketAddr {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         match self {
             SocketAddr::V4(v4) => {
-                0u32.encode(encoder)?;
+                crate::enc::encode_variant_index(encoder, 0)?;
                 v4.encode(encoder)
             }
             SocketAddr::V6(v6) => {
-                1u32.encode(encoder)?;
+                crate::enc::encode_variant_index(encoder, 1)?;
                 v6.encode(encoder)
             }
         }
@@ -342,7 +378,7 @@ impl Encode for SocketAddr {
 
 impl Decode for SocketAddr {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        match u32::decode(decoder)? {
+        match crate::de::decode_variant_index(decoder)? {
             0 => Ok(SocketAddr::V4(SocketAddrV4::decode(decoder)?)),
             1 => Ok(SocketAddr::V6(SocketAddrV6::decode(decoder)?)),
             found => Err(DecodeError::UnexpectedVariant {
@@ -355,6 +391,8 @@ impl Decode for SocketAddr {
 }
 impl_borrow_decode!(SocketAddr);
 
+impl DecodeInPlace for SocketAddr {}
+
 impl Encode for SocketAddrV4 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.ip().encode(encoder)?;
@@ -371,6 +409,8 @@ impl Decode for SocketAddrV4 {
 }
 impl_borrow_decode!(SocketAddrV4);
 
+impl DecodeInPlace for SocketAddrV4 {}
+
 impl Encode for SocketAddrV6 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.ip().encode(encoder)?;
@@ -387,6 +427,8 @@ impl Decode for SocketAddrV6 {
 }
 impl_borrow_decode!(SocketAddrV6);
 
+impl DecodeInPlace for SocketAddrV6 {}
+
 impl std::error::Error for EncodeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -406,6 +448,123 @@ impl std::error::Error for DecodeError {
     }
 }
 
+/// A decoder that records the bytes that are read from the wrapped decoder. This is used to check that the keys of unordered collections are sorted by their encoded bytes when strict decoding is enabled.
+struct RecordingDecoder<'a, D> {
+    decoder: &'a mut D,
+    bytes: Vec<u8>,
+}
+
+impl<'a, D: Decoder> RecordingDecoder<'a, D> {
+    fn new(decoder: &'a mut D) -> Self {
+        Self {
+            decoder,
+            bytes: Vec::new(),
+        }
+    }
+
+    /// Check that the recorded bytes of the key at `index` are greater than the bytes of the `previous` key, and store them in `previous`.
+    fn check_sorted(self, previous: &mut Vec<u8>, index: usize) -> Result<(), DecodeError> {
+        if index > 0 && self.bytes <= *previous {
+            return Err(DecodeError::UnsortedKeys { index });
+        }
+        *previous = self.bytes;
+        Ok(())
+    }
+}
+
+impl<'a, D> crate::utils::Sealed for RecordingDecoder<'a, D> {}
+
+impl<'a, D: Decoder> Decoder for RecordingDecoder<'a, D> {
+    type R = Self;
+
+    type C = D::C;
+
+    fn reader(&mut self) -> &mut Self::R {
+        self
+    }
+
+    fn config(&self) -> &Self::C {
+        self.decoder.config()
+    }
+
+    fn claim_bytes_read(&mut self, n: usize) -> Result<(), DecodeError> {
+        self.decoder.claim_bytes_read(n)
+    }
+
+    fn unclaim_bytes_read(&mut self, n: usize) {
+        self.decoder.unclaim_bytes_read(n)
+    }
+
+    fn claim_depth(&mut self) -> Result<(), DecodeError> {
+        self.decoder.claim_depth()
+    }
+
+    fn unclaim_depth(&mut self) {
+        self.decoder.unclaim_depth()
+    }
+}
+
+impl<'a, D: Decoder> Reader for RecordingDecoder<'a, D> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), DecodeError> {
+        self.decoder.reader().read(bytes)?;
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn peek_read(&mut self, n: usize) -> Option<&[u8]> {
+        self.decoder.reader().peek_read(n)
+    }
+
+    fn consume(&mut self, n: usize) {
+        if let Some(bytes) = self.decoder.reader().peek_read(n) {
+            self.bytes.extend_from_slice(&bytes[..n]);
+        }
+        self.decoder.reader().consume(n)
+    }
+}
+
+impl<'a, 'de, D: BorrowDecoder<'de>> BorrowDecoder<'de> for RecordingDecoder<'a, D> {
+    type BR = Self;
+
+    fn borrow_reader(&mut self) -> &mut Self::BR {
+        self
+    }
+}
+
+impl<'a, 'de, D: BorrowDecoder<'de>> BorrowReader<'de> for RecordingDecoder<'a, D> {
+    fn take_bytes(&mut self, length: usize) -> Result<&'de [u8], DecodeError> {
+        let bytes = self.decoder.borrow_reader().take_bytes(length)?;
+        self.bytes.extend_from_slice(bytes);
+        Ok(bytes)
+    }
+}
+
+/// The encoded keys of a hash collection, and its entries with the range of their key, as returned by [encode_sorted_keys].
+type SortedKeys<T> = (Vec<u8>, Vec<(Range<usize>, T)>);
+
+/// Encodes the keys of `entries` one after another into a single buffer, for the canonical encoding of hash collections.
+///
+/// Returns the buffer, and the entries with the range of their encoded key in the buffer, sorted by the encoded keys.
+fn encode_sorted_keys<'a, K, T, C>(
+    config: C,
+    entries: impl ExactSizeIterator<Item = (&'a K, T)>,
+) -> Result<SortedKeys<T>, EncodeError>
+where
+    K: Encode + 'a,
+    C: Config,
+{
+    let mut encoder = EncoderImpl::new(crate::VecWriter::new(), config);
+    let mut sorted = Vec::with_capacity(entries.len());
+    for (key, value) in entries {
+        let start = encoder.writer().as_slice().len();
+        key.encode(&mut encoder)?;
+        sorted.push((start..encoder.writer().as_slice().len(), value));
+    }
+    let keys = encoder.into_writer().into_inner();
+    sorted.sort_unstable_by(|(a, _), (b, _)| keys[a.clone()].cmp(&keys[b.clone()]));
+    Ok((keys, sorted))
+}
+
 impl<K, V, S> Encode for HashMap<K, V, S>
 where
     K: Encode,
@@ -413,6 +572,14 @@ where
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         crate::enc::encode_slice_len(encoder, self.len())?;
+        if encoder.config().is_canonical() {
+            let (keys, entries) = encode_sorted_keys(*encoder.config(), self.iter())?;
+            for (k, v) in entries {
+                crate::enc::ClaimedWriter(encoder).write(&keys[k])?;
+                Encode::encode(v, encoder)?;
+            }
+            return Ok(());
+        }
         for (k, v) in self.iter() {
             Encode::encode(k, encoder)?;
             Encode::encode(v, encoder)?;
@@ -421,6 +588,21 @@ where
     }
 }
 
+impl<K, V, S> EncodeMaxLen for HashMap<K, V, S>
+where
+    K: Encode,
+    V: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        crate::enc::check_collection_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
 impl<K, V, S> Decode for HashMap<K, V, S>
 where
     K: Decode + Eq + std::hash::Hash,
@@ -428,22 +610,115 @@ where
     S: std::hash::BuildHasher + Default,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<K, V, S> DecodeMaxLen for HashMap<K, V, S>
+where
+    K: Decode + Eq + std::hash::Hash,
+    V: Decode,
+    S: std::hash::BuildHasher + Default,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<(K, V)>(len)?;
 
         let hash_builder: S = Default::default();
-        let mut map = HashMap::with_capacity_and_hasher(len, hash_builder);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
-
-            let k = K::decode(decoder)?;
-            let v = V::decode(decoder)?;
-            map.insert(k, v);
-        }
+        let mut map = HashMap::with_hasher(hash_builder);
+        decode_hash_map_entries(
+            decoder,
+            &mut map,
+            len,
+            K::decode,
+            |recording| K::decode(recording),
+            V::decode,
+        )?;
+        decoder.unclaim_depth();
         Ok(map)
     }
 }
+
+impl<K, V, S> DecodeInPlace for HashMap<K, V, S>
+where
+    K: Decode + Eq + std::hash::Hash,
+    V: Decode,
+    S: std::hash::BuildHasher + Default,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<(K, V)>(len)?;
+
+        // Clearing the map keeps its allocated capacity
+        self.clear();
+        decode_hash_map_entries(
+            decoder,
+            self,
+            len,
+            K::decode,
+            |recording| K::decode(recording),
+            V::decode,
+        )?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
+
+/// Decodes `len` entries with `decode_key` and `decode_value`, and inserts them into `map`. The length has to be decoded and claimed already.
+///
+/// In strict mode the keys are decoded with `decode_recorded_key` instead, so their bytes can be checked to be sorted. This also applies the duplicate key policy of the config. It is shared by the `Decode` and `BorrowDecode` implementations.
+fn decode_hash_map_entries<K, V, S, D, FK, FR, FV>(
+    decoder: &mut D,
+    map: &mut HashMap<K, V, S>,
+    len: usize,
+    mut decode_key: FK,
+    mut decode_recorded_key: FR,
+    mut decode_value: FV,
+) -> Result<(), DecodeError>
+where
+    K: Eq + std::hash::Hash,
+    S: std::hash::BuildHasher,
+    D: Decoder,
+    FK: FnMut(&mut D) -> Result<K, DecodeError>,
+    FR: FnMut(&mut RecordingDecoder<'_, D>) -> Result<K, DecodeError>,
+    FV: FnMut(&mut D) -> Result<V, DecodeError>,
+{
+    map.try_reserve(crate::de::preallocation_len::<(K, V), _>(decoder, len))
+        .map_err(allocation_failed::<HashMap<K, V, S>>)?;
+    let mut previous_key = Vec::new();
+    for index in 0..len {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
+
+        let k = if decoder.config().is_strict() {
+            let mut recording = RecordingDecoder::new(decoder);
+            let k = decode_recorded_key(&mut recording)?;
+            recording.check_sorted(&mut previous_key, index)?;
+            k
+        } else {
+            decode_key(decoder)?
+        };
+        let v = decode_value(decoder)?;
+        map.try_reserve(1)
+            .map_err(allocation_failed::<HashMap<K, V, S>>)?;
+        match map.entry(k) {
+            Entry::Vacant(entry) => {
+                entry.insert(v);
+            }
+            Entry::Occupied(mut entry) => match decoder.config().duplicate_key_policy() {
+                DuplicateKeyPolicy::LastWins => {
+                    entry.insert(v);
+                }
+                DuplicateKeyPolicy::FirstWins => {}
+                DuplicateKeyPolicy::Error => return Err(DecodeError::DuplicateMapKey { index }),
+            },
+        }
+    }
+    Ok(())
+}
+
 impl<'de, K, V, S> BorrowDecode<'de> for HashMap<K, V, S>
 where
     K: BorrowDecode<'de> + Eq + std::hash::Hash,
@@ -451,19 +726,35 @@ where
     S: std::hash::BuildHasher + Default,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<'de, K, V, S> BorrowDecodeMaxLen<'de> for HashMap<K, V, S>
+where
+    K: BorrowDecode<'de> + Eq + std::hash::Hash,
+    V: BorrowDecode<'de>,
+    S: std::hash::BuildHasher + Default,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<(K, V)>(len)?;
 
         let hash_builder: S = Default::default();
-        let mut map = HashMap::with_capacity_and_hasher(len, hash_builder);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<(K, V)>());
-
-            let k = K::borrow_decode(decoder)?;
-            let v = V::borrow_decode(decoder)?;
-            map.insert(k, v);
-        }
+        let mut map = HashMap::with_hasher(hash_builder);
+        decode_hash_map_entries(
+            decoder,
+            &mut map,
+            len,
+            K::borrow_decode,
+            |recording| K::borrow_decode(recording),
+            V::borrow_decode,
+        )?;
+        decoder.unclaim_depth();
         Ok(map)
     }
 }
@@ -474,20 +765,99 @@ where
     S: std::hash::BuildHasher + Default,
 {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
+        Self::decode_max_len(decoder, usize::MAX)
+    }
+}
+
+impl<T, S> DecodeMaxLen for HashSet<T, S>
+where
+    T: Decode + Eq + Hash,
+    S: std::hash::BuildHasher + Default,
+{
+    fn decode_max_len<D: Decoder>(decoder: &mut D, max_len: usize) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
         decoder.claim_container_read::<T>(len)?;
 
         let hash_builder: S = Default::default();
-        let mut map: HashSet<T, S> = HashSet::with_capacity_and_hasher(len, hash_builder);
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+        let mut set: HashSet<T, S> = HashSet::with_hasher(hash_builder);
+        decode_hash_set_items(decoder, &mut set, len, T::decode, |recording| {
+            T::decode(recording)
+        })?;
+        decoder.unclaim_depth();
+        Ok(set)
+    }
+}
+
+impl<T, S> DecodeInPlace for HashSet<T, S>
+where
+    T: Decode + Eq + Hash,
+    S: std::hash::BuildHasher + Default,
+{
+    fn decode_in_place<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, usize::MAX)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            let key = T::decode(decoder)?;
-            map.insert(key);
+        // Clearing the set keeps its allocated capacity
+        self.clear();
+        decode_hash_set_items(decoder, self, len, T::decode, |recording| {
+            T::decode(recording)
+        })?;
+        decoder.unclaim_depth();
+        Ok(())
+    }
+}
+
+/// Decodes `len` items with `decode` and inserts them into `set`. The length has to be decoded and claimed already.
+///
+/// In strict mode the items are decoded with `decode_recorded` instead, so their bytes can be checked to be sorted. This also applies the duplicate key policy of the config. It is shared by the `Decode` and `BorrowDecode` implementations.
+fn decode_hash_set_items<T, S, D, F, FR>(
+    decoder: &mut D,
+    set: &mut HashSet<T, S>,
+    len: usize,
+    mut decode: F,
+    mut decode_recorded: FR,
+) -> Result<(), DecodeError>
+where
+    T: Eq + Hash,
+    S: std::hash::BuildHasher,
+    D: Decoder,
+    F: FnMut(&mut D) -> Result<T, DecodeError>,
+    FR: FnMut(&mut RecordingDecoder<'_, D>) -> Result<T, DecodeError>,
+{
+    set.try_reserve(crate::de::preallocation_len::<T, _>(decoder, len))
+        .map_err(allocation_failed::<HashSet<T, S>>)?;
+    let mut previous_key = Vec::new();
+    for index in 0..len {
+        // See the documentation on `unclaim_bytes_read` as to why we're doing this here
+        decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+
+        let key = if decoder.config().is_strict() {
+            let mut recording = RecordingDecoder::new(decoder);
+            let key = decode_recorded(&mut recording)?;
+            recording.check_sorted(&mut previous_key, index)?;
+            key
+        } else {
+            decode(decoder)?
+        };
+        set.try_reserve(1)
+            .map_err(allocation_failed::<HashSet<T, S>>)?;
+        match decoder.config().duplicate_key_policy() {
+            DuplicateKeyPolicy::LastWins => {
+                set.replace(key);
+            }
+            DuplicateKeyPolicy::FirstWins => {
+                set.insert(key);
+            }
+            DuplicateKeyPolicy::Error => {
+                if !set.insert(key) {
+                    return Err(DecodeError::DuplicateMapKey { index });
+                }
+            }
         }
-        Ok(map)
     }
+    Ok(())
 }
 
 impl<'de, T, S> BorrowDecode<'de> for HashSet<T, S>
@@ -496,18 +866,29 @@ where
     S: std::hash::BuildHasher + Default,
 {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
-        let len = crate::de::decode_slice_len(decoder)?;
-        decoder.claim_container_read::<T>(len)?;
+        Self::borrow_decode_max_len(decoder, usize::MAX)
+    }
+}
 
-        let mut map = HashSet::with_capacity_and_hasher(len, S::default());
-        for _ in 0..len {
-            // See the documentation on `unclaim_bytes_read` as to why we're doing this here
-            decoder.unclaim_bytes_read(core::mem::size_of::<T>());
+impl<'de, T, S> BorrowDecodeMaxLen<'de> for HashSet<T, S>
+where
+    T: BorrowDecode<'de> + Eq + Hash,
+    S: std::hash::BuildHasher + Default,
+{
+    fn borrow_decode_max_len<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+        max_len: usize,
+    ) -> Result<Self, DecodeError> {
+        decoder.claim_depth()?;
+        let len = crate::de::decode_collection_len(decoder, max_len)?;
+        decoder.claim_container_read::<T>(len)?;
 
-            let key = T::borrow_decode(decoder)?;
-            map.insert(key);
-        }
-        Ok(map)
+        let mut set = HashSet::with_hasher(S::default());
+        decode_hash_set_items(decoder, &mut set, len, T::borrow_decode, |recording| {
+            T::borrow_decode(recording)
+        })?;
+        decoder.unclaim_depth();
+        Ok(set)
     }
 }
 
@@ -517,9 +898,140 @@ where
 {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         crate::enc::encode_slice_len(encoder, self.len())?;
+        if encoder.config().is_canonical() {
+            let (items, entries) =
+                encode_sorted_keys(*encoder.config(), self.iter().map(|item| (item, ())))?;
+            for (item, ()) in entries {
+                crate::enc::ClaimedWriter(encoder).write(&items[item])?;
+            }
+            return Ok(());
+        }
         for item in self.iter() {
             item.encode(encoder)?;
         }
         Ok(())
     }
 }
+
+impl<T, S> EncodeMaxLen for HashSet<T, S>
+where
+    T: Encode,
+{
+    fn encode_max_len<E: Encoder>(
+        &self,
+        encoder: &mut E,
+        max_len: usize,
+    ) -> Result<(), EncodeError> {
+        crate::enc::check_collection_len(self.len(), max_len)?;
+        Encode::encode(self, encoder)
+    }
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for CString {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for CStr {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for PathBuf {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for Path {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<K, V, S, C: StaticConfig> MaxEncodedSize<C> for HashMap<K, V, S> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<K, V, S, C> MaxEncodedEntrySize<C> for HashMap<K, V, S>
+where
+    K: MaxEncodedSize<C>,
+    V: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = max_size_add(K::MAX_SIZE, V::MAX_SIZE);
+}
+
+impl<T, S, C: StaticConfig> MaxEncodedSize<C> for HashSet<T, S> {
+    const MAX_SIZE: Option<usize> = None;
+}
+
+impl<T, S, C> MaxEncodedEntrySize<C> for HashSet<T, S>
+where
+    T: MaxEncodedSize<C>,
+    C: StaticConfig,
+{
+    const MAX_ENTRY_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C> MaxEncodedSize<C> for Mutex<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<T, C> MaxEncodedSize<C> for RwLock<T>
+where
+    T: MaxEncodedSize<C> + ?Sized,
+    C: StaticConfig,
+{
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for SystemTime {
+    const MAX_SIZE: Option<usize> = <Duration as MaxEncodedSize<C>>::MAX_SIZE;
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for Ipv4Addr {
+    const MAX_SIZE: Option<usize> = Some(4);
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for Ipv6Addr {
+    const MAX_SIZE: Option<usize> = Some(16);
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for IpAddr {
+    const MAX_SIZE: Option<usize> = max_size_max(
+        max_size_add(
+            Some(variant_index_max_size::<C>(0)),
+            <Ipv4Addr as MaxEncodedSize<C>>::MAX_SIZE,
+        ),
+        max_size_add(
+            Some(variant_index_max_size::<C>(1)),
+            <Ipv6Addr as MaxEncodedSize<C>>::MAX_SIZE,
+        ),
+    );
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for SocketAddrV4 {
+    const MAX_SIZE: Option<usize> = max_size_add(
+        <Ipv4Addr as MaxEncodedSize<C>>::MAX_SIZE,
+        <u16 as MaxEncodedSize<C>>::MAX_SIZE,
+    );
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for SocketAddrV6 {
+    const MAX_SIZE: Option<usize> = max_size_add(
+        <Ipv6Addr as MaxEncodedSize<C>>::MAX_SIZE,
+        <u16 as MaxEncodedSize<C>>::MAX_SIZE,
+    );
+}
+
+impl<C: StaticConfig> MaxEncodedSize<C> for SocketAddr {
+    const MAX_SIZE: Option<usize> = max_size_max(
+        max_size_add(
+            Some(variant_index_max_size::<C>(0)),
+            <SocketAddrV4 as MaxEncodedSize<C>>::MAX_SIZE,
+        ),
+        max_size_add(
+            Some(variant_index_max_size::<C>(1)),
+            <SocketAddrV6 as MaxEncodedSize<C>>::MAX_SIZE,
+        ),
+    );
+}
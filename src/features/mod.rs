@@ -3,6 +3,11 @@ mod impl_alloc;
 #[cfg(feature = "alloc")]
 pub use self::impl_alloc::*;
 
+#[cfg(feature = "alloc")]
+mod bounded;
+#[cfg(feature = "alloc")]
+pub use self::bounded::*;
+
 #[cfg(feature = "std")]
 mod impl_std;
 #[cfg(feature = "std")]
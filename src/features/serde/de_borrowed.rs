@@ -1,7 +1,7 @@
 use super::DecodeError as SerdeDecodeError;
 use crate::{
     config::Config,
-    de::{BorrowDecode, BorrowDecoder, Decode},
+    de::{BorrowDecode, BorrowDecoder, Decode, Decoder},
     error::DecodeError,
 };
 use core::marker::PhantomData;
@@ -13,7 +13,7 @@ where
     T: Deserialize<'de>,
     C: Config,
 {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let reader = crate::de::read::SliceReader::new(slice);
@@ -25,6 +25,32 @@ where
     T::deserialize(serde_decoder)
 }
 
+/// Decode a borrowed type from the given slice, requiring that the entire slice is consumed. Some parts of the decoded type are expected to be referring to the given slice
+///
+/// Returns `DecodeError::TrailingBytes` if any bytes are left in the slice after `T` is decoded.
+pub fn decode_borrowed_from_slice_exact<'de, T, C>(
+    slice: &'de [u8],
+    config: C,
+) -> Result<T, DecodeError>
+where
+    T: Deserialize<'de>,
+    C: Config,
+{
+    if config.skips_fixed_array_length() {
+        return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
+    }
+    let reader = crate::de::read::SliceReader::new(slice);
+    let mut decoder = crate::de::DecoderImpl::new(reader, config);
+    let serde_decoder = SerdeDecoder {
+        de: &mut decoder,
+        pd: PhantomData,
+    };
+    let result = T::deserialize(serde_decoder)?;
+    let bytes_read = slice.len() - decoder.reader().slice.len();
+    crate::check_trailing_bytes(slice, bytes_read)?;
+    Ok(result)
+}
+
 /// Decode a borrowed type from the given slice using a seed. Some parts of the decoded type are expected to be referring to the given slice
 pub fn decode_seed_from_slice<'de, T, C>(
     seed: T,
@@ -35,7 +61,7 @@ where
     T: DeserializeSeed<'de>,
     C: Config,
 {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let reader = crate::de::read::SliceReader::new(slice);
@@ -209,7 +235,10 @@ impl<'a, 'de, DE: BorrowDecoder<'de>> Deserializer<'de> for SerdeDecoder<'a, 'de
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_byte_buf(Decode::decode(&mut self.de)?)
+        visitor.visit_byte_buf(crate::features::impl_alloc::decode_string_bytes(
+            &mut self.de,
+            usize::MAX,
+        )?)
     }
 
     fn deserialize_option<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
@@ -257,7 +286,7 @@ impl<'a, 'de, DE: BorrowDecoder<'de>> Deserializer<'de> for SerdeDecoder<'a, 'de
     where
         V: serde::de::Visitor<'de>,
     {
-        let len = usize::decode(&mut self.de)?;
+        let len = crate::de::decode_collection_len(&mut self.de, usize::MAX)?;
         self.deserialize_tuple(len, visitor)
     }
 
@@ -297,10 +326,13 @@ impl<'a, 'de, DE: BorrowDecoder<'de>> Deserializer<'de> for SerdeDecoder<'a, 'de
             }
         }
 
-        visitor.visit_seq(Access {
+        self.de.claim_depth()?;
+        let value = visitor.visit_seq(Access {
             deserializer: &mut self,
             len,
-        })
+        })?;
+        self.de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_tuple_struct<V>(
@@ -365,12 +397,15 @@ impl<'a, 'de, DE: BorrowDecoder<'de>> Deserializer<'de> for SerdeDecoder<'a, 'de
             }
         }
 
-        let len = usize::decode(&mut self.de)?;
+        let len = crate::de::decode_collection_len(&mut self.de, usize::MAX)?;
 
-        visitor.visit_map(Access {
+        self.de.claim_depth()?;
+        let value = visitor.visit_map(Access {
             deserializer: &mut self,
             len,
-        })
+        })?;
+        self.de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_struct<V>(
@@ -394,7 +429,14 @@ impl<'a, 'de, DE: BorrowDecoder<'de>> Deserializer<'de> for SerdeDecoder<'a, 'de
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        let de = self.de;
+        de.claim_depth()?;
+        let value = visitor.visit_enum(SerdeDecoder {
+            de: &mut *de,
+            pd: PhantomData,
+        })?;
+        de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -424,7 +466,7 @@ impl<'de, 'a, DE: BorrowDecoder<'de>> EnumAccess<'de> for SerdeDecoder<'a, 'de,
     where
         V: DeserializeSeed<'de>,
     {
-        let idx = u32::decode(&mut self.de)?;
+        let idx = crate::de::decode_variant_index(&mut self.de)?;
         let val = seed.deserialize(idx.into_deserializer())?;
         Ok((val, self))
     }
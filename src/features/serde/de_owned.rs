@@ -16,7 +16,7 @@ where
     T: DeserializeOwned,
     C: Config,
 {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let reader = crate::de::read::SliceReader::new(slice);
@@ -27,6 +27,19 @@ where
     Ok((result, bytes_read))
 }
 
+/// Decode an owned type from the given slice, requiring that the entire slice is consumed.
+///
+/// Returns `DecodeError::TrailingBytes` if any bytes are left in the slice after `T` is decoded.
+pub fn decode_from_slice_exact<T, C>(slice: &[u8], config: C) -> Result<T, DecodeError>
+where
+    T: DeserializeOwned,
+    C: Config,
+{
+    let (result, bytes_read) = decode_from_slice(slice, config)?;
+    crate::check_trailing_bytes(slice, bytes_read)?;
+    Ok(result)
+}
+
 /// Decode an owned type from the given `std::io::Read`.
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
@@ -34,7 +47,7 @@ pub fn decode_from_std_read<D: DeserializeOwned, C: Config, R: std::io::Read>(
     src: &mut R,
     config: C,
 ) -> Result<D, DecodeError> {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let reader = crate::IoReader::new(src);
@@ -52,7 +65,7 @@ pub fn decode_from_reader<D: DeserializeOwned, R: Reader, C: Config>(
     reader: R,
     config: C,
 ) -> Result<D, DecodeError> {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeDecodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let mut decoder = crate::de::DecoderImpl::<_, C>::new(reader, config);
@@ -181,7 +194,23 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_string(Decode::decode(&mut self.de)?)
+        let len = crate::de::decode_string_len(&mut self.de, usize::MAX)?;
+        self.de.claim_container_read::<u8>(len)?;
+        // If the reader has the whole string in its buffer, the visitor can look at it without it being copied into a `String` first
+        if let Some(bytes) = self.de.reader().peek_read(len) {
+            let str =
+                core::str::from_utf8(&bytes[..len]).map_err(|inner| DecodeError::Utf8 { inner })?;
+            let value = visitor.visit_str(str)?;
+            self.de.reader().consume(len);
+            return Ok(value);
+        }
+        // Otherwise the string has to be read into memory before the visitor can check it
+        let mut bytes = alloc::vec::Vec::new();
+        u8::decode_vec(&mut self.de, &mut bytes, len)?;
+        let string = alloc::string::String::from_utf8(bytes).map_err(|e| DecodeError::Utf8 {
+            inner: e.utf8_error(),
+        })?;
+        visitor.visit_string(string)
     }
 
     #[cfg(not(feature = "alloc"))]
@@ -213,7 +242,10 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_byte_buf(Decode::decode(&mut self.de)?)
+        visitor.visit_byte_buf(crate::features::impl_alloc::decode_string_bytes(
+            &mut self.de,
+            usize::MAX,
+        )?)
     }
 
     #[cfg(not(feature = "alloc"))]
@@ -229,7 +261,10 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_byte_buf(Decode::decode(&mut self.de)?)
+        visitor.visit_byte_buf(crate::features::impl_alloc::decode_string_bytes(
+            &mut self.de,
+            usize::MAX,
+        )?)
     }
     #[cfg(not(feature = "alloc"))]
     fn deserialize_byte_buf<V>(self, _: V) -> Result<V::Value, Self::Error>
@@ -284,7 +319,7 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
     where
         V: serde::de::Visitor<'de>,
     {
-        let len = usize::decode(&mut self.de)?;
+        let len = crate::de::decode_collection_len(&mut self.de, usize::MAX)?;
         self.deserialize_tuple(len, visitor)
     }
 
@@ -323,10 +358,13 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
             }
         }
 
-        visitor.visit_seq(Access {
+        self.de.claim_depth()?;
+        let value = visitor.visit_seq(Access {
             deserializer: &mut self,
             len,
-        })
+        })?;
+        self.de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_tuple_struct<V>(
@@ -389,12 +427,15 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
             }
         }
 
-        let len = usize::decode(&mut self.de)?;
+        let len = crate::de::decode_collection_len(&mut self.de, usize::MAX)?;
 
-        visitor.visit_map(Access {
+        self.de.claim_depth()?;
+        let value = visitor.visit_map(Access {
             deserializer: &mut self,
             len,
-        })
+        })?;
+        self.de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_struct<V>(
@@ -418,7 +459,11 @@ impl<'a, 'de, DE: Decoder> Deserializer<'de> for SerdeDecoder<'a, DE> {
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        let de = self.de;
+        de.claim_depth()?;
+        let value = visitor.visit_enum(SerdeDecoder { de: &mut *de })?;
+        de.unclaim_depth();
+        Ok(value)
     }
 
     fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -448,7 +493,7 @@ impl<'de, 'a, DE: Decoder> EnumAccess<'de> for SerdeDecoder<'a, DE> {
     where
         V: DeserializeSeed<'de>,
     {
-        let idx = u32::decode(&mut self.de)?;
+        let idx = crate::de::decode_variant_index(&mut self.de)?;
         let val = seed.deserialize(idx.into_deserializer())?;
         Ok((val, self))
     }
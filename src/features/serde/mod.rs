@@ -2,9 +2,13 @@
 //!
 //! To encode/decode type that implement serde's trait, you can use:
 //! - [decode_borrowed_from_slice]
+//! - [decode_borrowed_from_slice_exact]
 //! - [decode_from_slice]
+//! - [decode_from_slice_exact]
 //! - [encode_to_slice]
 //! - [encode_to_vec]
+//! - [encode_append_to_vec]
+//! - [encoded_size]
 //!
 //! For interop with bincode's [Decode]/[Encode], you can use:
 //! - [Compat]
@@ -196,6 +200,8 @@ where
         T::deserialize(serde_decoder).map(Compat)
     }
 }
+
+impl<T> crate::de::DecodeInPlace for Compat<T> where T: serde::de::DeserializeOwned {}
 impl<'de, T> crate::BorrowDecode<'de> for Compat<T>
 where
     T: serde::de::DeserializeOwned,
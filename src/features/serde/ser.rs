@@ -16,13 +16,59 @@ where
     T: Serialize,
     C: Config,
 {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
     }
-    let mut encoder = crate::enc::EncoderImpl::new(crate::VecWriter::default(), config);
+    // Calculate the size up front, so the vec only has to be allocated once
+    let size = encoded_size(&t, config)?;
+    let mut encoder = crate::enc::EncoderImpl::new(crate::VecWriter::with_capacity(size), config);
     let serializer = SerdeEncoder { enc: &mut encoder };
     t.serialize(serializer)?;
-    Ok(encoder.into_writer().collect())
+    Ok(encoder.into_writer().into_inner())
+}
+
+#[cfg(feature = "alloc")]
+/// Encode a `serde` `Serialize` type to the end of `dst` with the bincode algorithm. Returns the amount of bytes that have been appended.
+///
+/// The capacity of `dst` is reused. If encoding fails, `dst` is restored to its original length.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_append_to_vec<T, C>(t: T, dst: &mut Vec<u8>, config: C) -> Result<usize, EncodeError>
+where
+    T: Serialize,
+    C: Config,
+{
+    if config.skips_fixed_array_length() {
+        return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
+    }
+    let start = dst.len();
+    let mut encoder =
+        crate::enc::EncoderImpl::new(crate::VecWriter::from(core::mem::take(dst)), config);
+    let serializer = SerdeEncoder { enc: &mut encoder };
+    let result = t.serialize(serializer);
+    *dst = encoder.into_writer().into_inner();
+    match result {
+        Ok(()) => Ok(dst.len() - start),
+        Err(e) => {
+            dst.truncate(start);
+            Err(e)
+        }
+    }
+}
+
+/// Calculate the amount of bytes that a `serde` `Serialize` type encodes to with the bincode algorithm, without allocating.
+pub fn encoded_size<T, C>(t: T, config: C) -> Result<usize, EncodeError>
+where
+    T: Serialize,
+    C: Config,
+{
+    if config.skips_fixed_array_length() {
+        return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
+    }
+    let mut encoder =
+        crate::enc::EncoderImpl::new(crate::enc::write::SizeWriter::default(), config);
+    let serializer = SerdeEncoder { enc: &mut encoder };
+    t.serialize(serializer)?;
+    Ok(encoder.into_writer().bytes_written())
 }
 
 /// Encode a `serde` `Serialize` type into a given byte slice with the bincode algorithm
@@ -31,7 +77,7 @@ where
     T: Serialize,
     C: Config,
 {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let mut encoder =
@@ -51,7 +97,7 @@ pub fn encode_into_writer<E: Serialize, W: Writer, C: Config>(
     writer: W,
     config: C,
 ) -> Result<(), EncodeError> {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
     }
     let mut encoder = crate::enc::EncoderImpl::<_, C>::new(writer, config);
@@ -71,9 +117,16 @@ pub fn encode_into_std_write<E: Serialize, C: Config, W: std::io::Write>(
     dst: &mut W,
     config: C,
 ) -> Result<usize, EncodeError> {
-    if C::SKIP_FIXED_ARRAY_LENGTH {
+    if config.skips_fixed_array_length() {
         return Err(SerdeEncodeError::SkipFixedArrayLengthNotSupported.into());
     }
+    if config.limit().is_some() {
+        // Make sure the limit is not exceeded before anything is written to `dst`
+        let mut encoder =
+            crate::enc::EncoderImpl::<_, C>::new(crate::enc::write::SizeWriter::default(), config);
+        let serializer = SerdeEncoder { enc: &mut encoder };
+        val.serialize(serializer)?;
+    }
     let writer = crate::IoWriter::new(dst);
     let mut encoder = crate::enc::EncoderImpl::<_, C>::new(writer, config);
     let serializer = SerdeEncoder { enc: &mut encoder };
@@ -195,7 +248,7 @@ where
         variant_index: u32,
         _variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        variant_index.encode(self.enc)
+        crate::enc::encode_variant_index(self.enc, variant_index)
     }
 
     fn serialize_newtype_struct<T: ?Sized>(
@@ -210,7 +263,7 @@ where
     }
 
     fn serialize_newtype_variant<T: ?Sized>(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
@@ -219,13 +272,13 @@ where
     where
         T: Serialize,
     {
-        variant_index.encode(&mut self.enc)?;
+        crate::enc::encode_variant_index(self.enc, variant_index)?;
         value.serialize(self)
     }
 
     fn serialize_seq(mut self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         let len = len.ok_or_else(|| SerdeEncodeError::SequenceMustHaveLength.into())?;
-        len.encode(&mut self.enc)?;
+        crate::enc::encode_slice_len(&mut self.enc, len)?;
         Ok(Compound { enc: self.enc })
     }
 
@@ -242,19 +295,19 @@ where
     }
 
     fn serialize_tuple_variant(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        variant_index.encode(&mut self.enc)?;
+        crate::enc::encode_variant_index(self.enc, variant_index)?;
         Ok(Compound { enc: self.enc })
     }
 
     fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         let len = len.ok_or_else(|| SerdeEncodeError::SequenceMustHaveLength.into())?;
-        len.encode(&mut self.enc)?;
+        crate::enc::encode_slice_len(&mut self.enc, len)?;
         Ok(Compound { enc: self.enc })
     }
 
@@ -267,13 +320,13 @@ where
     }
 
     fn serialize_struct_variant(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        variant_index.encode(&mut self.enc)?;
+        crate::enc::encode_variant_index(self.enc, variant_index)?;
         Ok(Compound { enc: self.enc })
     }
 
@@ -18,9 +18,9 @@
 //! |Name  |Default?|Supported types for Encode/Decode|Enabled methods                                                  |Other|
 //! |------|--------|-----------------------------------------|-----------------------------------------------------------------|-----|
 //! |std   | Yes    |`HashMap` and `HashSet`|`decode_from_std_read` and `encode_into_std_write`|
-//! |alloc | Yes    |All common containers in alloc, like `Vec`, `String`, `Box`|`encode_to_vec`|
+//! |alloc | Yes    |All common containers in alloc, like `Vec`, `String`, `Box`, and the length-limited `BoundedVec` and `BoundedString`|`encode_to_vec` and `encode_append_to_vec`|
 //! |atomic| Yes    |All `Atomic*` integer types, e.g. `AtomicUsize`, and `AtomicBool`||
-//! |derive| Yes    |||Enables the `BorrowDecode`, `Decode` and `Encode` derive macros|
+//! |derive| Yes    |||Enables the `BorrowDecode`, `Decode`, `DecodeInPlace`, `Encode` and `MaxEncodedSize` derive macros|
 //! |serde | No     |`Compat` and `BorrowCompat`, which will work for all types that implement serde's traits|serde-specific encode/decode functions in the [serde] module|Note: There are several [known issues](serde/index.html#known-issues) when using serde and bincode|
 //!
 //! # Which functions to use
@@ -32,7 +32,8 @@
 //! |You're working with [`fs::File`] or [`net::TcpStream`]|[`encode_into_std_write`]|[`decode_from_std_read`]|
 //! |you're working with in-memory buffers|[`encode_to_vec`]|[`decode_from_slice`]|
 //! |You want to use a custom [Reader](de::read::Reader) and [writer](enc::write::Writer)|[`encode_into_writer`]|[`decode_from_reader`]|
-//! |You're working with pre-allocated buffers or on embedded targets|[`encode_into_slice`]|[`decode_from_slice`]|
+//! |You're working with pre-allocated buffers or on embedded targets|[`encode_into_slice`] or [`encode_to_array`]|[`decode_from_slice`]|
+//! |You're decoding the same type many times and want to reuse its allocations|[`encode_append_to_vec`]|[`decode_from_slice_into`]|
 //!
 //! **Note:** If you're using `serde`, use `bincode::serde::...` instead of `bincode::...`
 //!
@@ -80,6 +81,7 @@ extern crate alloc;
 #[cfg(any(feature = "std", test))]
 extern crate std;
 
+mod aligned;
 mod atomic;
 mod features;
 pub(crate) mod utils;
@@ -95,6 +97,7 @@ pub mod de;
 pub mod enc;
 pub mod error;
 
+pub use aligned::*;
 pub use atomic::*;
 pub use de::{BorrowDecode, Decode};
 pub use enc::Encode;
@@ -117,6 +120,68 @@ pub fn encode_into_slice<E: enc::Encode, C: Config>(
     Ok(encoder.into_writer().bytes_written())
 }
 
+/// Encode the given value into a new array of `N` bytes. Unlike [encode_into_slice], the returned [EncodedArray] owns its bytes, which makes this useful on targets without an allocator.
+///
+/// Returns [EncodeError::UnexpectedEnd] if the value does not fit in `N` bytes. For types that implement [MaxEncodedSize], [max_encoded_size] can be used to pick an `N` that always fits.
+///
+/// ```
+/// use bincode::config::Configuration;
+/// use bincode::enc::max_encoded_size;
+///
+/// let encoded = bincode::encode_to_array::<8, _, _>([1u16, 2, 3], bincode::config::standard()).unwrap();
+/// assert_eq!(encoded.as_slice(), &[3, 1, 2, 3]);
+///
+/// const SIZE: usize = max_encoded_size::<(u8, u64), Configuration>();
+/// let encoded = bincode::encode_to_array::<SIZE, _, _>((5u8, u64::MAX), bincode::config::standard()).unwrap();
+/// assert_eq!(encoded.len(), SIZE);
+/// ```
+///
+/// See the [config] module for more information on configurations.
+///
+/// [config]: config/index.html
+/// [EncodedArray]: enc/struct.EncodedArray.html
+/// [EncodeError::UnexpectedEnd]: error/enum.EncodeError.html#variant.UnexpectedEnd
+/// [MaxEncodedSize]: enc/trait.MaxEncodedSize.html
+/// [max_encoded_size]: enc/fn.max_encoded_size.html
+pub fn encode_to_array<const N: usize, E: enc::Encode, C: Config>(
+    val: E,
+    config: C,
+) -> Result<enc::EncodedArray<N>, error::EncodeError> {
+    let mut buffer = [0u8; N];
+    let len = encode_into_slice(val, &mut buffer, config)?;
+    Ok(enc::EncodedArray::new(buffer, len))
+}
+
+/// Calculate the amount of bytes that the given value encodes to, without allocating. This can be used to size a buffer for [encode_into_slice].
+///
+/// The only values that allocate are `HashMap`s and `HashSet`s with a [canonical] config, because their entries have to be sorted.
+///
+/// Returns an error if the value can not be encoded with the given config, e.g. because it exceeds the [limit] of the config.
+///
+/// ```
+/// let value = (5u8, String::from("Hello"));
+/// let size = bincode::encoded_size(&value, bincode::config::standard()).unwrap();
+/// assert_eq!(size, 7);
+///
+/// let mut buffer = vec![0u8; size];
+/// let written = bincode::encode_into_slice(&value, &mut buffer, bincode::config::standard()).unwrap();
+/// assert_eq!(written, size);
+/// ```
+///
+/// See the [config] module for more information on configurations.
+///
+/// [config]: config/index.html
+/// [limit]: config/struct.Configuration.html#method.with_limit
+/// [canonical]: config/struct.Configuration.html#method.with_canonical_encoding
+pub fn encoded_size<E: enc::Encode, C: Config>(
+    val: E,
+    config: C,
+) -> Result<usize, error::EncodeError> {
+    let mut encoder = enc::EncoderImpl::<_, C>::new(enc::write::SizeWriter::default(), config);
+    val.encode(&mut encoder)?;
+    Ok(encoder.into_writer().bytes_written())
+}
+
 /// Encode the given value into a custom [Writer].
 ///
 /// See the [config] module for more information on configurations.
@@ -132,6 +197,26 @@ pub fn encode_into_writer<E: enc::Encode, W: Writer, C: Config>(
     Ok(())
 }
 
+/// Attempt to decode a given type `D` from the given slice into `dst`, reusing the allocations of the `Vec`s, `String`s and other collections it already holds. Returns the amount of bytes read.
+///
+/// If decoding fails, `dst` is left in a valid but unspecified state. See [DecodeInPlace] for more information.
+///
+/// See the [config] module for more information on configurations.
+///
+/// [config]: config/index.html
+/// [DecodeInPlace]: de/trait.DecodeInPlace.html
+pub fn decode_from_slice_into<D: de::DecodeInPlace, C: Config>(
+    dst: &mut D,
+    src: &[u8],
+    config: C,
+) -> Result<usize, error::DecodeError> {
+    let reader = de::read::SliceReader::new(src);
+    let mut decoder = de::DecoderImpl::<_, C>::new(reader, config);
+    dst.decode_in_place(&mut decoder)?;
+    let bytes_read = src.len() - decoder.reader().slice.len();
+    Ok(bytes_read)
+}
+
 /// Attempt to decode a given type `D` from the given slice. Returns the decoded output and the amount of bytes read.
 ///
 /// See the [config] module for more information on configurations.
@@ -164,6 +249,49 @@ pub fn borrow_decode_from_slice<'a, D: de::BorrowDecode<'a>, C: Config>(
     Ok((result, bytes_read))
 }
 
+/// Attempt to decode a given type `D` from the given slice, requiring that the entire slice is consumed.
+///
+/// Returns `DecodeError::TrailingBytes` if any bytes are left in the slice after `D` is decoded.
+///
+/// See the [config] module for more information on configurations.
+///
+/// [config]: config/index.html
+pub fn decode_from_slice_exact<D: de::Decode, C: Config>(
+    src: &[u8],
+    config: C,
+) -> Result<D, error::DecodeError> {
+    let (result, bytes_read) = decode_from_slice(src, config)?;
+    check_trailing_bytes(src, bytes_read)?;
+    Ok(result)
+}
+
+/// Attempt to decode a given type `D` from the given slice, requiring that the entire slice is consumed.
+///
+/// Returns `DecodeError::TrailingBytes` if any bytes are left in the slice after `D` is decoded.
+///
+/// See the [config] module for more information on configurations.
+///
+/// [config]: config/index.html
+pub fn borrow_decode_from_slice_exact<'a, D: de::BorrowDecode<'a>, C: Config>(
+    src: &'a [u8],
+    config: C,
+) -> Result<D, error::DecodeError> {
+    let (result, bytes_read) = borrow_decode_from_slice(src, config)?;
+    check_trailing_bytes(src, bytes_read)?;
+    Ok(result)
+}
+
+/// Returns `DecodeError::TrailingBytes` if `bytes_read` is less than the length of `src`.
+pub(crate) fn check_trailing_bytes(
+    src: &[u8],
+    bytes_read: usize,
+) -> Result<(), error::DecodeError> {
+    match src.len() - bytes_read {
+        0 => Ok(()),
+        remaining => Err(error::DecodeError::TrailingBytes { remaining }),
+    }
+}
+
 /// Attempt to decode a given type `D` from the given [Reader].
 ///
 /// See the [config] module for more information on configurations.
@@ -1,3 +1,85 @@
 pub trait Sealed {}
 
 impl<'a, T> Sealed for &'a mut T where T: Sealed {}
+
+/// The endianness of the target.
+pub(crate) const NATIVE_ENDIAN: crate::config::Endian = if cfg!(target_endian = "little") {
+    crate::config::Endian::Little
+} else {
+    crate::config::Endian::Big
+};
+
+/// Integer and float types, which have no padding and are valid for every bit pattern. A slice of these can be copied from and to bytes directly.
+///
+/// # Safety
+///
+/// This may only be implemented for types without padding bytes, for which every bit pattern is a valid value.
+pub(crate) unsafe trait Primitive: Copy + Default {
+    /// `true` for `f32` and `f64`, which are encoded the same way with every int encoding.
+    const IS_FLOAT: bool = false;
+
+    /// Returns `true` if this is a `NaN` that strict decoding rejects.
+    fn is_non_canonical_nan(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty),*) => {
+        $(
+            // Safety: integers have no padding and are valid for every bit pattern
+            unsafe impl Primitive for $ty {}
+        )*
+    };
+}
+
+impl_primitive!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// Safety: floats have no padding and are valid for every bit pattern
+unsafe impl Primitive for f32 {
+    const IS_FLOAT: bool = true;
+
+    fn is_non_canonical_nan(&self) -> bool {
+        self.is_nan() && self.to_bits() != 0x7FC0_0000
+    }
+}
+
+// Safety: floats have no padding and are valid for every bit pattern
+unsafe impl Primitive for f64 {
+    const IS_FLOAT: bool = true;
+
+    fn is_non_canonical_nan(&self) -> bool {
+        self.is_nan() && self.to_bits() != 0x7FF8_0000_0000_0000
+    }
+}
+
+/// View a slice of primitives as its in-memory bytes.
+pub(crate) fn as_bytes<T: Primitive>(slice: &[T]) -> &[u8] {
+    // Safety: `T` has no padding, so every byte of the slice is initialized
+    unsafe {
+        core::slice::from_raw_parts(slice.as_ptr() as *const u8, core::mem::size_of_val(slice))
+    }
+}
+
+/// View a slice of primitives as its in-memory bytes, so it can be filled from bytes.
+pub(crate) fn as_bytes_mut<T: Primitive>(slice: &mut [T]) -> &mut [u8] {
+    // Safety: `T` has no padding and is valid for every bit pattern, so any bytes that are written make up valid values
+    unsafe {
+        core::slice::from_raw_parts_mut(
+            slice.as_mut_ptr() as *mut u8,
+            core::mem::size_of_val(slice),
+        )
+    }
+}
+
+/// View bytes as a slice of primitives, if they are aligned for `T` and their length is a multiple of the size of `T`.
+pub(crate) fn try_from_bytes<T: Primitive>(bytes: &[u8]) -> Option<&[T]> {
+    let len = bytes.len() / core::mem::size_of::<T>();
+    // Alignments are always a power of two
+    let misaligned = bytes.as_ptr() as usize & (core::mem::align_of::<T>() - 1) != 0;
+    if misaligned || len * core::mem::size_of::<T>() != bytes.len() {
+        return None;
+    }
+    // Safety: the bytes are aligned and every bit pattern is a valid `T`
+    Some(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const T, len) })
+}
@@ -0,0 +1,71 @@
+use super::SINGLE_BYTE_MAX;
+use crate::de::read::Reader;
+
+/// The amount of bytes that are checked at once. Checking a fixed-size chunk without an early exit lets the compiler vectorize the loops below.
+const CHUNK_SIZE: usize = 16;
+
+/// Decode the run of single-byte varints at the start of `read` into `out`, and return how many values were decoded.
+///
+/// This only looks at the bytes that `read.peek_read` makes available, and stops at the first marker byte or when `out` is full. Values that are not a single byte have to be decoded by the caller with the normal varint functions. A return value of `0` does not mean that the reader is empty.
+///
+/// Single-byte varints are always canonical, so this does not need to know about strict decoding. The endianness does not matter either.
+pub fn varint_decode_batch<R, T, F>(read: &mut R, out: &mut [T], from_byte: F) -> usize
+where
+    R: Reader,
+    F: Fn(u8) -> T,
+{
+    let mut len = out.len();
+    if let Some(remaining) = read.remaining_hint() {
+        len = len.min(remaining);
+    }
+    // Readers that do not implement `peek_read` at all are decoded one value at a time
+    if read.peek_read(1).is_none() {
+        return 0;
+    }
+    // `peek_read` fails if less than `len` bytes are available, e.g. when a `BufReader` has only part of its buffer filled
+    while read.peek_read(len).is_none() {
+        if len == 0 {
+            return 0;
+        }
+        len /= 2;
+    }
+    let bytes = match read.peek_read(len) {
+        Some(bytes) => &bytes[..len],
+        None => return 0,
+    };
+
+    let mut decoded = 0;
+    for (chunk, out) in bytes
+        .chunks_exact(CHUNK_SIZE)
+        .zip(out.chunks_exact_mut(CHUNK_SIZE))
+    {
+        if chunk
+            .iter()
+            .fold(false, |acc, &byte| acc | (byte > SINGLE_BYTE_MAX))
+        {
+            break;
+        }
+        for (out, &byte) in out.iter_mut().zip(chunk) {
+            *out = from_byte(byte);
+        }
+        decoded += CHUNK_SIZE;
+    }
+
+    // The last bytes, or the chunk with the first marker byte
+    for (out, &byte) in out[decoded..].iter_mut().zip(&bytes[decoded..]) {
+        if byte > SINGLE_BYTE_MAX {
+            break;
+        }
+        *out = from_byte(byte);
+        decoded += 1;
+    }
+
+    read.consume(decoded);
+    decoded
+}
+
+/// Convert a single-byte varint to a signed integer by undoing the zigzag encoding.
+pub fn zigzag_decode_byte(byte: u8) -> i8 {
+    // Single-byte varints are at most 250, so the result always fits in an `i8`
+    ((byte >> 1) as i8) ^ -((byte & 1) as i8)
+}
@@ -4,8 +4,12 @@ use crate::{
     error::{DecodeError, IntegerType},
 };
 
-pub fn varint_decode_i16<R: Reader>(read: &mut R, endian: Endian) -> Result<i16, DecodeError> {
-    let n = super::varint_decode_u16(read, endian)
+pub fn varint_decode_i16<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<i16, DecodeError> {
+    let n = super::varint_decode_u16(read, endian, strict)
         .map_err(DecodeError::change_integer_type_to_signed)?;
     Ok(if n % 2 == 0 {
         // positive number
@@ -21,8 +25,12 @@ pub fn varint_decode_i16<R: Reader>(read: &mut R, endian: Endian) -> Result<i16,
     })
 }
 
-pub fn varint_decode_i32<R: Reader>(read: &mut R, endian: Endian) -> Result<i32, DecodeError> {
-    let n = super::varint_decode_u32(read, endian)
+pub fn varint_decode_i32<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<i32, DecodeError> {
+    let n = super::varint_decode_u32(read, endian, strict)
         .map_err(DecodeError::change_integer_type_to_signed)?;
     Ok(if n % 2 == 0 {
         // positive number
@@ -38,8 +46,12 @@ pub fn varint_decode_i32<R: Reader>(read: &mut R, endian: Endian) -> Result<i32,
     })
 }
 
-pub fn varint_decode_i64<R: Reader>(read: &mut R, endian: Endian) -> Result<i64, DecodeError> {
-    let n = super::varint_decode_u64(read, endian)
+pub fn varint_decode_i64<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<i64, DecodeError> {
+    let n = super::varint_decode_u64(read, endian, strict)
         .map_err(DecodeError::change_integer_type_to_signed)?;
     Ok(if n % 2 == 0 {
         // positive number
@@ -55,8 +67,12 @@ pub fn varint_decode_i64<R: Reader>(read: &mut R, endian: Endian) -> Result<i64,
     })
 }
 
-pub fn varint_decode_i128<R: Reader>(read: &mut R, endian: Endian) -> Result<i128, DecodeError> {
-    let n = super::varint_decode_u128(read, endian)
+pub fn varint_decode_i128<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<i128, DecodeError> {
+    let n = super::varint_decode_u128(read, endian, strict)
         .map_err(DecodeError::change_integer_type_to_signed)?;
     Ok(if n % 2 == 0 {
         // positive number
@@ -72,8 +88,12 @@ pub fn varint_decode_i128<R: Reader>(read: &mut R, endian: Endian) -> Result<i12
     })
 }
 
-pub fn varint_decode_isize<R: Reader>(read: &mut R, endian: Endian) -> Result<isize, DecodeError> {
-    match varint_decode_i64(read, endian) {
+pub fn varint_decode_isize<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<isize, DecodeError> {
+    match varint_decode_i64(read, endian, strict) {
         Ok(val) => Ok(val as isize),
         Err(DecodeError::InvalidIntegerType { found, .. }) => {
             Err(DecodeError::InvalidIntegerType {
@@ -81,6 +101,9 @@ pub fn varint_decode_isize<R: Reader>(read: &mut R, endian: Endian) -> Result<is
                 found: found.into_signed(),
             })
         }
+        Err(DecodeError::NonCanonicalInteger { .. }) => Err(DecodeError::NonCanonicalInteger {
+            integer_type: IntegerType::Isize,
+        }),
         Err(e) => Err(e),
     }
 }
@@ -9,13 +9,18 @@ use crate::{
 
 #[inline(never)]
 #[cold]
-fn deserialize_varint_cold_u16<R>(read: &mut R, endian: Endian) -> Result<u16, DecodeError>
+fn deserialize_varint_cold_u16<R>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u16, DecodeError>
 where
     R: Reader,
 {
     let mut bytes = [0u8; 1];
     read.read(&mut bytes)?;
-    match bytes[0] {
+    let marker = bytes[0];
+    let out = match marker {
         byte @ 0..=SINGLE_BYTE_MAX => Ok(byte as u16),
         U16_BYTE => {
             let mut bytes = [0u8; 2];
@@ -29,18 +34,27 @@ where
         U64_BYTE => invalid_varint_discriminant(IntegerType::U16, IntegerType::U64),
         U128_BYTE => invalid_varint_discriminant(IntegerType::U16, IntegerType::U128),
         _ => invalid_varint_discriminant(IntegerType::U16, IntegerType::Reserved),
+    }?;
+    if strict {
+        check_canonical(out as u128, marker, IntegerType::U16)?;
     }
+    Ok(out)
 }
 
 #[inline(never)]
 #[cold]
-fn deserialize_varint_cold_u32<R>(read: &mut R, endian: Endian) -> Result<u32, DecodeError>
+fn deserialize_varint_cold_u32<R>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u32, DecodeError>
 where
     R: Reader,
 {
     let mut bytes = [0u8; 1];
     read.read(&mut bytes)?;
-    match bytes[0] {
+    let marker = bytes[0];
+    let out = match marker {
         byte @ 0..=SINGLE_BYTE_MAX => Ok(byte as u32),
         U16_BYTE => {
             let mut bytes = [0u8; 2];
@@ -61,18 +75,27 @@ where
         U64_BYTE => invalid_varint_discriminant(IntegerType::U32, IntegerType::U64),
         U128_BYTE => invalid_varint_discriminant(IntegerType::U32, IntegerType::U128),
         _ => invalid_varint_discriminant(IntegerType::U32, IntegerType::Reserved),
+    }?;
+    if strict {
+        check_canonical(out as u128, marker, IntegerType::U32)?;
     }
+    Ok(out)
 }
 
 #[inline(never)]
 #[cold]
-fn deserialize_varint_cold_u64<R>(read: &mut R, endian: Endian) -> Result<u64, DecodeError>
+fn deserialize_varint_cold_u64<R>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u64, DecodeError>
 where
     R: Reader,
 {
     let mut bytes = [0u8; 1];
     read.read(&mut bytes)?;
-    match bytes[0] {
+    let marker = bytes[0];
+    let out = match marker {
         byte @ 0..=SINGLE_BYTE_MAX => Ok(byte as u64),
         U16_BYTE => {
             let mut bytes = [0u8; 2];
@@ -100,18 +123,27 @@ where
         }
         U128_BYTE => invalid_varint_discriminant(IntegerType::U64, IntegerType::U128),
         _ => invalid_varint_discriminant(IntegerType::U64, IntegerType::Reserved),
+    }?;
+    if strict {
+        check_canonical(out as u128, marker, IntegerType::U64)?;
     }
+    Ok(out)
 }
 
 #[inline(never)]
 #[cold]
-fn deserialize_varint_cold_usize<R>(read: &mut R, endian: Endian) -> Result<usize, DecodeError>
+fn deserialize_varint_cold_usize<R>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<usize, DecodeError>
 where
     R: Reader,
 {
     let mut bytes = [0u8; 1];
     read.read(&mut bytes)?;
-    match bytes[0] {
+    let marker = bytes[0];
+    let out = match marker {
         byte @ 0..=SINGLE_BYTE_MAX => Ok(byte as usize),
         U16_BYTE => {
             let mut bytes = [0u8; 2];
@@ -139,18 +171,27 @@ where
         }
         U128_BYTE => invalid_varint_discriminant(IntegerType::Usize, IntegerType::U128),
         _ => invalid_varint_discriminant(IntegerType::Usize, IntegerType::Reserved),
+    }?;
+    if strict {
+        check_canonical(out as u128, marker, IntegerType::Usize)?;
     }
+    Ok(out)
 }
 
 #[inline(never)]
 #[cold]
-fn deserialize_varint_cold_u128<R>(read: &mut R, endian: Endian) -> Result<u128, DecodeError>
+fn deserialize_varint_cold_u128<R>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u128, DecodeError>
 where
     R: Reader,
 {
     let mut bytes = [0u8; 1];
     read.read(&mut bytes)?;
-    match bytes[0] {
+    let marker = bytes[0];
+    let out = match marker {
         byte @ 0..=SINGLE_BYTE_MAX => Ok(byte as u128),
         U16_BYTE => {
             let mut bytes = [0u8; 2];
@@ -185,7 +226,11 @@ where
             })
         }
         _ => invalid_varint_discriminant(IntegerType::U128, IntegerType::Reserved),
+    }?;
+    if strict {
+        check_canonical(out, marker, IntegerType::U128)?;
     }
+    Ok(out)
 }
 
 #[inline(never)]
@@ -197,7 +242,32 @@ fn invalid_varint_discriminant<T>(
     Err(DecodeError::InvalidIntegerType { expected, found })
 }
 
-pub fn varint_decode_u16<R: Reader>(read: &mut R, endian: Endian) -> Result<u16, DecodeError> {
+/// Returns `DecodeError::NonCanonicalInteger` if `value` would have been encoded with a different `marker`.
+#[inline]
+fn check_canonical(value: u128, marker: u8, integer_type: IntegerType) -> Result<(), DecodeError> {
+    let canonical = if value <= SINGLE_BYTE_MAX as u128 {
+        marker <= SINGLE_BYTE_MAX
+    } else if value <= u16::MAX as u128 {
+        marker == U16_BYTE
+    } else if value <= u32::MAX as u128 {
+        marker == U32_BYTE
+    } else if value <= u64::MAX as u128 {
+        marker == U64_BYTE
+    } else {
+        marker == U128_BYTE
+    };
+    if canonical {
+        Ok(())
+    } else {
+        Err(DecodeError::NonCanonicalInteger { integer_type })
+    }
+}
+
+pub fn varint_decode_u16<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u16, DecodeError> {
     if let Some(bytes) = read.peek_read(3) {
         let (discriminant, bytes) = bytes.split_at(1);
         let (out, used) = match discriminant[0] {
@@ -216,14 +286,21 @@ pub fn varint_decode_u16<R: Reader>(read: &mut R, endian: Endian) -> Result<u16,
             _ => return invalid_varint_discriminant(IntegerType::U16, IntegerType::Reserved),
         };
 
+        if strict {
+            check_canonical(out as u128, discriminant[0], IntegerType::U16)?;
+        }
         read.consume(used);
         Ok(out)
     } else {
-        deserialize_varint_cold_u16(read, endian)
+        deserialize_varint_cold_u16(read, endian, strict)
     }
 }
 
-pub fn varint_decode_u32<R: Reader>(read: &mut R, endian: Endian) -> Result<u32, DecodeError> {
+pub fn varint_decode_u32<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u32, DecodeError> {
     if let Some(bytes) = read.peek_read(5) {
         let (discriminant, bytes) = bytes.split_at(1);
         let (out, used) = match discriminant[0] {
@@ -249,14 +326,21 @@ pub fn varint_decode_u32<R: Reader>(read: &mut R, endian: Endian) -> Result<u32,
             _ => return invalid_varint_discriminant(IntegerType::U32, IntegerType::Reserved),
         };
 
+        if strict {
+            check_canonical(out as u128, discriminant[0], IntegerType::U32)?;
+        }
         read.consume(used);
         Ok(out)
     } else {
-        deserialize_varint_cold_u32(read, endian)
+        deserialize_varint_cold_u32(read, endian, strict)
     }
 }
 
-pub fn varint_decode_u64<R: Reader>(read: &mut R, endian: Endian) -> Result<u64, DecodeError> {
+pub fn varint_decode_u64<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u64, DecodeError> {
     if let Some(bytes) = read.peek_read(9) {
         let (discriminant, bytes) = bytes.split_at(1);
         let (out, used) = match discriminant[0] {
@@ -289,14 +373,21 @@ pub fn varint_decode_u64<R: Reader>(read: &mut R, endian: Endian) -> Result<u64,
             _ => return invalid_varint_discriminant(IntegerType::U32, IntegerType::Reserved),
         };
 
+        if strict {
+            check_canonical(out as u128, discriminant[0], IntegerType::U64)?;
+        }
         read.consume(used);
         Ok(out)
     } else {
-        deserialize_varint_cold_u64(read, endian)
+        deserialize_varint_cold_u64(read, endian, strict)
     }
 }
 
-pub fn varint_decode_usize<R: Reader>(read: &mut R, endian: Endian) -> Result<usize, DecodeError> {
+pub fn varint_decode_usize<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<usize, DecodeError> {
     if let Some(bytes) = read.peek_read(9) {
         let (discriminant, bytes) = bytes.split_at(1);
         let (out, used) = match discriminant[0] {
@@ -329,14 +420,21 @@ pub fn varint_decode_usize<R: Reader>(read: &mut R, endian: Endian) -> Result<us
             _ => return invalid_varint_discriminant(IntegerType::Usize, IntegerType::Reserved),
         };
 
+        if strict {
+            check_canonical(out as u128, discriminant[0], IntegerType::Usize)?;
+        }
         read.consume(used);
         Ok(out)
     } else {
-        deserialize_varint_cold_usize(read, endian)
+        deserialize_varint_cold_usize(read, endian, strict)
     }
 }
 
-pub fn varint_decode_u128<R: Reader>(read: &mut R, endian: Endian) -> Result<u128, DecodeError> {
+pub fn varint_decode_u128<R: Reader>(
+    read: &mut R,
+    endian: Endian,
+    strict: bool,
+) -> Result<u128, DecodeError> {
     if let Some(bytes) = read.peek_read(17) {
         let (discriminant, bytes) = bytes.split_at(1);
         let (out, used) = match discriminant[0] {
@@ -376,10 +474,13 @@ pub fn varint_decode_u128<R: Reader>(read: &mut R, endian: Endian) -> Result<u12
             _ => return invalid_varint_discriminant(IntegerType::Usize, IntegerType::Reserved),
         };
 
+        if strict {
+            check_canonical(out, discriminant[0], IntegerType::U128)?;
+        }
         read.consume(used);
         Ok(out)
     } else {
-        deserialize_varint_cold_u128(read, endian)
+        deserialize_varint_cold_u128(read, endian, strict)
     }
 }
 
@@ -392,11 +493,11 @@ fn test_decode_u16() {
     ];
     for &(slice, expected_le, expected_be) in cases {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u16(&mut reader, Endian::Little).unwrap();
+        let found = varint_decode_u16(&mut reader, Endian::Little, false).unwrap();
         assert_eq!(expected_le, found);
 
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u16(&mut reader, Endian::Big).unwrap();
+        let found = varint_decode_u16(&mut reader, Endian::Big, false).unwrap();
         assert_eq!(expected_be, found);
     }
 
@@ -428,7 +529,7 @@ fn test_decode_u16() {
 
     for (slice, expected) in errors {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u16(&mut reader, Endian::Little).unwrap_err();
+        let found = varint_decode_u16(&mut reader, Endian::Little, false).unwrap_err();
         assert_eq!(std::format!("{:?}", expected), std::format!("{:?}", found));
     }
 }
@@ -443,11 +544,11 @@ fn test_decode_u32() {
     ];
     for &(slice, expected_le, expected_be) in cases {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u32(&mut reader, Endian::Little).unwrap();
+        let found = varint_decode_u32(&mut reader, Endian::Little, false).unwrap();
         assert_eq!(expected_le, found);
 
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u32(&mut reader, Endian::Big).unwrap();
+        let found = varint_decode_u32(&mut reader, Endian::Big, false).unwrap();
         assert_eq!(expected_be, found);
     }
 
@@ -482,7 +583,7 @@ fn test_decode_u32() {
 
     for (slice, expected) in errors {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u32(&mut reader, Endian::Little).unwrap_err();
+        let found = varint_decode_u32(&mut reader, Endian::Little, false).unwrap_err();
         assert_eq!(std::format!("{:?}", expected), std::format!("{:?}", found));
     }
 }
@@ -502,11 +603,11 @@ fn test_decode_u64() {
     ];
     for &(slice, expected_le, expected_be) in cases {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u64(&mut reader, Endian::Little).unwrap();
+        let found = varint_decode_u64(&mut reader, Endian::Little, false).unwrap();
         assert_eq!(expected_le, found);
 
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u64(&mut reader, Endian::Big).unwrap();
+        let found = varint_decode_u64(&mut reader, Endian::Big, false).unwrap();
         assert_eq!(expected_be, found);
     }
 
@@ -560,7 +661,7 @@ fn test_decode_u64() {
 
     for (slice, expected) in errors {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u64(&mut reader, Endian::Little).unwrap_err();
+        let found = varint_decode_u64(&mut reader, Endian::Little, false).unwrap_err();
         assert_eq!(std::format!("{:?}", expected), std::format!("{:?}", found));
     }
 }
@@ -585,11 +686,11 @@ fn test_decode_u128() {
     ];
     for &(slice, expected_le, expected_be) in cases {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u128(&mut reader, Endian::Little).unwrap();
+        let found = varint_decode_u128(&mut reader, Endian::Little, false).unwrap();
         assert_eq!(expected_le, found);
 
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u128(&mut reader, Endian::Big).unwrap();
+        let found = varint_decode_u128(&mut reader, Endian::Big, false).unwrap();
         assert_eq!(expected_be, found);
     }
 
@@ -697,8 +798,50 @@ fn test_decode_u128() {
 
     for (slice, expected) in errors {
         let mut reader = crate::de::read::SliceReader::new(slice);
-        let found = varint_decode_u128(&mut reader, Endian::Little).unwrap_err();
+        let found = varint_decode_u128(&mut reader, Endian::Little, false).unwrap_err();
         std::dbg!(slice);
         assert_eq!(std::format!("{:?}", expected), std::format!("{:?}", found));
     }
 }
+
+#[test]
+fn test_decode_strict() {
+    let cases: &[(&[u8], u64)] = &[
+        (&[250], 250),
+        (&[U16_BYTE, 251, 0], 251),
+        (&[U32_BYTE, 0, 0, 1, 0], 65536),
+        (&[U64_BYTE, 0, 0, 0, 0, 1, 0, 0, 0], 1 << 32),
+    ];
+    for &(slice, expected) in cases {
+        let mut reader = crate::de::read::SliceReader::new(slice);
+        let found = varint_decode_u64(&mut reader, Endian::Little, true).unwrap();
+        assert_eq!(expected, found);
+    }
+
+    let errors: &[&[u8]] = &[
+        &[U16_BYTE, 250, 0],
+        &[U32_BYTE, 255, 255, 0, 0],
+        &[U64_BYTE, 255, 255, 255, 255, 0, 0, 0, 0],
+    ];
+    for &slice in errors {
+        // The fast path
+        let mut reader = crate::de::read::SliceReader::new(slice);
+        assert!(varint_decode_u64(&mut reader, Endian::Little, false).is_ok());
+        let mut reader = crate::de::read::SliceReader::new(slice);
+        assert!(matches!(
+            varint_decode_u64(&mut reader, Endian::Little, true),
+            Err(DecodeError::NonCanonicalInteger {
+                integer_type: IntegerType::U64
+            })
+        ));
+
+        // The cold path
+        let mut reader = crate::de::read::SliceReader::new(slice);
+        assert!(matches!(
+            deserialize_varint_cold_u64(&mut reader, Endian::Little, true),
+            Err(DecodeError::NonCanonicalInteger {
+                integer_type: IntegerType::U64
+            })
+        ));
+    }
+}
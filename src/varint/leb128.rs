@@ -0,0 +1,322 @@
+//! Unsigned LEB128 encoding, as used by protobuf and Go's `encoding/binary`.
+//!
+//! Every byte stores 7 bits of the value, starting with the least significant bits. The most significant bit of a byte is set if more bytes follow.
+//! Signed integers are first converted to unsigned integers with the zigzag algorithm.
+
+use crate::{
+    de::read::Reader,
+    enc::write::Writer,
+    error::{DecodeError, EncodeError, IntegerType},
+};
+
+const CONTINUATION_BIT: u8 = 0x80;
+const VALUE_BITS: u8 = 0x7F;
+
+#[inline]
+fn leb128_encode_u64_inner<W: Writer>(writer: &mut W, mut val: u64) -> Result<(), EncodeError> {
+    let mut bytes = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = val as u8 & VALUE_BITS;
+        val >>= 7;
+        if val == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | CONTINUATION_BIT;
+        len += 1;
+    }
+    writer.write(&bytes[..len])
+}
+
+pub fn leb128_encode_u16<W: Writer>(writer: &mut W, val: u16) -> Result<(), EncodeError> {
+    leb128_encode_u64_inner(writer, val as u64)
+}
+
+pub fn leb128_encode_u32<W: Writer>(writer: &mut W, val: u32) -> Result<(), EncodeError> {
+    leb128_encode_u64_inner(writer, val as u64)
+}
+
+pub fn leb128_encode_u64<W: Writer>(writer: &mut W, val: u64) -> Result<(), EncodeError> {
+    leb128_encode_u64_inner(writer, val)
+}
+
+pub fn leb128_encode_u128<W: Writer>(writer: &mut W, mut val: u128) -> Result<(), EncodeError> {
+    let mut bytes = [0u8; 19];
+    let mut len = 0;
+    loop {
+        let byte = val as u8 & VALUE_BITS;
+        val >>= 7;
+        if val == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | CONTINUATION_BIT;
+        len += 1;
+    }
+    writer.write(&bytes[..len])
+}
+
+pub fn leb128_encode_usize<W: Writer>(writer: &mut W, val: usize) -> Result<(), EncodeError> {
+    // usize is being encoded as a u64
+    leb128_encode_u64_inner(writer, val as u64)
+}
+
+pub fn leb128_encode_i16<W: Writer>(writer: &mut W, val: i16) -> Result<(), EncodeError> {
+    leb128_encode_u16(writer, ((val << 1) ^ (val >> 15)) as u16)
+}
+
+pub fn leb128_encode_i32<W: Writer>(writer: &mut W, val: i32) -> Result<(), EncodeError> {
+    leb128_encode_u32(writer, ((val << 1) ^ (val >> 31)) as u32)
+}
+
+pub fn leb128_encode_i64<W: Writer>(writer: &mut W, val: i64) -> Result<(), EncodeError> {
+    leb128_encode_u64(writer, ((val << 1) ^ (val >> 63)) as u64)
+}
+
+pub fn leb128_encode_i128<W: Writer>(writer: &mut W, val: i128) -> Result<(), EncodeError> {
+    leb128_encode_u128(writer, ((val << 1) ^ (val >> 127)) as u128)
+}
+
+pub fn leb128_encode_isize<W: Writer>(writer: &mut W, val: isize) -> Result<(), EncodeError> {
+    // isize is being encoded as a i64
+    leb128_encode_i64(writer, val as i64)
+}
+
+/// Decode a value of at most `bits` bits, reading bytes with `next_byte`. Returns the value and the amount of bytes that were read.
+///
+/// If `strict` is set, values that end with a redundant zero byte are rejected.
+#[inline]
+fn leb128_decode_bytes(
+    mut next_byte: impl FnMut() -> Result<u8, DecodeError>,
+    bits: u32,
+    integer_type: IntegerType,
+    strict: bool,
+) -> Result<(u128, usize), DecodeError> {
+    // None of the integer sizes are a multiple of 7, so this rounds up
+    let max_bytes = bits as usize / 7 + 1;
+    let mut result = 0u128;
+    let mut shift = 0;
+    for used in 1..=max_bytes {
+        let byte = next_byte()?;
+        let value = (byte & VALUE_BITS) as u128;
+        // The last byte may only contain the bits that are left
+        if shift + 7 > bits && value >> (bits - shift) != 0 {
+            break;
+        }
+        result |= value << shift;
+        if byte & CONTINUATION_BIT == 0 {
+            if strict && byte == 0 && used > 1 {
+                return Err(DecodeError::NonCanonicalInteger { integer_type });
+            }
+            return Ok((result, used));
+        }
+        shift += 7;
+    }
+    Err(DecodeError::Leb128Overflow {
+        expected: integer_type,
+    })
+}
+
+#[inline]
+fn leb128_decode<R: Reader>(
+    read: &mut R,
+    bits: u32,
+    integer_type: IntegerType,
+    strict: bool,
+) -> Result<u128, DecodeError> {
+    // None of the integer sizes are a multiple of 7, so this rounds up
+    let max_bytes = bits as usize / 7 + 1;
+    if let Some(bytes) = read.peek_read(max_bytes) {
+        let mut bytes = bytes.iter();
+        // `peek_read` returned at least `max_bytes` bytes, so `next()` will always return a byte
+        let (result, used) = leb128_decode_bytes(
+            || Ok(bytes.next().copied().unwrap_or_default()),
+            bits,
+            integer_type,
+            strict,
+        )?;
+        read.consume(used);
+        Ok(result)
+    } else {
+        leb128_decode_bytes(
+            || {
+                let mut byte = [0u8; 1];
+                read.read(&mut byte)?;
+                Ok(byte[0])
+            },
+            bits,
+            integer_type,
+            strict,
+        )
+        .map(|(result, _)| result)
+    }
+}
+
+pub fn leb128_decode_u16<R: Reader>(read: &mut R, strict: bool) -> Result<u16, DecodeError> {
+    leb128_decode(read, 16, IntegerType::U16, strict).map(|val| val as u16)
+}
+
+pub fn leb128_decode_u32<R: Reader>(read: &mut R, strict: bool) -> Result<u32, DecodeError> {
+    leb128_decode(read, 32, IntegerType::U32, strict).map(|val| val as u32)
+}
+
+pub fn leb128_decode_u64<R: Reader>(read: &mut R, strict: bool) -> Result<u64, DecodeError> {
+    leb128_decode(read, 64, IntegerType::U64, strict).map(|val| val as u64)
+}
+
+pub fn leb128_decode_u128<R: Reader>(read: &mut R, strict: bool) -> Result<u128, DecodeError> {
+    leb128_decode(read, 128, IntegerType::U128, strict)
+}
+
+pub fn leb128_decode_usize<R: Reader>(read: &mut R, strict: bool) -> Result<usize, DecodeError> {
+    // usize is being encoded as a u64
+    let val = leb128_decode(read, 64, IntegerType::Usize, strict)? as u64;
+    val.try_into()
+        .map_err(|_| DecodeError::OutsideUsizeRange(val))
+}
+
+pub fn leb128_decode_i16<R: Reader>(read: &mut R, strict: bool) -> Result<i16, DecodeError> {
+    let n = leb128_decode(read, 16, IntegerType::I16, strict)? as u16;
+    Ok((n >> 1) as i16 ^ -((n & 1) as i16))
+}
+
+pub fn leb128_decode_i32<R: Reader>(read: &mut R, strict: bool) -> Result<i32, DecodeError> {
+    let n = leb128_decode(read, 32, IntegerType::I32, strict)? as u32;
+    Ok((n >> 1) as i32 ^ -((n & 1) as i32))
+}
+
+pub fn leb128_decode_i64<R: Reader>(read: &mut R, strict: bool) -> Result<i64, DecodeError> {
+    let n = leb128_decode(read, 64, IntegerType::I64, strict)? as u64;
+    Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+}
+
+pub fn leb128_decode_i128<R: Reader>(read: &mut R, strict: bool) -> Result<i128, DecodeError> {
+    let n = leb128_decode(read, 128, IntegerType::I128, strict)?;
+    Ok((n >> 1) as i128 ^ -((n & 1) as i128))
+}
+
+pub fn leb128_decode_isize<R: Reader>(read: &mut R, strict: bool) -> Result<isize, DecodeError> {
+    // isize is being encoded as a i64
+    let n = leb128_decode(read, 64, IntegerType::Isize, strict)? as u64;
+    Ok(((n >> 1) as i64 ^ -((n & 1) as i64)) as isize)
+}
+
+#[test]
+fn test_encode_u64() {
+    use crate::enc::write::SliceWriter;
+
+    let cases: &[(u64, &[u8])] = &[
+        (0, &[0]),
+        (1, &[1]),
+        (127, &[127]),
+        (128, &[0x80, 1]),
+        (300, &[0xAC, 0x02]),
+        (16_384, &[0x80, 0x80, 1]),
+        (
+            u64::MAX,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 1],
+        ),
+    ];
+    for &(value, expected) in cases {
+        let mut buffer = [0u8; 20];
+        let mut writer = SliceWriter::new(&mut buffer);
+        leb128_encode_u64(&mut writer, value).unwrap();
+        assert_eq!(&buffer[..expected.len()], expected);
+
+        let mut reader = crate::de::read::SliceReader::new(expected);
+        assert_eq!(leb128_decode_u64(&mut reader, false).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_encode_signed() {
+    use crate::enc::write::SliceWriter;
+
+    // These are the zigzag values from the protobuf documentation
+    let cases: &[(i64, &[u8])] = &[
+        (0, &[0]),
+        (-1, &[1]),
+        (1, &[2]),
+        (-2, &[3]),
+        (2_147_483_647, &[0xFE, 0xFF, 0xFF, 0xFF, 0x0F]),
+        (-2_147_483_648, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+    ];
+    for &(value, expected) in cases {
+        let mut buffer = [0u8; 20];
+        let mut writer = SliceWriter::new(&mut buffer);
+        leb128_encode_i64(&mut writer, value).unwrap();
+        assert_eq!(&buffer[..expected.len()], expected);
+
+        let mut reader = crate::de::read::SliceReader::new(expected);
+        assert_eq!(leb128_decode_i64(&mut reader, false).unwrap(), value);
+
+        let mut buffer = [0u8; 20];
+        let mut writer = SliceWriter::new(&mut buffer);
+        leb128_encode_i32(&mut writer, value as i32).unwrap();
+        assert_eq!(&buffer[..expected.len()], expected);
+
+        let mut reader = crate::de::read::SliceReader::new(expected);
+        assert_eq!(leb128_decode_i32(&mut reader, false).unwrap(), value as i32);
+    }
+
+    for value in [i128::MIN, -1, 0, 1, i128::MAX] {
+        let mut buffer = [0u8; 20];
+        let mut writer = SliceWriter::new(&mut buffer);
+        leb128_encode_i128(&mut writer, value).unwrap();
+        let mut reader = crate::de::read::SliceReader::new(&buffer);
+        assert_eq!(leb128_decode_i128(&mut reader, false).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_decode_overflow() {
+    let errors: &[(&[u8], IntegerType)] = &[
+        // u16::MAX + 1
+        (&[0x80, 0x80, 0x04], IntegerType::U16),
+        // Too many bytes
+        (&[0x80, 0x80, 0x80, 0], IntegerType::U16),
+        // u32::MAX + 1
+        (&[0x80, 0x80, 0x80, 0x80, 0x10], IntegerType::U32),
+        (
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 2],
+            IntegerType::U64,
+        ),
+    ];
+    for (slice, integer_type) in errors {
+        let mut reader = crate::de::read::SliceReader::new(slice);
+        let result = match integer_type {
+            IntegerType::U16 => leb128_decode_u16(&mut reader, false).map(|_| ()),
+            IntegerType::U32 => leb128_decode_u32(&mut reader, false).map(|_| ()),
+            _ => leb128_decode_u64(&mut reader, false).map(|_| ()),
+        };
+        match result {
+            Err(DecodeError::Leb128Overflow { expected }) if &expected == integer_type => {}
+            x => panic!("Expected overflow of {:?}, got {:?}", integer_type, x),
+        }
+    }
+
+    // Redundant zero bytes are allowed, as long as they fit in the maximum length
+    let mut reader = crate::de::read::SliceReader::new(&[0x81, 0x80, 0]);
+    assert_eq!(leb128_decode_u16(&mut reader, false).unwrap(), 1);
+    // Unless strict decoding is used
+    let mut reader = crate::de::read::SliceReader::new(&[0x81, 0x80, 0]);
+    assert!(matches!(
+        leb128_decode_u16(&mut reader, true),
+        Err(DecodeError::NonCanonicalInteger {
+            integer_type: IntegerType::U16
+        })
+    ));
+    let mut reader = crate::de::read::SliceReader::new(&[0x80, 0x01]);
+    assert_eq!(leb128_decode_u16(&mut reader, true).unwrap(), 128);
+    let mut reader = crate::de::read::SliceReader::new(&[0]);
+    assert_eq!(leb128_decode_u16(&mut reader, true).unwrap(), 0);
+
+    let mut reader = crate::de::read::SliceReader::new(&[0x80]);
+    assert!(matches!(
+        leb128_decode_u32(&mut reader, false),
+        Err(DecodeError::UnexpectedEnd { .. })
+    ));
+}
@@ -1,9 +1,12 @@
+mod decode_batch;
 mod decode_signed;
 mod decode_unsigned;
 mod encode_signed;
 mod encode_unsigned;
+mod leb128;
 
 pub use self::{
+    decode_batch::{varint_decode_batch, zigzag_decode_byte},
     decode_signed::{
         varint_decode_i128, varint_decode_i16, varint_decode_i32, varint_decode_i64,
         varint_decode_isize,
@@ -20,6 +23,13 @@ pub use self::{
         varint_encode_u128, varint_encode_u16, varint_encode_u32, varint_encode_u64,
         varint_encode_usize,
     },
+    leb128::{
+        leb128_decode_i128, leb128_decode_i16, leb128_decode_i32, leb128_decode_i64,
+        leb128_decode_isize, leb128_decode_u128, leb128_decode_u16, leb128_decode_u32,
+        leb128_decode_u64, leb128_decode_usize, leb128_encode_i128, leb128_encode_i16,
+        leb128_encode_i32, leb128_encode_i64, leb128_encode_isize, leb128_encode_u128,
+        leb128_encode_u16, leb128_encode_u32, leb128_encode_u64, leb128_encode_usize,
+    },
 };
 
 pub(self) const SINGLE_BYTE_MAX: u8 = 250;
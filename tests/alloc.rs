@@ -175,6 +175,58 @@ fn test_container_limits() {
     }
 }
 
+#[test]
+fn test_container_preallocation() {
+    use bincode::{error::DecodeError, BorrowDecode, Decode};
+
+    // A length that would allocate terabytes of memory, followed by only a single entry
+    #[cfg(target_pointer_width = "64")]
+    let len = 1u64 << 40;
+    #[cfg(target_pointer_width = "32")]
+    let len = u64::from(u32::max_value());
+    let slice = bincode::encode_to_vec((len, 5u8), bincode::config::standard()).unwrap();
+
+    fn validate_fail<T: Decode + for<'de> BorrowDecode<'de> + core::fmt::Debug>(slice: &[u8]) {
+        let name = core::any::type_name::<T>();
+        let result = bincode::decode_from_slice::<T, _>(slice, bincode::config::standard());
+        match result {
+            Err(DecodeError::UnexpectedEnd { .. }) => {}
+            other => panic!(
+                "Expected UnexpectedEnd whilst decoding {}, got {:?}",
+                name, other
+            ),
+        }
+
+        // Readers that don't know how many bytes are left
+        #[cfg(feature = "std")]
+        {
+            let result = bincode::decode_from_std_read::<T, _, _>(
+                &mut std::io::Cursor::new(slice),
+                bincode::config::standard(),
+            );
+            match result {
+                Err(DecodeError::Io { inner, .. })
+                    if inner.kind() == std::io::ErrorKind::UnexpectedEof => {}
+                other => panic!(
+                    "Expected an UnexpectedEof io error whilst decoding {}, got {:?}",
+                    name, other
+                ),
+            }
+        }
+    }
+
+    validate_fail::<BinaryHeap<u64>>(&slice);
+    validate_fail::<VecDeque<u64>>(&slice);
+    validate_fail::<Vec<u64>>(&slice);
+    validate_fail::<Vec<Vec<u8>>>(&slice);
+    validate_fail::<String>(&slice);
+    #[cfg(feature = "std")]
+    {
+        validate_fail::<std::collections::HashMap<u64, u64>>(&slice);
+        validate_fail::<std::collections::HashSet<u64>>(&slice);
+    }
+}
+
 #[cfg(target_has_atomic = "ptr")]
 #[test]
 fn test_arc_str() {
@@ -193,3 +245,452 @@ fn test_arc_str() {
     let decoded: Arc<str> = bincode::borrow_decode_from_slice(slice, config).unwrap().0;
     assert_eq!(decoded, start);
 }
+
+#[test]
+fn test_duplicate_key_policy() {
+    use bincode::error::DecodeError;
+
+    // A map with 3 entries, of which the first and last have key 1
+    let encoded = [3, 1, 10, 2, 20, 1, 30];
+
+    let decoded: BTreeMap<u8, u8> =
+        bincode::decode_from_slice(&encoded, bincode::config::standard())
+            .unwrap()
+            .0;
+    assert_eq!(decoded[&1], 30);
+    let decoded: BTreeMap<u8, u8> =
+        bincode::decode_from_slice(&encoded, bincode::config::standard().with_first_key_wins())
+            .unwrap()
+            .0;
+    assert_eq!(decoded[&1], 10);
+    assert_eq!(decoded.len(), 2);
+    let result: Result<(BTreeMap<u8, u8>, usize), _> = bincode::decode_from_slice(
+        &encoded,
+        bincode::config::standard().with_duplicate_key_error(),
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::DuplicateMapKey { index: 2 })
+    ));
+
+    // A set with 3 entries, of which the first and last are equal
+    let encoded = [3, 1, 2, 1];
+    let decoded: BTreeSet<u8> =
+        bincode::decode_from_slice(&encoded, bincode::config::standard().with_first_key_wins())
+            .unwrap()
+            .0;
+    assert_eq!(decoded.len(), 2);
+    let result: Result<(BTreeSet<u8>, usize), _> = bincode::decode_from_slice(
+        &encoded,
+        bincode::config::standard().with_duplicate_key_error(),
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::DuplicateMapKey { index: 2 })
+    ));
+
+    let mut config = bincode::config::DynamicConfig::standard();
+    config.duplicate_key_policy = bincode::config::DuplicateKeyPolicy::Error;
+    let result: Result<(BTreeSet<u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DuplicateMapKey { index: 2 })
+    ));
+}
+
+#[test]
+fn test_bounded() {
+    use bincode::error::DecodeError;
+    use bincode::{BoundedString, BoundedVec};
+
+    let mut vec = BoundedVec::<u32, 2>::new();
+    assert_eq!(vec.try_push(1), Ok(()));
+    assert_eq!(vec.try_push(2), Ok(()));
+    assert_eq!(vec.try_push(3), Err(3));
+    assert_eq!(*vec, [1, 2]);
+    the_same(vec);
+    assert_eq!(
+        BoundedVec::<u32, 2>::try_from(vec![1, 2, 3]),
+        Err(vec![1, 2, 3])
+    );
+
+    let mut string = BoundedString::<3>::try_from("ab").unwrap();
+    assert_eq!(string.try_push('c'), Ok(()));
+    assert_eq!(string.try_push('d'), Err('d'));
+    assert_eq!(string.as_str(), "abc");
+    the_same(string);
+    assert_eq!(
+        BoundedString::<3>::try_from(String::from("abcd")),
+        Err(String::from("abcd"))
+    );
+
+    let config = bincode::config::standard();
+    let encoded = bincode::encode_to_vec(vec![1u32, 2, 3], config).unwrap();
+    let result = bincode::decode_from_slice::<BoundedVec<u32, 2>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 3, max: 2 })
+    ));
+    let result = bincode::borrow_decode_from_slice::<BoundedVec<u32, 2>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 3, max: 2 })
+    ));
+
+    let encoded = bincode::encode_to_vec("abcd", config).unwrap();
+    let result = bincode::decode_from_slice::<BoundedString<3>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 4, max: 3 })
+    ));
+    let result = bincode::borrow_decode_from_slice::<BoundedString<3>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 4, max: 3 })
+    ));
+}
+
+#[test]
+fn test_encoded_size() {
+    fn validate<C: bincode::config::Config>(config: C) {
+        let value = (5u32, String::from("Hello world"), vec![1u64, 2, u64::MAX]);
+        let encoded = bincode::encode_to_vec(&value, config).unwrap();
+        assert_eq!(encoded.len(), encoded.capacity());
+        assert_eq!(
+            bincode::encoded_size(&value, config).unwrap(),
+            encoded.len()
+        );
+    }
+    validate(bincode::config::standard());
+    validate(bincode::config::standard().with_fixed_int_encoding());
+
+    let result = bincode::encoded_size(
+        String::from("Hello world"),
+        bincode::config::standard().with_limit::<8>(),
+    );
+    assert!(matches!(
+        result,
+        Err(bincode::error::EncodeError::LimitExceeded)
+    ));
+}
+
+#[test]
+fn test_encode_append_to_vec() {
+    let config = bincode::config::standard();
+    let mut buffer = Vec::with_capacity(64);
+    let capacity = buffer.capacity();
+
+    let first = (5u32, String::from("Hello"));
+    let second = vec![1u16, 2, 3];
+    let written = bincode::encode_append_to_vec(&first, &mut buffer, config).unwrap();
+    assert_eq!(written, buffer.len());
+    let written = bincode::encode_append_to_vec(&second, &mut buffer, config).unwrap();
+    assert_eq!(written, 4);
+    assert_eq!(buffer.capacity(), capacity);
+
+    let (decoded, len): ((u32, String), usize) =
+        bincode::decode_from_slice(&buffer, config).unwrap();
+    assert_eq!(decoded, first);
+    let (decoded, _): (Vec<u16>, usize) =
+        bincode::decode_from_slice(&buffer[len..], config).unwrap();
+    assert_eq!(decoded, second);
+
+    // A failed encode leaves the buffer as it was
+    let before = buffer.clone();
+    let config = config.with_limit::<4>();
+    let result = bincode::encode_append_to_vec(&first, &mut buffer, config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::EncodeError::LimitExceeded)
+    ));
+    assert_eq!(buffer, before);
+
+    let mut writer = bincode::VecWriter::from(buffer);
+    bincode::encode_into_writer(7u8, &mut writer, config).unwrap();
+    assert_eq!(writer.as_slice().last(), Some(&7));
+    assert_eq!(writer.into_inner().len(), before.len() + 1);
+}
+
+#[test]
+fn test_byte_collections() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    for config in [
+        bincode::config::standard(),
+        bincode::config::legacy().with_variable_int_encoding(),
+    ] {
+        let mut expected = bincode::encode_to_vec(256usize, config).unwrap();
+        expected.extend_from_slice(&bytes);
+
+        assert_eq!(bincode::encode_to_vec(&bytes, config).unwrap(), expected);
+        assert_eq!(
+            bincode::encode_to_vec(&bytes[..], config).unwrap(),
+            expected
+        );
+        let boxed: Box<[u8]> = bytes.clone().into_boxed_slice();
+        assert_eq!(bincode::encode_to_vec(&boxed, config).unwrap(), expected);
+        let cow: Cow<[u8]> = Cow::Borrowed(&bytes);
+        assert_eq!(bincode::encode_to_vec(&cow, config).unwrap(), expected);
+
+        // Make sure the deque wraps around, so it consists of two slices
+        let mut deque: VecDeque<u8> = VecDeque::with_capacity(256);
+        deque.extend(&bytes[128..]);
+        for byte in bytes[..128].iter().rev() {
+            deque.push_front(*byte);
+        }
+        assert!(!deque.as_slices().1.is_empty());
+        assert_eq!(bincode::encode_to_vec(&deque, config).unwrap(), expected);
+
+        let (decoded, len): (Vec<u8>, usize) =
+            bincode::decode_from_slice(&expected, config).unwrap();
+        assert_eq!(decoded, bytes);
+        assert_eq!(len, expected.len());
+        let (decoded, _): (Box<[u8]>, usize) =
+            bincode::decode_from_slice(&expected, config).unwrap();
+        assert_eq!(decoded, boxed);
+        let (decoded, _): (Cow<[u8]>, usize) =
+            bincode::decode_from_slice(&expected, config).unwrap();
+        assert_eq!(decoded, cow);
+        let (decoded, _): (VecDeque<u8>, usize) =
+            bincode::decode_from_slice(&expected, config).unwrap();
+        assert_eq!(decoded, deque);
+        let (decoded, _): (Vec<u8>, usize) =
+            bincode::borrow_decode_from_slice(&expected, config).unwrap();
+        assert_eq!(decoded, bytes);
+    }
+
+    let array = [7u8; 300];
+    let encoded = bincode::encode_to_vec(array, bincode::config::standard()).unwrap();
+    assert_eq!(&encoded[..3], &[251, 44, 1]);
+    assert_eq!(&encoded[3..], &array[..]);
+
+    // A length that is larger than the input fails without allocating that much memory
+    let encoded =
+        bincode::encode_to_vec((u64::MAX, 1u8, 2u8), bincode::config::standard()).unwrap();
+    let result = bincode::decode_from_slice::<Vec<u8>, _>(&encoded, bincode::config::standard());
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::UnexpectedEnd { .. })
+    ));
+    let result = bincode::decode_from_slice::<Vec<u8>, _>(
+        &encoded,
+        bincode::config::standard().with_limit::<1000>(),
+    );
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::LimitExceeded)
+    ));
+}
+
+/// Encode `values` one by one, which is what the bulk encoding of numeric slices has to match
+fn encode_per_element<T: bincode::Encode, C: bincode::config::Config>(
+    values: &[T],
+    config: C,
+) -> Vec<u8> {
+    let mut expected = bincode::encode_to_vec(values.len(), config).unwrap();
+    for value in values {
+        bincode::encode_append_to_vec(value, &mut expected, config).unwrap();
+    }
+    expected
+}
+
+fn check_numeric_collections<C: bincode::config::Config>(config: C) {
+    let ints: Vec<u32> = (0..1000u32).map(|i| i.wrapping_mul(0x0101_0101)).collect();
+    let encoded = bincode::encode_to_vec(&ints, config).unwrap();
+    assert_eq!(encoded, encode_per_element(&ints, config));
+    let (decoded, len): (Vec<u32>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, ints);
+    assert_eq!(len, encoded.len());
+    let (decoded, _): (Vec<u32>, usize) =
+        bincode::borrow_decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, ints);
+
+    let signed: Box<[i64]> = (-500..500).map(|i| i * 0x0102_0304_0506).collect();
+    let encoded = bincode::encode_to_vec(&signed, config).unwrap();
+    assert_eq!(encoded, encode_per_element(&signed, config));
+    let (decoded, _): (Box<[i64]>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, signed);
+
+    let deque: VecDeque<u16> = (0..300).collect();
+    let encoded = bincode::encode_to_vec(&deque, config).unwrap();
+    assert_eq!(
+        encoded,
+        encode_per_element(&deque.iter().collect::<Vec<_>>(), config)
+    );
+    let (decoded, _): (VecDeque<u16>, usize) =
+        bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, deque);
+
+    let floats = [0.5f32, -1.25, f32::MAX, f32::MIN_POSITIVE, f32::INFINITY];
+    let encoded = bincode::encode_to_vec(floats, config).unwrap();
+    let mut expected = Vec::new();
+    for float in floats {
+        bincode::encode_append_to_vec(float, &mut expected, config).unwrap();
+    }
+    // Depending on the config, arrays may start with their length
+    assert!(encoded.ends_with(&expected));
+    let (decoded, _): ([f32; 5], usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, floats);
+
+    let doubles: Vec<f64> = (0..100).map(|i| i as f64 / 3.0).collect();
+    let encoded = bincode::encode_to_vec(&doubles, config).unwrap();
+    assert_eq!(encoded, encode_per_element(&doubles, config));
+    let (decoded, _): (Vec<f64>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, doubles);
+
+    // Truncated input fails instead of returning partially initialized values
+    let result = bincode::decode_from_slice::<Vec<f64>, _>(&encoded[..encoded.len() - 1], config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn test_numeric_collections() {
+    check_numeric_collections(bincode::config::standard());
+    check_numeric_collections(bincode::config::legacy());
+    check_numeric_collections(bincode::config::standard().with_fixed_int_encoding());
+    check_numeric_collections(
+        bincode::config::standard()
+            .with_fixed_int_encoding()
+            .with_big_endian(),
+    );
+    check_numeric_collections(bincode::config::legacy().with_big_endian());
+
+    // NaNs are still checked and replaced one by one
+    let nan = f32::from_bits(0x7FC0_0001);
+    let config = bincode::config::legacy().with_canonical_encoding();
+    let encoded = bincode::encode_to_vec(vec![1.0f32, nan], config).unwrap();
+    assert_eq!(&encoded[12..], &0x7FC0_0000u32.to_le_bytes());
+
+    let config = bincode::config::legacy().with_strict_decoding();
+    let encoded = bincode::encode_to_vec(vec![1.0f32, nan], config).unwrap();
+    let result = bincode::decode_from_slice::<Vec<f32>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::NonCanonicalNaN)
+    ));
+    let (decoded, _): (Vec<f32>, usize) =
+        bincode::decode_from_slice(&encoded, bincode::config::legacy()).unwrap();
+    assert_eq!(decoded[1].to_bits(), nan.to_bits());
+}
+
+#[test]
+fn test_varint_collections() {
+    let config = bincode::config::standard();
+    // Long runs of single-byte values, interrupted by values that need a marker byte
+    let values: Vec<u64> = (0..5000u64)
+        .map(|i| match i % 97 {
+            0 => u64::MAX - i,
+            50 => 251 + i,
+            _ => i % 251,
+        })
+        .collect();
+    let encoded = bincode::encode_to_vec(&values, config).unwrap();
+    let (decoded, len): (Vec<u64>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, values);
+    assert_eq!(len, encoded.len());
+    let (decoded, _): (VecDeque<u64>, usize) =
+        bincode::borrow_decode_from_slice(&encoded, config).unwrap();
+    assert!(decoded.iter().eq(values.iter()));
+
+    let signed: Vec<i32> = (-3000..3000)
+        .map(|i| if i % 10 == 0 { i * 1000 } else { i % 125 })
+        .collect();
+    let encoded = bincode::encode_to_vec(&signed, config).unwrap();
+    let (decoded, _): (Box<[i32]>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(&decoded[..], &signed[..]);
+
+    let mut array = [0i16; 40];
+    for (i, value) in array.iter_mut().enumerate() {
+        *value = i as i16 - 20;
+    }
+    array[33] = -1000;
+    let encoded = bincode::encode_to_vec(array, config).unwrap();
+    let (decoded, _): ([i16; 40], usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, array);
+
+    // Errors in the middle of a run are still reported
+    let mut encoded = bincode::encode_to_vec(vec![1u16; 40], config).unwrap();
+    encoded[30] = 252;
+    let result = bincode::decode_from_slice::<Vec<u16>, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::InvalidIntegerType { .. })
+    ));
+
+    let mut encoded = bincode::encode_to_vec(vec![1u32; 40], config).unwrap();
+    encoded.splice(20..21, [251, 5, 0]);
+    let (decoded, _): (Vec<u32>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded[19], 5);
+    let result = bincode::decode_from_slice::<Vec<u32>, _>(&encoded, config.with_strict_decoding());
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::NonCanonicalInteger { .. })
+    ));
+
+    let encoded = bincode::encode_to_vec(&values, config).unwrap();
+    let result = bincode::decode_from_slice::<Vec<u64>, _>(&encoded[..encoded.len() - 1], config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn test_decode_in_place() {
+    let config = bincode::config::standard();
+
+    // Existing allocations are reused when the new value fits
+    let mut strings: Vec<String> = (0..4).map(|_| String::with_capacity(64)).collect();
+    let pointers: Vec<*const u8> = strings.iter().map(|s| s.as_ptr()).collect();
+    let values = vec![String::from("a"), String::from("bc"), String::from("def")];
+    let encoded = bincode::encode_to_vec(&values, config).unwrap();
+    let len = bincode::decode_from_slice_into(&mut strings, &encoded, config).unwrap();
+    assert_eq!(len, encoded.len());
+    assert_eq!(strings, values);
+    assert!(strings.iter().zip(&pointers).all(|(s, &p)| s.as_ptr() == p));
+
+    let values = vec![String::from("a"); 6];
+    let encoded = bincode::encode_to_vec(&values, config).unwrap();
+    bincode::decode_from_slice_into(&mut strings, &encoded, config).unwrap();
+    assert_eq!(strings, values);
+
+    fn check_numbers<C: bincode::config::Config>(config: C) {
+        let mut numbers: Vec<u32> = Vec::with_capacity(100);
+        let pointer = numbers.as_ptr();
+        let values: Vec<u32> = (0..100).map(|i| i * 1000).collect();
+        let encoded = bincode::encode_to_vec(&values, config).unwrap();
+        bincode::decode_from_slice_into(&mut numbers, &encoded, config).unwrap();
+        assert_eq!(numbers, values);
+        assert_eq!(numbers.as_ptr(), pointer);
+    }
+    check_numbers(bincode::config::standard());
+    check_numbers(bincode::config::legacy());
+
+    let mut value: (Option<String>, Box<Vec<u8>>, [u16; 3]) = (None, Box::default(), [0; 3]);
+    let expected = (Some(String::from("abc")), Box::new(vec![1u8, 2]), [1, 2, 3]);
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+    assert_eq!(value, expected);
+    let expected = (None, Box::new(vec![]), [4, 5, 6]);
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+    assert_eq!(value, expected);
+
+    let mut map = BTreeMap::from([(1u8, String::from("one")), (2, String::from("two"))]);
+    let expected = BTreeMap::from([(3u8, String::from("three"))]);
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    bincode::decode_from_slice_into(&mut map, &encoded, config).unwrap();
+    assert_eq!(map, expected);
+
+    // Errors are still reported, and leave a valid value behind
+    let encoded = bincode::encode_to_vec(vec![String::from("abc"); 3], config).unwrap();
+    let result =
+        bincode::decode_from_slice_into(&mut strings, &encoded[..encoded.len() - 1], config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::DecodeError::UnexpectedEnd { .. })
+    ));
+    assert!(strings.len() <= 3);
+}
@@ -144,6 +144,139 @@ fn test_slice() {
     assert_eq!(len, 8);
 }
 
+/// A buffer that is aligned for every primitive type
+#[repr(align(16))]
+struct AlignedBuffer([u8; 256]);
+
+#[test]
+fn test_borrow_numeric_slice() {
+    let mut buffer = AlignedBuffer([0; 256]);
+    let config = bincode::config::legacy().with_little_endian();
+    let input: &[u32] = &[1, 2, 3, 0xFFFF_FFFF];
+
+    if cfg!(target_endian = "little") {
+        // The u64 length keeps the values aligned
+        let len = bincode::encode_into_slice(input, &mut buffer.0, config).unwrap();
+        assert_eq!(len, 24);
+        let (output, _): (&[u32], usize) =
+            bincode::borrow_decode_from_slice(&buffer.0[..len], config).unwrap();
+        assert_eq!(output, input);
+        assert_eq!(output.as_ptr() as usize, buffer.0[8..].as_ptr() as usize);
+
+        let len = bincode::encode_into_slice((1u8, input), &mut buffer.0, config).unwrap();
+        let result = bincode::borrow_decode_from_slice::<(u8, &[u32]), _>(&buffer.0[..len], config);
+        assert!(matches!(
+            result,
+            Err(DecodeError::UnalignedSlice {
+                type_name: "u32",
+                align: 4
+            })
+        ));
+
+        // Floats are encoded as their in-memory bytes with every int encoding
+        let floats: &[f64] = &[1.5, -2.5, f64::INFINITY];
+        let config = bincode::config::standard();
+        let len =
+            bincode::encode_into_slice((1u8, bincode::AlignedSlice(floats)), &mut buffer.0, config)
+                .unwrap();
+        assert_eq!(
+            len,
+            bincode::encoded_size((1u8, bincode::AlignedSlice(floats)), config).unwrap()
+        );
+        assert_eq!(&buffer.0[..4], &[1, 3, 5, 0]);
+        let ((byte, output), decoded_len): ((u8, bincode::AlignedSlice<f64>), usize) =
+            bincode::borrow_decode_from_slice(&buffer.0[..len], config).unwrap();
+        assert_eq!(byte, 1);
+        assert_eq!(output.0, floats);
+        assert_eq!(decoded_len, len);
+
+        // Strict decoding still checks NaNs
+        let floats: &[f32] = &[1.0, f32::from_bits(0x7FC0_0001)];
+        let len = bincode::encode_into_slice(bincode::AlignedSlice(floats), &mut buffer.0, config)
+            .unwrap();
+        let result = bincode::borrow_decode_from_slice::<bincode::AlignedSlice<f32>, _>(
+            &buffer.0[..len],
+            config.with_strict_decoding(),
+        );
+        assert!(matches!(result, Err(DecodeError::NonCanonicalNaN)));
+
+        // The padding must be shorter than the alignment, and zero with strict decoding
+        let floats: &[f64] = &[1.5];
+        let len =
+            bincode::encode_into_slice((1u8, bincode::AlignedSlice(floats)), &mut buffer.0, config)
+                .unwrap();
+        assert_eq!(&buffer.0[..8], &[1, 1, 5, 0, 0, 0, 0, 0]);
+        buffer.0[4] = 0xFF;
+        let result = bincode::borrow_decode_from_slice::<(u8, bincode::AlignedSlice<f64>), _>(
+            &buffer.0[..len],
+            config,
+        );
+        assert_eq!(result.unwrap().0 .1 .0, floats);
+        let result = bincode::borrow_decode_from_slice::<(u8, bincode::AlignedSlice<f64>), _>(
+            &buffer.0[..len],
+            config.with_strict_decoding(),
+        );
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidSlicePadding {
+                type_name: "f64",
+                padding: 5
+            })
+        ));
+        let mut input = [0u8; 24];
+        input[..2].copy_from_slice(&[1, 1]);
+        input[2] = 13;
+        input[16..].copy_from_slice(&1.5f64.to_ne_bytes());
+        buffer.0[..24].copy_from_slice(&input);
+        let result = bincode::borrow_decode_from_slice::<(u8, bincode::AlignedSlice<f64>), _>(
+            &buffer.0[..24],
+            config,
+        );
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidSlicePadding {
+                type_name: "f64",
+                padding: 13
+            })
+        ));
+
+        // Appending to a `Vec` aligns the values relative to the start of the `Vec`
+        #[cfg(feature = "alloc")]
+        {
+            let mut vec = vec![0u8; 3];
+            bincode::encode_append_to_vec(bincode::AlignedSlice(floats), &mut vec, config).unwrap();
+            assert_eq!(&vec[3..5], &[1, 3]);
+            assert_eq!(vec.len(), 16);
+        }
+
+        // Canonical encoding does not depend on the position, so it writes no padding
+        let canonical = config.with_canonical_encoding();
+        let len = bincode::encode_into_slice(
+            (1u8, bincode::AlignedSlice(floats)),
+            &mut buffer.0,
+            canonical,
+        )
+        .unwrap();
+        assert_eq!(
+            &buffer.0[..len],
+            &[&[1, 1, 0][..], &1.5f64.to_le_bytes()].concat()
+        );
+    }
+
+    // Varint encoded integers can not be borrowed
+    let config = bincode::config::standard();
+    let len =
+        bincode::encode_into_slice(bincode::AlignedSlice(input), &mut buffer.0, config).unwrap();
+    let result = bincode::borrow_decode_from_slice::<bincode::AlignedSlice<u32>, _>(
+        &buffer.0[..len],
+        config,
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::NonNativeSlice { type_name: "u32" })
+    ));
+}
+
 #[test]
 fn test_option_slice() {
     let mut buffer = [0u8; 32];
@@ -282,3 +415,119 @@ fn test_duration_wrapping() {
 
     assert_eq!(result.subsec_nanos(), 294967295);
 }
+
+#[test]
+fn test_decode_exact() {
+    let config = bincode::config::standard();
+    let mut input = [0u8; 4];
+    let len = bincode::encode_into_slice((5u8, "ab"), &mut input, config).unwrap();
+    assert_eq!(len, 4);
+
+    let result: u32 = bincode::decode_from_slice_exact(&[5], config).unwrap();
+    assert_eq!(result, 5);
+    let result: (u8, &str) = bincode::borrow_decode_from_slice_exact(&input, config).unwrap();
+    assert_eq!(result, (5, "ab"));
+
+    let result: Result<u8, _> = bincode::decode_from_slice_exact(&input, config);
+    match result {
+        Err(DecodeError::TrailingBytes { remaining: 3 }) => {}
+        x => panic!("Expected TrailingBytes, got {:?}", x),
+    }
+    let result: Result<&str, _> = bincode::borrow_decode_from_slice_exact(&input[1..], config);
+    assert!(matches!(result, Ok("ab")));
+    let result: Result<(u8, u8), _> = bincode::borrow_decode_from_slice_exact(&input, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::TrailingBytes { remaining: 2 })
+    ));
+}
+
+/// Check that `value`, which should be the largest value of its type, encodes to exactly `MAX_SIZE` bytes
+fn check_max_size<T, C>(value: T, config: C)
+where
+    T: bincode::Encode + bincode::enc::MaxEncodedSize<C>,
+    C: bincode::config::StaticConfig,
+{
+    let size = bincode::encoded_size(&value, config).unwrap();
+    assert_eq!(Some(size), T::MAX_SIZE);
+}
+
+#[test]
+fn test_max_encoded_size() {
+    use bincode::config;
+    use bincode::enc::{max_encoded_size, MaxEncodedSize};
+
+    fn check_all<C: config::StaticConfig>(config: C) {
+        check_max_size(u8::MAX, config);
+        check_max_size(u16::MAX, config);
+        check_max_size(u32::MAX, config);
+        check_max_size(u64::MAX, config);
+        check_max_size(u128::MAX, config);
+        check_max_size(usize::MAX, config);
+        check_max_size(i16::MIN, config);
+        check_max_size(i32::MIN, config);
+        check_max_size(i64::MIN, config);
+        check_max_size(i128::MIN, config);
+        check_max_size(isize::MIN, config);
+        check_max_size(NonZeroU32::new(u32::MAX).unwrap(), config);
+        check_max_size(f64::MAX, config);
+        check_max_size(char::MAX, config);
+        check_max_size(true, config);
+        check_max_size((), config);
+        check_max_size([u16::MAX; 300], config);
+        check_max_size((u8::MAX, i64::MIN, Some(u32::MAX)), config);
+        check_max_size(Duration::MAX, config);
+        check_max_size(Bound::Excluded(u64::MAX), config);
+        check_max_size(Cell::new(u32::MAX), config);
+    }
+
+    check_all(config::standard());
+    check_all(config::legacy());
+    check_all(config::standard().with_leb128_int_encoding());
+    check_all(config::standard().skip_fixed_array_length());
+    check_all(config::standard().with_fixed_u32_lengths());
+
+    // `Result` is as large as its largest variant
+    let config = config::standard();
+    check_max_size::<Result<u8, u64>, _>(Err(u64::MAX), config);
+    check_max_size::<Result<u64, u8>, _>(Ok(u64::MAX), config);
+    let config = config::standard().with_varint_discriminants();
+    check_max_size::<Result<u64, u8>, _>(Ok(u64::MAX), config);
+
+    assert_eq!(<&str as MaxEncodedSize>::MAX_SIZE, None);
+    assert_eq!(<(u8, &[u8]) as MaxEncodedSize>::MAX_SIZE, None);
+    assert_eq!(
+        max_encoded_size::<(u8, [u8; 4]), config::Configuration>(),
+        6
+    );
+}
+
+#[test]
+fn test_encode_to_array() {
+    let config = bincode::config::standard();
+    let encoded = bincode::encode_to_array::<16, _, _>((1u8, "abc", u32::MAX), config).unwrap();
+    assert_eq!(
+        encoded.as_slice(),
+        &[1, 3, b'a', b'b', b'c', 252, 255, 255, 255, 255]
+    );
+    assert_eq!(encoded.len(), 10);
+
+    // The array can be moved and copied
+    let copy = encoded;
+    let (array, len) = copy.into_parts();
+    assert_eq!(&array[..len], encoded.as_slice());
+    assert!(array[len..].iter().all(|b| *b == 0));
+
+    let (decoded, _): ((u8, &str, u32), usize) =
+        bincode::borrow_decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, (1, "abc", u32::MAX));
+
+    let encoded = bincode::encode_to_array::<0, _, _>((), config).unwrap();
+    assert!(encoded.is_empty());
+
+    let result = bincode::encode_to_array::<4, _, _>(u64::MAX, config);
+    assert!(matches!(
+        result,
+        Err(bincode::error::EncodeError::UnexpectedEnd)
+    ));
+}
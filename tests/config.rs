@@ -0,0 +1,616 @@
+#![cfg(feature = "alloc")]
+
+use bincode::config::{self, DynamicConfig, Endian, IntEncoding};
+use bincode::enc::{write::SliceWriter, Encode, Encoder, EncoderImpl};
+use bincode::error::{DecodeError, EncodeError};
+
+#[test]
+fn test_dynamic_config_matches_static_config() {
+    let value = (5u16, -300i32, u64::MAX, 1.5f32, [1u8, 2, 3], 'a');
+    let mut buffer = [0u8; 64];
+    let mut dynamic_buffer = [0u8; 64];
+
+    macro_rules! check {
+        ($config:expr) => {
+            let config = $config;
+            let len = bincode::encode_into_slice(value, &mut buffer, config).unwrap();
+            let dynamic_len =
+                bincode::encode_into_slice(value, &mut dynamic_buffer, DynamicConfig::from(config))
+                    .unwrap();
+            assert_eq!(&buffer[..len], &dynamic_buffer[..dynamic_len]);
+        };
+    }
+
+    check!(config::standard());
+    check!(config::legacy());
+    check!(config::standard().with_big_endian());
+    check!(config::legacy().with_big_endian().skip_fixed_array_length());
+}
+
+#[test]
+fn test_dynamic_config_options() {
+    let mut config = DynamicConfig::standard();
+    assert_eq!(
+        bincode::encode_to_vec(300u32, config).unwrap(),
+        [251, 44, 1]
+    );
+
+    config.endian = Endian::Big;
+    assert_eq!(
+        bincode::encode_to_vec(300u32, config).unwrap(),
+        [251, 1, 44]
+    );
+
+    config.int_encoding = IntEncoding::Fixed;
+    assert_eq!(
+        bincode::encode_to_vec(300u32, config).unwrap(),
+        [0, 0, 1, 44]
+    );
+
+    config.skip_fixed_array_length = true;
+    assert_eq!(bincode::encode_to_vec([1u8, 2], config).unwrap(), [1, 2]);
+
+    let decoded: u32 = bincode::decode_from_slice(&[0, 0, 1, 44], config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, 300);
+
+    assert_eq!(DynamicConfig::default(), DynamicConfig::standard());
+    assert_eq!(
+        DynamicConfig::from(config::legacy()),
+        DynamicConfig::legacy()
+    );
+}
+
+#[test]
+fn test_dynamic_config_limit() {
+    let encoded = bincode::encode_to_vec(vec![0u8; 100], config::standard()).unwrap();
+
+    let mut config = DynamicConfig::standard();
+    config.limit = Some(50);
+    let result: Result<(Vec<u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+
+    config.limit = Some(200);
+    let (decoded, _): (Vec<u8>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, vec![0u8; 100]);
+}
+
+#[test]
+fn test_runtime_limit() {
+    let encoded = bincode::encode_to_vec(vec![0u8; 100], config::standard()).unwrap();
+
+    let config = config::standard().with_runtime_limit(50);
+    let result: Result<(Vec<u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+
+    let config = config::standard().with_runtime_limit(200);
+    let (decoded, _): (Vec<u8>, usize) = bincode::decode_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, vec![0u8; 100]);
+
+    // The limit is kept when changing other options
+    let config = config::standard().with_runtime_limit(50).with_big_endian();
+    assert_eq!(DynamicConfig::from(config).limit, Some(50));
+    assert_eq!(
+        DynamicConfig::from(config.with_runtime_limit(10).with_no_limit()).limit,
+        None
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_runtime_limit_std_read() {
+    let encoded = bincode::encode_to_vec(vec![0u8; 100], config::standard()).unwrap();
+
+    let result: Result<Vec<u8>, _> = bincode::decode_from_std_read(
+        &mut encoded.as_slice(),
+        config::standard().with_runtime_limit(50),
+    );
+    assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+
+    let decoded: Vec<u8> = bincode::decode_from_std_read(
+        &mut encoded.as_slice(),
+        config::standard().with_runtime_limit(200),
+    )
+    .unwrap();
+    assert_eq!(decoded, vec![0u8; 100]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_runtime_limit_serde() {
+    let encoded = bincode::encode_to_vec(vec![0u8; 100], config::standard()).unwrap();
+
+    let result: Result<(Vec<u8>, usize), _> =
+        bincode::serde::decode_from_slice(&encoded, config::standard().with_runtime_limit(50));
+    assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+
+    let result: Result<&[u8], _> = bincode::serde::decode_borrowed_from_slice(
+        &encoded,
+        config::standard().with_runtime_limit(50),
+    );
+    assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+
+    let decoded: &[u8] = bincode::serde::decode_borrowed_from_slice(
+        &encoded,
+        config::standard().with_runtime_limit(200),
+    )
+    .unwrap();
+    assert_eq!(decoded, &[0u8; 100][..]);
+}
+
+#[test]
+fn test_encode_limit() {
+    let value = vec![0u8; 100];
+
+    let result = bincode::encode_to_vec(&value, config::standard().with_limit::<50>());
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+    let result = bincode::encode_to_vec(&value, config::standard().with_runtime_limit(50));
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+
+    let mut buffer = [0u8; 200];
+    let result =
+        bincode::encode_into_slice(&value, &mut buffer, config::standard().with_limit::<50>());
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+
+    // 1 byte for the length, and 100 bytes for the content
+    let len =
+        bincode::encode_into_slice(&value, &mut buffer, config::standard().with_limit::<101>())
+            .unwrap();
+    assert_eq!(len, 101);
+    let result =
+        bincode::encode_into_slice(&value, &mut buffer, config::standard().with_limit::<100>());
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+
+    // The encoder keeps exposing the writer it was created with
+    let mut encoder = EncoderImpl::new(
+        SliceWriter::new(&mut buffer),
+        config::standard().with_limit::<4>(),
+    );
+    1u32.encode(&mut encoder).unwrap();
+    assert_eq!(encoder.writer().bytes_written(), 1);
+    // The length of the array still fits, but its contents do not
+    let result = [1u8; 4].encode(&mut encoder);
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+    assert_eq!(encoder.writer().bytes_written(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_encode_limit_std_write() {
+    let value = vec![0u8; 100];
+
+    // Nothing should be written if the limit is exceeded
+    let mut target = Vec::new();
+    let result =
+        bincode::encode_into_std_write(&value, &mut target, config::standard().with_limit::<50>());
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+    assert!(target.is_empty());
+
+    let len = bincode::encode_into_std_write(
+        &value,
+        &mut target,
+        config::standard().with_runtime_limit(101),
+    )
+    .unwrap();
+    assert_eq!(len, 101);
+    assert_eq!(target.len(), 101);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_encode_limit_serde() {
+    let value = vec![0u8; 100];
+
+    let result = bincode::serde::encode_to_vec(&value, config::standard().with_limit::<50>());
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+
+    let mut target = Vec::new();
+    let result = bincode::serde::encode_into_std_write(
+        &value,
+        &mut target,
+        config::standard().with_limit::<50>(),
+    );
+    assert!(matches!(result, Err(EncodeError::LimitExceeded)));
+    assert!(target.is_empty());
+
+    let encoded =
+        bincode::serde::encode_to_vec(&value, config::standard().with_limit::<101>()).unwrap();
+    assert_eq!(encoded.len(), 101);
+}
+
+#[test]
+fn test_discriminant_encoding() {
+    use bincode::config::DiscriminantEncoding;
+    use bincode::enc::{write::SliceWriter, EncoderImpl};
+
+    let value: Result<u32, u32> = Err(5);
+    assert_eq!(
+        bincode::encode_to_vec(value, config::legacy()).unwrap(),
+        [1, 0, 0, 0, 5, 0, 0, 0]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(value, config::legacy().with_u8_discriminants()).unwrap(),
+        [1, 5, 0, 0, 0]
+    );
+
+    let mut config = DynamicConfig::legacy();
+    config.discriminant_encoding = DiscriminantEncoding::Varint;
+    let encoded = bincode::encode_to_vec(value, config).unwrap();
+    assert_eq!(encoded, [1, 5, 0, 0, 0]);
+    let decoded: Result<u32, u32> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, value);
+
+    // Discriminants that don't fit in the configured width can not be encoded
+    let mut buffer = [0u8; 8];
+    let mut encoder = EncoderImpl::new(
+        SliceWriter::new(&mut buffer),
+        config::standard().with_u8_discriminants(),
+    );
+    assert!(matches!(
+        bincode::enc::encode_variant_index(&mut encoder, 256),
+        Err(EncodeError::DiscriminantOutOfRange { index: 256 })
+    ));
+    bincode::enc::encode_variant_index(&mut encoder, 255).unwrap();
+}
+
+#[test]
+fn test_discriminant_encoding_limit() {
+    use bincode::config::DiscriminantEncoding;
+
+    let value: Result<(), ()> = Err(());
+    for discriminant_encoding in [
+        DiscriminantEncoding::U32,
+        DiscriminantEncoding::U8,
+        DiscriminantEncoding::Varint,
+    ] {
+        let mut config = DynamicConfig::legacy();
+        config.discriminant_encoding = discriminant_encoding;
+        let encoded = bincode::encode_to_vec(value, config).unwrap();
+
+        // The discriminant is claimed like an integer, however many bytes it takes up
+        config.limit = Some(0);
+        let result = bincode::decode_from_slice::<Result<(), ()>, _>(&encoded, config);
+        assert!(matches!(result, Err(DecodeError::LimitExceeded)));
+        config.limit = Some(4);
+        let result = bincode::decode_from_slice::<Result<(), ()>, _>(&encoded, config);
+        assert_eq!(result.unwrap(), (value, encoded.len()));
+    }
+}
+
+#[test]
+fn test_leb128_int_encoding() {
+    let config = config::standard().with_leb128_int_encoding();
+
+    assert_eq!(bincode::encode_to_vec(1u64, config).unwrap(), [1]);
+    assert_eq!(
+        bincode::encode_to_vec(300u16, config).unwrap(),
+        [0xAC, 0x02]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(u32::MAX, config).unwrap(),
+        [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]
+    );
+    assert_eq!(bincode::encode_to_vec(-1i64, config).unwrap(), [1]);
+    assert_eq!(bincode::encode_to_vec(64i64, config).unwrap(), [0x80, 0x01]);
+    // u8 is always encoded as a single byte
+    assert_eq!(bincode::encode_to_vec(200u8, config).unwrap(), [200]);
+    // Lengths are encoded with LEB128 too
+    assert_eq!(
+        bincode::encode_to_vec(vec![0u8; 200], config).unwrap()[..2],
+        [0xC8, 0x01]
+    );
+
+    // The endianness has no effect
+    assert_eq!(
+        bincode::encode_to_vec(300u16, config.with_big_endian()).unwrap(),
+        [0xAC, 0x02]
+    );
+
+    let result: Result<(u16, usize), _> = bincode::decode_from_slice(&[0xFF, 0xFF, 0x7F], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::Leb128Overflow {
+            expected: bincode::error::IntegerType::U16
+        })
+    ));
+}
+
+#[test]
+fn test_length_encoding() {
+    use bincode::config::LengthEncoding;
+
+    let value = vec![1u16, 2];
+    assert_eq!(
+        bincode::encode_to_vec(&value, config::legacy()).unwrap(),
+        [2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(&value, config::legacy().with_fixed_u32_lengths()).unwrap(),
+        [2, 0, 0, 0, 1, 0, 2, 0]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(&value, config::legacy().with_varint_lengths()).unwrap(),
+        [2, 1, 0, 2, 0]
+    );
+    // The integer encoding of the config is not used for fixed lengths
+    assert_eq!(
+        bincode::encode_to_vec(
+            "ab",
+            config::standard()
+                .with_big_endian()
+                .with_fixed_u64_lengths()
+        )
+        .unwrap(),
+        [0, 0, 0, 0, 0, 0, 0, 2, b'a', b'b']
+    );
+
+    let mut config = DynamicConfig::legacy();
+    config.length_encoding = LengthEncoding::FixedU32;
+    let encoded = bincode::encode_to_vec(&value, config).unwrap();
+    assert_eq!(encoded, [2, 0, 0, 0, 1, 0, 2, 0]);
+    let decoded: Vec<u16> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, value);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_length_encoding_serde() {
+    let config = config::legacy().with_fixed_u32_lengths();
+    let value: std::collections::BTreeMap<u8, &str> = [(1, "a")].into_iter().collect();
+
+    let encoded = bincode::serde::encode_to_vec(&value, config).unwrap();
+    assert_eq!(encoded, bincode::encode_to_vec(&value, config).unwrap());
+    assert_eq!(encoded, [1, 0, 0, 0, 1, 1, 0, 0, 0, b'a']);
+    let decoded: std::collections::BTreeMap<u8, String> =
+        bincode::serde::decode_from_slice(&encoded, config)
+            .unwrap()
+            .0;
+    assert_eq!(decoded.get(&1).map(String::as_str), Some("a"));
+}
+
+#[test]
+fn test_canonical_nan() {
+    let config = config::standard().with_canonical_encoding();
+    let other_nan = f32::from_bits(0xFFC0_0001);
+    assert!(other_nan.is_nan());
+
+    assert_eq!(
+        bincode::encode_to_vec(other_nan, config::standard()).unwrap(),
+        [0x01, 0x00, 0xC0, 0xFF]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(other_nan, config).unwrap(),
+        [0x00, 0x00, 0xC0, 0x7F]
+    );
+    assert_eq!(
+        bincode::encode_to_vec(-f64::NAN, config.with_big_endian()).unwrap(),
+        [0x7F, 0xF8, 0, 0, 0, 0, 0, 0]
+    );
+    // Other values are not changed
+    assert_eq!(
+        bincode::encode_to_vec(-0.0f32, config).unwrap(),
+        bincode::encode_to_vec(-0.0f32, config::standard()).unwrap()
+    );
+
+    let mut dynamic = DynamicConfig::standard();
+    dynamic.canonical = true;
+    assert_eq!(
+        bincode::encode_to_vec(other_nan, dynamic).unwrap(),
+        [0x00, 0x00, 0xC0, 0x7F]
+    );
+    assert_eq!(DynamicConfig::from(config), dynamic);
+}
+
+#[test]
+fn test_strict_decoding() {
+    use bincode::error::IntegerType;
+    use std::collections::BTreeSet;
+
+    let config = config::standard().with_strict_decoding();
+
+    // 5 as a varint with a u16 marker
+    let non_canonical = [251, 5, 0];
+    let decoded: u32 = bincode::decode_from_slice(&non_canonical, config::standard())
+        .unwrap()
+        .0;
+    assert_eq!(decoded, 5);
+    let result: Result<(u32, usize), _> = bincode::decode_from_slice(&non_canonical, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::NonCanonicalInteger {
+            integer_type: IntegerType::U32
+        })
+    ));
+    let result: Result<(i32, usize), _> = bincode::decode_from_slice(&non_canonical, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::NonCanonicalInteger {
+            integer_type: IntegerType::I32
+        })
+    ));
+    // Lengths are checked as well
+    let result: Result<(Vec<u8>, usize), _> = bincode::decode_from_slice(&[251, 1, 0, 0], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::NonCanonicalInteger {
+            integer_type: IntegerType::U64
+        })
+    ));
+
+    // NaN values must be canonical
+    let encoded = bincode::encode_to_vec(f32::from_bits(0xFFC0_0001), config::standard()).unwrap();
+    let result: Result<(f32, usize), _> = bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(result, Err(DecodeError::NonCanonicalNaN)));
+    let encoded = bincode::encode_to_vec(f64::NAN, config.with_canonical_encoding()).unwrap();
+    let decoded: f64 = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert!(decoded.is_nan());
+
+    // Sets and maps must be sorted, and may not contain duplicates
+    let result: Result<(BTreeSet<u8>, usize), _> =
+        bincode::decode_from_slice(&[3, 1, 3, 2], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnsortedKeys { index: 2 })
+    ));
+    let result: Result<(BTreeSet<u8>, usize), _> = bincode::decode_from_slice(&[2, 1, 1], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnsortedKeys { index: 1 })
+    ));
+    let decoded: BTreeSet<u8> = bincode::decode_from_slice(&[3, 1, 2, 3], config).unwrap().0;
+    assert_eq!(decoded.len(), 3);
+
+    // The nanoseconds of a duration must be less than a second
+    let encoded = bincode::encode_to_vec((1u64, 1_000_000_000u32), config::standard()).unwrap();
+    let result: Result<(std::time::Duration, usize), _> =
+        bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(result, Err(DecodeError::InvalidDuration { .. })));
+
+    let mut dynamic = DynamicConfig::standard();
+    dynamic.strict_decoding = true;
+    assert_eq!(DynamicConfig::from(config), dynamic);
+    let result: Result<(u32, usize), _> = bincode::decode_from_slice(&non_canonical, dynamic);
+    assert!(matches!(
+        result,
+        Err(DecodeError::NonCanonicalInteger { .. })
+    ));
+}
+
+#[test]
+fn test_depth_limit() {
+    let value = vec![vec![vec![1u8, 2], vec![3]], vec![]];
+    let encoded = bincode::encode_to_vec(&value, config::standard()).unwrap();
+
+    let config = config::standard().with_depth_limit::<3>();
+    let decoded: Vec<Vec<Vec<u8>>> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, value);
+
+    let config = config::standard().with_depth_limit::<2>();
+    let result: Result<(Vec<Vec<Vec<u8>>>, usize), _> =
+        bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 2 })
+    ));
+
+    // Values that are not nested are not affected
+    let config = config::standard().with_depth_limit::<0>();
+    let decoded: (u32, Option<char>) = bincode::decode_from_slice(&[5, 1, b'a'], config).unwrap().0;
+    assert_eq!(decoded, (5, Some('a')));
+    let result: Result<(Box<u32>, usize), _> = bincode::decode_from_slice(&[5], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 0 })
+    ));
+
+    let mut dynamic = DynamicConfig::standard();
+    dynamic.depth_limit = Some(2);
+    assert_eq!(
+        DynamicConfig::from(config::standard().with_depth_limit::<2>()),
+        dynamic
+    );
+    let result: Result<(Vec<Vec<Vec<u8>>>, usize), _> =
+        bincode::decode_from_slice(&encoded, dynamic);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 2 })
+    ));
+    dynamic.depth_limit = None;
+    let decoded: Vec<Vec<Vec<u8>>> = bincode::decode_from_slice(&encoded, dynamic).unwrap().0;
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_max_collection_len() {
+    use std::collections::{BTreeMap, VecDeque};
+
+    let config = config::standard().with_max_collection_len::<3>();
+
+    let encoded = bincode::encode_to_vec(vec![1u16, 2, 3], config).unwrap();
+    let decoded: Vec<u16> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, [1, 2, 3]);
+
+    let encoded = bincode::encode_to_vec(vec![1u16, 2, 3, 4], config).unwrap();
+    macro_rules! check {
+        ($ty:ty) => {
+            let result: Result<($ty, usize), _> = bincode::decode_from_slice(&encoded, config);
+            assert!(matches!(
+                result,
+                Err(DecodeError::CollectionTooLong { len: 4, max: 3 })
+            ));
+        };
+    }
+    check!(Vec<u16>);
+    check!(VecDeque<u16>);
+    #[cfg(feature = "std")]
+    check!(std::collections::HashSet<u16>);
+    check!(Box<[u16]>);
+
+    let map: BTreeMap<u8, u8> = (0..4).map(|i| (i, i)).collect();
+    let encoded = bincode::encode_to_vec(&map, config).unwrap();
+    let result: Result<(BTreeMap<u8, u8>, usize), _> = bincode::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 4, max: 3 })
+    ));
+
+    // Strings and byte vecs are not collections
+    let encoded = bincode::encode_to_vec("Hello", config).unwrap();
+    let decoded: String = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, "Hello");
+    let decoded: Vec<u8> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, b"Hello");
+
+    let mut dynamic = DynamicConfig::standard();
+    dynamic.max_collection_len = Some(3);
+    assert_eq!(DynamicConfig::from(config), dynamic);
+    let encoded = bincode::encode_to_vec(vec![1u16, 2, 3, 4], dynamic).unwrap();
+    let result: Result<(Vec<u16>, usize), _> = bincode::decode_from_slice(&encoded, dynamic);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 4, max: 3 })
+    ));
+}
+
+#[test]
+fn test_max_string_len() {
+    let config = config::standard().with_max_string_len::<4>();
+
+    let encoded = bincode::encode_to_vec("Hell", config).unwrap();
+    let decoded: String = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, "Hell");
+
+    let encoded = bincode::encode_to_vec("Hello", config).unwrap();
+    macro_rules! check {
+        ($ty:ty) => {
+            let result: Result<($ty, usize), _> =
+                bincode::borrow_decode_from_slice(&encoded, config);
+            assert!(matches!(
+                result,
+                Err(DecodeError::StringTooLong { len: 5, max: 4 })
+            ));
+        };
+    }
+    check!(String);
+    check!(&str);
+    check!(&[u8]);
+    check!(Box<str>);
+    #[cfg(feature = "std")]
+    check!(std::ffi::CString);
+    #[cfg(feature = "std")]
+    check!(std::path::PathBuf);
+    // Byte vecs have the same encoding as `&[u8]`, so they are limited in the same way
+    check!(Vec<u8>);
+    check!(Box<[u8]>);
+    check!(std::collections::VecDeque<u8>);
+
+    let mut dynamic = DynamicConfig::standard();
+    dynamic.max_string_len = Some(4);
+    assert_eq!(DynamicConfig::from(config), dynamic);
+    let result: Result<(String, usize), _> = bincode::decode_from_slice(&encoded, dynamic);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 5, max: 4 })
+    ));
+}
@@ -444,3 +444,550 @@ mod zoxide {
         assert!(matches!(decoded[1].path, Cow::Borrowed("Bar")));
     }
 }
+
+#[test]
+fn test_enum_discriminant_encoding() {
+    let start = TestEnum::Baz(1, 2, 3);
+    let mut slice = [0u8; 1024];
+
+    let config = bincode::config::legacy();
+    let bytes_written = bincode::encode_into_slice(&start, &mut slice, config).unwrap();
+    assert_eq!(
+        &slice[..bytes_written],
+        &[2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]
+    );
+
+    let config = bincode::config::legacy().with_u8_discriminants();
+    let bytes_written = bincode::encode_into_slice(&start, &mut slice, config).unwrap();
+    assert_eq!(
+        &slice[..bytes_written],
+        &[2, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]
+    );
+    let (result, len): (TestEnum, usize) =
+        bincode::decode_from_slice(&slice[..bytes_written], config).unwrap();
+    assert_eq!(result, start);
+    assert_eq!(len, bytes_written);
+
+    let config = bincode::config::legacy().with_varint_discriminants();
+    let bytes_written = bincode::encode_into_slice(&start, &mut slice, config).unwrap();
+    assert_eq!(
+        &slice[..bytes_written],
+        &[2, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]
+    );
+    let (result, _): (TestEnum, usize) =
+        bincode::decode_from_slice(&slice[..bytes_written], config).unwrap();
+    assert_eq!(result, start);
+
+    // Out of range discriminants are still rejected
+    let result = bincode::decode_from_slice::<TestEnum, _>(
+        &[3],
+        bincode::config::legacy().with_u8_discriminants(),
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnexpectedVariant {
+            found: 3,
+            type_name: "TestEnum",
+            ..
+        })
+    ));
+}
+
+#[derive(bincode::Decode, bincode::Encode, PartialEq, Eq, Debug)]
+#[repr(u8)]
+enum ReprEnum {
+    A = 0x10,
+    B,
+    C = 200,
+}
+
+#[derive(bincode::Decode, bincode::Encode, PartialEq, Eq, Debug)]
+#[bincode(tag_type = "u16")]
+enum TagTypeEnum {
+    A,
+    B(u8),
+    C { value: u8 },
+}
+
+#[derive(bincode::Decode, bincode::Encode, bincode::MaxEncodedSize, PartialEq, Eq, Debug)]
+#[bincode(tag_type = "u16")]
+enum WideTagEnum {
+    A = 1,
+    B = 300,
+}
+
+#[test]
+fn test_enum_tag_type() {
+    // The tag type is used regardless of the discriminant encoding of the config
+    let config = bincode::config::legacy().with_varint_discriminants();
+
+    for (value, expected) in [(ReprEnum::A, 0x10), (ReprEnum::B, 0x11), (ReprEnum::C, 200)] {
+        let mut slice = [0u8; 10];
+        let bytes_written = bincode::encode_into_slice(&value, &mut slice, config).unwrap();
+        assert_eq!(&slice[..bytes_written], &[expected]);
+        let (decoded, len): (ReprEnum, usize) =
+            bincode::decode_from_slice(&slice[..bytes_written], config).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(len, 1);
+    }
+
+    let result = bincode::decode_from_slice::<ReprEnum, _>(&[0], config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnexpectedVariant {
+            found: 0,
+            type_name: "ReprEnum",
+            allowed: &bincode::error::AllowedEnumVariants::Allowed(&[0x10, 0x11, 200]),
+        })
+    ));
+
+    let mut slice = [0u8; 10];
+    let bytes_written = bincode::encode_into_slice(
+        TagTypeEnum::C { value: 5 },
+        &mut slice,
+        bincode::config::legacy().with_big_endian(),
+    )
+    .unwrap();
+    assert_eq!(&slice[..bytes_written], &[0, 2, 5]);
+    let (decoded, _): (TagTypeEnum, usize) = bincode::decode_from_slice(
+        &slice[..bytes_written],
+        bincode::config::legacy().with_big_endian(),
+    )
+    .unwrap();
+    assert_eq!(decoded, TagTypeEnum::C { value: 5 });
+
+    let result = bincode::decode_from_slice::<TagTypeEnum, _>(&[3, 0], bincode::config::legacy());
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnexpectedVariant {
+            found: 3,
+            type_name: "TagTypeEnum",
+            allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 2 },
+        })
+    ));
+
+    // The tag always takes up the size of the tag type, regardless of the int encoding of the config
+    let config = bincode::config::standard();
+    let mut encoded = [0u8; 8];
+    for (value, expected) in [(WideTagEnum::A, [1, 0]), (WideTagEnum::B, [44, 1])] {
+        let len = bincode::encode_into_slice(&value, &mut encoded, config).unwrap();
+        assert_eq!(encoded[..len], expected);
+        let (decoded, _): (WideTagEnum, usize) =
+            bincode::decode_from_slice(&encoded[..len], config).unwrap();
+        assert_eq!(decoded, value);
+    }
+    let len =
+        bincode::encode_into_slice(WideTagEnum::B, &mut encoded, config.with_big_endian()).unwrap();
+    assert_eq!(encoded[..len], [1, 44]);
+    assert_eq!(
+        <WideTagEnum as bincode::enc::MaxEncodedSize<bincode::config::Configuration>>::MAX_SIZE,
+        Some(2)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, PartialEq, Debug)]
+enum List {
+    Nil,
+    Cons(u8, Box<List>),
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, PartialEq, Debug)]
+struct ListWrapper {
+    list: List,
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_depth_limit() {
+    let value = ListWrapper {
+        list: List::Cons(1, Box::new(List::Cons(2, Box::new(List::Nil)))),
+    };
+    let encoded = bincode::encode_to_vec(&value, bincode::config::standard()).unwrap();
+
+    // The wrapper, 2 `Cons` with their `Box`, and `Nil` are nested 6 levels deep
+    let config = bincode::config::standard().with_depth_limit::<6>();
+    let decoded: ListWrapper = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, value);
+    let decoded: ListWrapper = bincode::borrow_decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, value);
+
+    let config = bincode::config::standard().with_depth_limit::<5>();
+    let result = bincode::decode_from_slice::<ListWrapper, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 5 })
+    ));
+    let result = bincode::borrow_decode_from_slice::<ListWrapper, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 5 })
+    ));
+
+    // A list that is nested deep enough to overflow the stack
+    let encoded = [1u8, 0].repeat(1_000_000);
+    let config = bincode::config::standard().with_depth_limit::<64>();
+    let result = bincode::decode_from_slice::<List, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 64 })
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, PartialEq, Debug)]
+struct MaxLenStruct {
+    #[bincode(max_len = 2)]
+    list: Vec<u8>,
+    #[bincode(max_len = 3)]
+    name: String,
+    #[bincode(max_len = 1_000)]
+    boxed: Box<[u32]>,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::BorrowDecode, PartialEq, Debug)]
+enum MaxLenEnum<'a> {
+    Map(#[bincode(max_len = 1)] std::collections::BTreeMap<u8, u8>),
+    Bytes {
+        #[bincode(max_len = 2)]
+        bytes: &'a [u8],
+    },
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_max_len() {
+    use bincode::error::EncodeError;
+
+    let config = bincode::config::standard();
+    let value = MaxLenStruct {
+        list: vec![1, 2],
+        name: String::from("abc"),
+        boxed: vec![1, 2, 3].into_boxed_slice(),
+    };
+    let encoded = bincode::encode_to_vec(&value, config).unwrap();
+    let decoded: MaxLenStruct = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, value);
+
+    // Encode the same layout without the attributes, so that the decoder sees the long fields
+    let encoded = bincode::encode_to_vec((vec![1u8, 2, 3], "abc", [1u32]), config).unwrap();
+    let result = bincode::decode_from_slice::<MaxLenStruct, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 3, max: 2 })
+    ));
+    let encoded = bincode::encode_to_vec((vec![1u8, 2], "abcd", [1u32]), config).unwrap();
+    let result = bincode::borrow_decode_from_slice::<MaxLenStruct, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 4, max: 3 })
+    ));
+
+    let value = MaxLenStruct {
+        list: vec![1, 2, 3],
+        name: String::new(),
+        boxed: Box::new([]),
+    };
+    let result = bincode::encode_to_vec(&value, config);
+    assert!(matches!(
+        result,
+        Err(EncodeError::StringTooLong { len: 3, max: 2 })
+    ));
+
+    let value = MaxLenEnum::Bytes { bytes: &[1, 2] };
+    let encoded = bincode::encode_to_vec(&value, config).unwrap();
+    let decoded: MaxLenEnum = bincode::borrow_decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, value);
+    let result = bincode::encode_to_vec(MaxLenEnum::Bytes { bytes: &[1, 2, 3] }, config);
+    assert!(matches!(
+        result,
+        Err(EncodeError::StringTooLong { len: 3, max: 2 })
+    ));
+    let encoded = bincode::encode_to_vec((1u32, &[1u8, 2, 3][..]), config).unwrap();
+    let result = bincode::borrow_decode_from_slice::<MaxLenEnum, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 3, max: 2 })
+    ));
+    let encoded = bincode::encode_to_vec((0u32, [(1u8, 1u8), (2, 2)]), config).unwrap();
+    let result = bincode::borrow_decode_from_slice::<MaxLenEnum, _>(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 2, max: 1 })
+    ));
+}
+
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+struct MaxSizeStruct<'a, T: Copy, const N: usize> {
+    a: u8,
+    b: [T; N],
+    c: Option<&'a u64>,
+}
+
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+struct MaxSizeTuple(u32, (i16, bool));
+
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+enum MaxSizeEnum<T> {
+    A,
+    B(T),
+    C { a: u64, b: u64 },
+}
+
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+#[repr(u8)]
+enum MaxSizeReprEnum {
+    A = 3,
+    B = 250,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+struct MaxSizeUnbounded {
+    a: u8,
+    b: String,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::MaxEncodedSize)]
+struct MaxSizeMaxLen<'a> {
+    #[bincode(max_len = 16)]
+    bytes: Vec<u8>,
+    #[bincode(max_len = 300)]
+    name: String,
+    #[bincode(max_len = 2)]
+    map: std::collections::BTreeMap<u16, u32>,
+    #[bincode(max_len = 3)]
+    slice: &'a [u64],
+}
+
+#[test]
+fn test_derive_max_encoded_size() {
+    use bincode::config;
+    use bincode::enc::MaxEncodedSize;
+
+    fn check<T, C>(value: T, config: C)
+    where
+        T: bincode::Encode + MaxEncodedSize<C>,
+        C: config::StaticConfig,
+    {
+        let size = bincode::encoded_size(&value, config).unwrap();
+        assert_eq!(Some(size), T::MAX_SIZE);
+    }
+
+    fn check_all<C: config::StaticConfig>(config: C) {
+        check(
+            MaxSizeStruct {
+                a: 1,
+                b: [u32::MAX; 3],
+                c: Some(&u64::MAX),
+            },
+            config,
+        );
+        check(MaxSizeTuple(u32::MAX, (i16::MIN, true)), config);
+        check(
+            MaxSizeEnum::C::<u8> {
+                a: u64::MAX,
+                b: u64::MAX,
+            },
+            config,
+        );
+        check(MaxSizeEnum::B([u64::MAX; 3]), config);
+        check(MaxSizeReprEnum::B, config);
+        #[cfg(feature = "alloc")]
+        check(
+            MaxSizeMaxLen {
+                bytes: vec![u8::MAX; 16],
+                name: "a".repeat(300),
+                map: [(u16::MAX - 1, u32::MAX), (u16::MAX, u32::MAX)]
+                    .into_iter()
+                    .collect(),
+                slice: &[u64::MAX; 3],
+            },
+            config,
+        );
+    }
+
+    // Unit variants only take up the size of their tag
+    let config = config::standard();
+    assert_eq!(
+        bincode::encoded_size(MaxSizeEnum::<u8>::A, config).unwrap(),
+        1
+    );
+    assert_eq!(
+        bincode::encoded_size(MaxSizeReprEnum::A, config).unwrap(),
+        1
+    );
+
+    check_all(config::standard());
+    check_all(config::legacy());
+    check_all(config::standard().with_u8_discriminants());
+    check_all(config::standard().skip_fixed_array_length());
+
+    assert_eq!(
+        <MaxSizeEnum<u8> as MaxEncodedSize>::MAX_SIZE,
+        Some(1 + 9 + 9)
+    );
+    #[cfg(feature = "alloc")]
+    assert_eq!(<MaxSizeUnbounded as MaxEncodedSize>::MAX_SIZE, None);
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        <MaxSizeMaxLen as MaxEncodedSize>::MAX_SIZE,
+        Some((1 + 16) + (3 + 300) + (1 + 2 * (3 + 5)) + (1 + 3 * 9))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, bincode::DecodeInPlace, PartialEq, Debug)]
+struct InPlaceStruct<T> {
+    name: String,
+    values: Vec<T>,
+    #[bincode(max_len = 4)]
+    short: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, bincode::DecodeInPlace, PartialEq, Debug)]
+struct InPlaceTuple(u8, Option<String>);
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, bincode::DecodeInPlace, PartialEq, Debug)]
+enum InPlaceEnum {
+    Empty,
+    Tuple(Vec<u32>, InPlaceTuple),
+    Named { name: String, list: Vec<String> },
+}
+
+#[cfg(feature = "std")]
+#[derive(bincode::Encode, bincode::Decode, bincode::DecodeInPlace, PartialEq, Debug)]
+struct InPlaceMessage<'a> {
+    marker: std::marker::PhantomData<u8>,
+    result: Result<u32, String>,
+    deque: std::collections::VecDeque<String>,
+    set: std::collections::HashSet<u32>,
+    tree: std::collections::BTreeSet<String>,
+    cow: std::borrow::Cow<'a, str>,
+    rc: std::rc::Rc<String>,
+    arc: std::sync::Arc<str>,
+    boxed: Box<[u8]>,
+    path: std::path::PathBuf,
+    addr: std::net::SocketAddr,
+    range: std::ops::Range<u32>,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(bincode::Encode, bincode::Decode, bincode::DecodeInPlace, PartialEq, Debug)]
+#[bincode(
+    bounds = "T: bincode::Encode",
+    decode_bounds = "T: bincode::de::DecodeInPlace",
+    borrow_decode_bounds = "T: bincode::BorrowDecode<'__de>"
+)]
+struct InPlaceBounds<T> {
+    value: T,
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_derive_decode_in_place() {
+    let config = bincode::config::standard();
+
+    let mut value = InPlaceStruct {
+        name: String::with_capacity(64),
+        values: Vec::with_capacity(64),
+        short: Vec::new(),
+    };
+    let pointers = (value.name.as_ptr(), value.values.as_ptr());
+    let expected = InPlaceStruct {
+        name: String::from("name"),
+        values: vec![1u64, 2, 3],
+        short: vec![4, 5],
+    };
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    let len = bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+    assert_eq!(len, encoded.len());
+    assert_eq!(value, expected);
+    assert_eq!((value.name.as_ptr(), value.values.as_ptr()), pointers);
+
+    let mut value = InPlaceEnum::Empty;
+    let expected = [
+        InPlaceEnum::Tuple(vec![1, 2], InPlaceTuple(3, Some(String::from("a")))),
+        InPlaceEnum::Tuple(vec![4], InPlaceTuple(5, None)),
+        InPlaceEnum::Named {
+            name: String::from("b"),
+            list: vec![String::from("c")],
+        },
+        InPlaceEnum::Named {
+            name: String::new(),
+            list: vec![String::from("d"), String::from("e")],
+        },
+        InPlaceEnum::Empty,
+    ];
+    for expected in expected {
+        let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+        bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    let encoded = bincode::encode_to_vec(5u32, config).unwrap();
+    let result = bincode::decode_from_slice_into(&mut value, &encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnexpectedVariant { found: 5, .. })
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_derive_decode_in_place_collections() {
+    use std::collections::VecDeque;
+
+    let config = bincode::config::standard();
+
+    let expected = InPlaceMessage {
+        marker: std::marker::PhantomData,
+        result: Err(String::from("error")),
+        deque: VecDeque::from(vec![String::from("a"), String::from("b")]),
+        set: [1, 2, 3].into_iter().collect(),
+        tree: [String::from("c")].into_iter().collect(),
+        cow: "cow".into(),
+        rc: std::rc::Rc::new(String::from("rc")),
+        arc: "arc".into(),
+        boxed: vec![4, 5].into_boxed_slice(),
+        path: "/tmp".into(),
+        addr: "127.0.0.1:80".parse().unwrap(),
+        range: 6..7,
+    };
+    let mut value = InPlaceMessage {
+        marker: std::marker::PhantomData,
+        result: Ok(0),
+        deque: (0..64).map(|_| String::with_capacity(64)).collect(),
+        set: std::collections::HashSet::with_capacity(64),
+        tree: Default::default(),
+        cow: "".into(),
+        rc: Default::default(),
+        arc: "".into(),
+        boxed: Box::new([]),
+        path: Default::default(),
+        addr: "[::1]:0".parse().unwrap(),
+        range: 0..0,
+    };
+    let deque_pointer = value.deque[0].as_ptr();
+    let set_capacity = value.set.capacity();
+
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+    assert_eq!(value, expected);
+    assert_eq!(value.deque[0].as_ptr(), deque_pointer);
+    assert_eq!(value.set.capacity(), set_capacity);
+
+    let encoded = bincode::encode_to_vec(InPlaceBounds { value: 5u32 }, config).unwrap();
+    let mut value = InPlaceBounds { value: 0u32 };
+    bincode::decode_from_slice_into(&mut value, &encoded, config).unwrap();
+    assert_eq!(value, InPlaceBounds { value: 5 });
+}
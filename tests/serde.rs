@@ -198,3 +198,240 @@ mod derive {
         );
     }
 }
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum SerdeEnum {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, u32),
+    Struct { a: u32 },
+}
+
+#[test]
+fn test_serde_discriminant_encoding() {
+    let config = bincode::config::legacy().with_u8_discriminants();
+
+    for (value, expected) in [
+        (SerdeEnum::Unit, &[0][..]),
+        (SerdeEnum::Newtype(5), &[1, 5, 0, 0, 0][..]),
+        (SerdeEnum::Tuple(5, 6), &[2, 5, 0, 0, 0, 6, 0, 0, 0][..]),
+        (SerdeEnum::Struct { a: 5 }, &[3, 5, 0, 0, 0][..]),
+    ] {
+        let encoded = bincode::serde::encode_to_vec(&value, config).unwrap();
+        assert_eq!(encoded, expected);
+        let (decoded, len): (SerdeEnum, usize) =
+            bincode::serde::decode_from_slice(&encoded, config).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(len, encoded.len());
+    }
+
+    let encoded = bincode::serde::encode_to_vec(
+        SerdeEnum::Newtype(5),
+        bincode::config::legacy().with_varint_discriminants(),
+    )
+    .unwrap();
+    assert_eq!(encoded, [1, 5, 0, 0, 0]);
+}
+
+#[test]
+fn test_serde_decode_exact() {
+    use bincode::error::DecodeError;
+
+    let config = bincode::config::standard();
+    let encoded = bincode::serde::encode_to_vec(("ab", 5u8), config).unwrap();
+
+    let (s, n): (String, u8) = bincode::serde::decode_from_slice_exact(&encoded, config).unwrap();
+    assert_eq!((s.as_str(), n), ("ab", 5));
+    let borrowed: (&str, u8) =
+        bincode::serde::decode_borrowed_from_slice_exact(&encoded, config).unwrap();
+    assert_eq!(borrowed, ("ab", 5));
+
+    let result: Result<String, _> = bincode::serde::decode_from_slice_exact(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::TrailingBytes { remaining: 1 })
+    ));
+    let result: Result<&str, _> =
+        bincode::serde::decode_borrowed_from_slice_exact(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::TrailingBytes { remaining: 1 })
+    ));
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum SerdeList {
+    Nil,
+    Cons(u8, Box<SerdeList>),
+}
+
+#[test]
+fn test_serde_depth_limit() {
+    use bincode::error::DecodeError;
+
+    let value = SerdeList::Cons(1, Box::new(SerdeList::Nil));
+    let encoded = bincode::serde::encode_to_vec(&value, bincode::config::standard()).unwrap();
+
+    // The enum and tuple of `Cons`, and `Nil`, are nested 3 levels deep
+    let config = bincode::config::standard().with_depth_limit::<3>();
+    let decoded: SerdeList = bincode::serde::decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, value);
+    let decoded: SerdeList = bincode::serde::decode_borrowed_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, value);
+
+    let config = bincode::config::standard().with_depth_limit::<2>();
+    let result: Result<(SerdeList, usize), _> = bincode::serde::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 2 })
+    ));
+    let result: Result<SerdeList, _> = bincode::serde::decode_borrowed_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 2 })
+    ));
+
+    let result: Result<(Vec<Vec<u8>>, usize), _> =
+        bincode::serde::decode_from_slice(&[1, 1, 5], config.with_depth_limit::<1>());
+    assert!(matches!(
+        result,
+        Err(DecodeError::DepthLimitExceeded { limit: 1 })
+    ));
+}
+
+#[test]
+fn test_serde_max_len() {
+    use bincode::error::DecodeError;
+    use std::collections::BTreeMap;
+
+    let config = bincode::config::standard()
+        .with_max_collection_len::<2>()
+        .with_max_string_len::<3>();
+
+    let encoded = bincode::serde::encode_to_vec(vec![1u8, 2, 3], config).unwrap();
+    let result: Result<(Vec<u8>, usize), _> = bincode::serde::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 3, max: 2 })
+    ));
+
+    let map: BTreeMap<u8, u8> = (0..3).map(|i| (i, i)).collect();
+    let encoded = bincode::serde::encode_to_vec(&map, config).unwrap();
+    let result: Result<(BTreeMap<u8, u8>, usize), _> =
+        bincode::serde::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::CollectionTooLong { len: 3, max: 2 })
+    ));
+
+    let encoded = bincode::serde::encode_to_vec("abcd", config).unwrap();
+    let result: Result<(String, usize), _> = bincode::serde::decode_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 4, max: 3 })
+    ));
+    let result: Result<&str, _> = bincode::serde::decode_borrowed_from_slice(&encoded, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::StringTooLong { len: 4, max: 3 })
+    ));
+
+    let encoded = bincode::serde::encode_to_vec("abc", config).unwrap();
+    let decoded: &str = bincode::serde::decode_borrowed_from_slice(&encoded, config).unwrap();
+    assert_eq!(decoded, "abc");
+}
+
+#[test]
+fn test_serde_bounded() {
+    use bincode::error::DecodeError;
+    use bincode::serde::Compat;
+    use bincode::{BoundedString, BoundedVec};
+
+    let config = bincode::config::standard();
+    let vec = BoundedVec::<u32, 2>::try_from(vec![1, 2]).unwrap();
+    let encoded = bincode::serde::encode_to_vec(&vec, config).unwrap();
+    assert_eq!(encoded, bincode::encode_to_vec(&vec, config).unwrap());
+    let decoded: BoundedVec<u32, 2> = bincode::serde::decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, vec);
+    let decoded: Compat<BoundedVec<u32, 2>> =
+        bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded.0, vec);
+    let result: Result<(BoundedVec<u32, 1>, usize), _> =
+        bincode::serde::decode_from_slice(&encoded, config);
+    assert!(result.is_err());
+
+    let string = BoundedString::<3>::try_from("abc").unwrap();
+    let encoded = bincode::serde::encode_to_vec(&string, config).unwrap();
+    let decoded: BoundedString<3> = bincode::serde::decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded, string);
+    let is_too_long = |error: DecodeError| match error {
+        DecodeError::OtherString(message) => {
+            message == "invalid length 3, expected a string of at most 2 bytes"
+        }
+        _ => false,
+    };
+    let result: Result<(BoundedString<2>, usize), _> =
+        bincode::serde::decode_from_slice(&encoded, config);
+    assert!(is_too_long(result.unwrap_err()));
+    let result: Result<BoundedString<2>, _> =
+        bincode::serde::decode_borrowed_from_slice(&encoded, config);
+    assert!(is_too_long(result.unwrap_err()));
+    // Without a buffer the string is read into memory before it is checked
+    let result: Result<BoundedString<2>, _> =
+        bincode::serde::decode_from_std_read(&mut &encoded[..], config);
+    assert!(is_too_long(result.unwrap_err()));
+    let reader = std::io::BufReader::new(&encoded[..]);
+    let result: Result<BoundedString<2>, _> = bincode::serde::decode_from_reader(reader, config);
+    assert!(is_too_long(result.unwrap_err()));
+    let decoded: BoundedString<3> =
+        bincode::serde::decode_from_std_read(&mut &encoded[..], config).unwrap();
+    assert_eq!(decoded, string);
+    let reader = std::io::BufReader::new(&encoded[..]);
+    let decoded: BoundedString<3> = bincode::serde::decode_from_reader(reader, config).unwrap();
+    assert_eq!(decoded, string);
+}
+
+#[test]
+fn test_serde_encoded_size() {
+    let value = SerdeWithBorrowedData {
+        a: 5,
+        b: 0,
+        str: "Hello world",
+    };
+    let config = bincode::config::standard();
+    let encoded = bincode::serde::encode_to_vec(&value, config).unwrap();
+    assert_eq!(encoded.len(), encoded.capacity());
+    assert_eq!(
+        bincode::serde::encoded_size(&value, config).unwrap(),
+        encoded.len()
+    );
+}
+
+#[test]
+fn test_serde_encode_append_to_vec() {
+    let value = SerdeWithBorrowedData {
+        a: 5,
+        b: 0,
+        str: "Hello world",
+    };
+    let config = bincode::config::standard();
+    let mut buffer = vec![1, 2, 3];
+    let written = bincode::serde::encode_append_to_vec(&value, &mut buffer, config).unwrap();
+    assert_eq!(&buffer[..3], &[1, 2, 3]);
+    assert_eq!(
+        &buffer[3..],
+        bincode::serde::encode_to_vec(&value, config).unwrap()
+    );
+    assert_eq!(written, buffer.len() - 3);
+
+    let result =
+        bincode::serde::encode_append_to_vec(&value, &mut buffer, config.skip_fixed_array_length());
+    assert!(result.is_err());
+    assert_eq!(buffer.len(), written + 3);
+}
@@ -162,6 +162,124 @@ fn test_system_time_out_of_range() {
     }
 }
 
+#[test]
+fn test_canonical_hash_collections() {
+    use std::collections::{HashMap, HashSet};
+
+    let config = bincode::config::standard().with_canonical_encoding();
+
+    let map: HashMap<String, u32> = (0..50).map(|i| (i.to_string(), i)).collect();
+    let mut reversed: HashMap<String, u32> = HashMap::with_capacity(100);
+    reversed.extend((0..50).rev().map(|i| (i.to_string(), i)));
+    let encoded = bincode::encode_to_vec(&map, config).unwrap();
+    assert_eq!(encoded, bincode::encode_to_vec(&reversed, config).unwrap());
+
+    // Entries are sorted by the encoded key, including its length prefix
+    let map: HashMap<&str, u8> = [("10", 10), ("2", 2), ("11", 11)].into_iter().collect();
+    assert_eq!(
+        bincode::encode_to_vec(&map, config).unwrap(),
+        [3, 1, b'2', 2, 2, b'1', b'0', 10, 2, b'1', b'1', 11]
+    );
+
+    let set: HashSet<u32> = (0..50).collect();
+    let reversed: HashSet<u32> = (0..50).rev().collect();
+    let encoded = bincode::encode_to_vec(&set, config).unwrap();
+    assert_eq!(encoded, bincode::encode_to_vec(&reversed, config).unwrap());
+    let decoded: HashSet<u32> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, set);
+}
+
+#[test]
+fn test_strict_hash_collections() {
+    use std::collections::{HashMap, HashSet};
+
+    let config = bincode::config::standard()
+        .with_canonical_encoding()
+        .with_strict_decoding();
+
+    let map: HashMap<String, u32> = (0..50).map(|i| (i.to_string(), i)).collect();
+    let encoded = bincode::encode_to_vec(&map, config).unwrap();
+    let decoded: HashMap<String, u32> = bincode::decode_from_slice(&encoded, config).unwrap().0;
+    assert_eq!(decoded, map);
+    let decoded: HashMap<&str, u32> = bincode::borrow_decode_from_slice(&encoded, config)
+        .unwrap()
+        .0;
+    assert_eq!(decoded.len(), 50);
+
+    // "2" is encoded as [1, b'2'], which is smaller than [2, b'1', b'0']
+    let unsorted = [2, 2, b'1', b'0', 10, 1, b'2', 2];
+    let decoded: HashMap<String, u8> =
+        bincode::decode_from_slice(&unsorted, bincode::config::standard())
+            .unwrap()
+            .0;
+    assert_eq!(decoded.len(), 2);
+    let result: Result<(HashMap<String, u8>, usize), _> =
+        bincode::decode_from_slice(&unsorted, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnsortedKeys { index: 1 })
+    ));
+    let result: Result<(HashMap<&str, u8>, usize), _> =
+        bincode::borrow_decode_from_slice(&unsorted, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnsortedKeys { index: 1 })
+    ));
+
+    let duplicate = [2, 7, 7];
+    let result: Result<(HashSet<u32>, usize), _> = bincode::decode_from_slice(&duplicate, config);
+    assert!(matches!(
+        result,
+        Err(DecodeError::UnsortedKeys { index: 1 })
+    ));
+    let decoded: HashSet<u32> = bincode::decode_from_slice(&[2, 7, 8], config).unwrap().0;
+    assert_eq!(decoded.len(), 2);
+}
+
+#[test]
+fn test_hash_collections_duplicate_key_policy() {
+    use std::collections::{HashMap, HashSet};
+
+    // A map with 3 entries, of which the first and last have key "a"
+    let encoded = [3, 1, b'a', 10, 1, b'b', 20, 1, b'a', 30];
+
+    let decoded: HashMap<String, u8> =
+        bincode::decode_from_slice(&encoded, bincode::config::standard())
+            .unwrap()
+            .0;
+    assert_eq!(decoded["a"], 30);
+    let decoded: HashMap<&str, u8> = bincode::borrow_decode_from_slice(
+        &encoded,
+        bincode::config::standard().with_first_key_wins(),
+    )
+    .unwrap()
+    .0;
+    assert_eq!(decoded["a"], 10);
+    assert_eq!(decoded.len(), 2);
+    let result: Result<(HashMap<String, u8>, usize), _> = bincode::decode_from_slice(
+        &encoded,
+        bincode::config::standard().with_duplicate_key_error(),
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::DuplicateMapKey { index: 2 })
+    ));
+
+    let encoded = [3, 5, 6, 5];
+    let decoded: HashSet<u32> = bincode::decode_from_slice(&encoded, bincode::config::standard())
+        .unwrap()
+        .0;
+    assert_eq!(decoded.len(), 2);
+    let result: Result<(HashSet<u32>, usize), _> = bincode::decode_from_slice(
+        &encoded,
+        bincode::config::standard().with_duplicate_key_error(),
+    );
+    assert!(matches!(
+        result,
+        Err(DecodeError::DuplicateMapKey { index: 2 })
+    ));
+}
+
 /// Simple example of user-defined hasher to test encoding/decoding HashMap and HashSet with custom hash algorithms.
 #[derive(Copy, Clone, Default)]
 pub struct ExampleCustomHasher {
@@ -179,3 +297,55 @@ impl std::hash::Hasher for ExampleCustomHasher {
         self.hash
     }
 }
+
+#[test]
+fn test_std_read_large_bytes() {
+    // Larger than the amount of memory that is preallocated for readers that do not know their length
+    let bytes: Vec<u8> = (0..3 * 1024 * 1024 + 5).map(|i| i as u8).collect();
+    let encoded = bincode::encode_to_vec(&bytes, bincode::config::standard()).unwrap();
+
+    let mut cursor = std::io::Cursor::new(&encoded);
+    let decoded: Vec<u8> =
+        bincode::decode_from_std_read(&mut cursor, bincode::config::standard()).unwrap();
+    assert_eq!(decoded, bytes);
+
+    let mut cursor = std::io::Cursor::new(&encoded[..encoded.len() - 1]);
+    let result: Result<Vec<u8>, _> =
+        bincode::decode_from_std_read(&mut cursor, bincode::config::standard());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_std_read_varint_collections() {
+    let values: Vec<u64> = (0..100_000u64)
+        .map(|i| if i % 1000 == 0 { i * i } else { i % 200 })
+        .collect();
+    let encoded = bincode::encode_to_vec(&values, bincode::config::standard()).unwrap();
+
+    // A small buffer, so runs are split across refills of the buffer
+    let mut reader = std::io::BufReader::with_capacity(100, &encoded[..]);
+    let decoded: Vec<u64> =
+        bincode::decode_from_reader(&mut reader, bincode::config::standard()).unwrap();
+    assert_eq!(decoded, values);
+
+    let mut cursor = Cursor::new(&encoded);
+    let decoded: Vec<u64> =
+        bincode::decode_from_std_read(&mut cursor, bincode::config::standard()).unwrap();
+    assert_eq!(decoded, values);
+}
+
+#[test]
+fn test_decode_hash_map_in_place() {
+    use std::collections::HashMap;
+
+    let config = bincode::config::standard();
+    let mut map: HashMap<String, Vec<u8>> = HashMap::with_capacity(100);
+    map.insert(String::from("old"), vec![1, 2, 3]);
+    let capacity = map.capacity();
+
+    let expected: HashMap<String, Vec<u8>> = (0..20).map(|i| (i.to_string(), vec![i; 3])).collect();
+    let encoded = bincode::encode_to_vec(&expected, config).unwrap();
+    bincode::decode_from_slice_into(&mut map, &encoded, config).unwrap();
+    assert_eq!(map, expected);
+    assert_eq!(map.capacity(), capacity);
+}
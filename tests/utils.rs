@@ -1,6 +1,17 @@
 use core::fmt::Debug;
 
 fn the_same_with_config<V, C, CMP>(element: &V, config: C, cmp: CMP)
+where
+    V: TheSameTrait,
+    C: bincode::config::Config,
+    bincode::config::DynamicConfig: From<C>,
+    CMP: Fn(&V, &V) -> bool,
+{
+    the_same_with_any_config(element, config, &cmp);
+    the_same_with_any_config(element, bincode::config::DynamicConfig::from(config), &cmp);
+}
+
+fn the_same_with_any_config<V, C, CMP>(element: &V, config: C, cmp: CMP)
 where
     V: TheSameTrait,
     C: bincode::config::Config,
@@ -44,7 +55,7 @@ where
 
     let decoded = bincode::serde::decode_from_slice(&buffer, config);
 
-    if !C::SKIP_FIXED_ARRAY_LENGTH {
+    if !config.skips_fixed_array_length() {
         let len = len.unwrap();
         let (decoded, decoded_len): (V, usize) = decoded.unwrap();
         println!(
@@ -137,6 +148,55 @@ where
             .with_variable_int_encoding(),
         &cmp,
     );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_little_endian()
+            .with_fixed_int_encoding()
+            .with_u8_discriminants(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_int_encoding()
+            .with_varint_discriminants(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard().with_leb128_int_encoding(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_leb128_int_encoding()
+            .skip_fixed_array_length(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_u32_lengths(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_fixed_int_encoding()
+            .with_varint_lengths(),
+        &cmp,
+    );
+    the_same_with_config(
+        &element,
+        bincode::config::standard()
+            .with_canonical_encoding()
+            .with_strict_decoding(),
+        &cmp,
+    );
 }
 
 #[cfg(feature = "serde")]